@@ -0,0 +1,45 @@
+#![feature(test)]
+
+extern crate libsecp256k1_rs as secp256k1;
+extern crate rand;
+extern crate test;
+
+use rand::thread_rng;
+use secp256k1::ecmult::ECMULT_CONTEXT;
+use secp256k1::secp256k1::group::{Affine, Jacobian};
+use secp256k1::{PublicKey, SecretKey, SharedSecret};
+use test::Bencher;
+
+// Run with and without `--features endomorphism` to compare.
+
+#[bench]
+fn bench_ecmult(b: &mut Bencher) {
+    let a: Affine = PublicKey::from_secret_key(&SecretKey::random(&mut thread_rng())).into();
+    let na = SecretKey::random(&mut thread_rng()).into();
+    let ng = SecretKey::random(&mut thread_rng()).into();
+    let mut aj = Jacobian::default();
+    aj.set_ge(&a);
+    let mut r = Jacobian::default();
+    b.iter(|| {
+        ECMULT_CONTEXT.ecmult(&mut r, &aj, &na, &ng);
+    });
+}
+
+#[bench]
+fn bench_ecmult_const(b: &mut Bencher) {
+    let a: Affine = PublicKey::from_secret_key(&SecretKey::random(&mut thread_rng())).into();
+    let na = SecretKey::random(&mut thread_rng()).into();
+    let mut r = Jacobian::default();
+    b.iter(|| {
+        ECMULT_CONTEXT.ecmult_const(&mut r, &a, &na);
+    });
+}
+
+#[bench]
+fn bench_ecdh(b: &mut Bencher) {
+    let pubkey = PublicKey::from_secret_key(&SecretKey::random(&mut thread_rng()));
+    let seckey = SecretKey::random(&mut thread_rng());
+    b.iter(|| {
+        let _ = SharedSecret::new(&pubkey, &seckey).unwrap();
+    });
+}
@@ -0,0 +1,68 @@
+#![feature(test)]
+
+extern crate libsecp256k1_rs as secp256k1;
+extern crate rand;
+extern crate test;
+
+use rand::thread_rng;
+use secp256k1::schnorr::{self, Signature};
+use secp256k1::{PublicKey, SecretKey, XOnlyPublicKey};
+use test::Bencher;
+
+fn signatures(n: usize) -> Vec<(Signature, [u8; 32], XOnlyPublicKey)> {
+    (0..n)
+        .map(|i| {
+            let seckey = SecretKey::random(&mut thread_rng());
+            let msg = [i as u8; 32];
+            let signature = schnorr::sign(&msg, &seckey, &[0u8; 32]).unwrap();
+            (signature, msg, PublicKey::from_secret_key(&seckey).x_only().0)
+        })
+        .collect()
+}
+
+fn bench_verify(b: &mut Bencher, n: usize) {
+    let items = signatures(n);
+    b.iter(|| {
+        for item in items.iter() {
+            assert!(schnorr::verify(&item.0, &item.1, &item.2));
+        }
+    });
+}
+
+fn bench_verify_batch(b: &mut Bencher, n: usize) {
+    let items = signatures(n);
+    let batch: Vec<(&Signature, &[u8], &XOnlyPublicKey)> = items.iter().map(|i| (&i.0, &i.1[..], &i.2)).collect();
+    b.iter(|| {
+        assert!(schnorr::verify_batch(&batch));
+    });
+}
+
+#[bench]
+fn bench_schnorr_verify_1(b: &mut Bencher) {
+    bench_verify(b, 1);
+}
+
+#[bench]
+fn bench_schnorr_verify_batch_1(b: &mut Bencher) {
+    bench_verify_batch(b, 1);
+}
+
+#[bench]
+fn bench_schnorr_verify_64(b: &mut Bencher) {
+    bench_verify(b, 64);
+}
+
+#[bench]
+fn bench_schnorr_verify_batch_64(b: &mut Bencher) {
+    bench_verify_batch(b, 64);
+}
+
+#[bench]
+fn bench_schnorr_verify_1024(b: &mut Bencher) {
+    bench_verify(b, 1024);
+}
+
+#[bench]
+fn bench_schnorr_verify_batch_1024(b: &mut Bencher) {
+    bench_verify_batch(b, 1024);
+}
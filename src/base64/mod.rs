@@ -0,0 +1,141 @@
+//! Base64 encoding with the standard RFC 4648 alphabet and `=` padding,
+//! and the unpadded URL-safe variant used by JOSE.
+
+use secp256k1::Error;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode bytes as padded base64.
+pub fn encode(data: &[u8]) -> String {
+    encode_with(data, ALPHABET, true)
+}
+
+/// Decode padded base64. The input length must be a multiple of four and
+/// unused trailing bits must be zero.
+pub fn decode(s: &str) -> Result<Vec<u8>, Error> {
+    decode_with(s, ALPHABET, true)
+}
+
+/// Encode bytes as unpadded base64url (RFC 4648 section 5).
+pub fn encode_url(data: &[u8]) -> String {
+    encode_with(data, URL_ALPHABET, false)
+}
+
+/// Decode unpadded base64url. Padding characters are rejected and unused
+/// trailing bits must be zero.
+pub fn decode_url(s: &str) -> Result<Vec<u8>, Error> {
+    decode_with(s, URL_ALPHABET, false)
+}
+
+fn encode_with(data: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut ret = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                ret.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else if pad {
+                ret.push('=');
+            }
+        }
+    }
+    ret
+}
+
+fn decode_with(s: &str, alphabet: &[u8; 64], pad: bool) -> Result<Vec<u8>, Error> {
+    let s = s.as_bytes();
+    if (pad && s.len() & 3 != 0) || s.len() & 3 == 1 {
+        return Err(Error::InvalidBase64);
+    }
+
+    let chunks = s.len().div_ceil(4);
+    let mut ret = Vec::with_capacity(chunks * 3);
+    for (index, chunk) in s.chunks(4).enumerate() {
+        let last = index == chunks - 1;
+        let padding = if pad {
+            chunk.iter().rev().take_while(|c| **c == b'=').count()
+        } else {
+            4 - chunk.len()
+        };
+        if padding > 2 || (padding > 0 && !last) {
+            return Err(Error::InvalidBase64);
+        }
+
+        let mut n = 0u32;
+        for c in &chunk[..(4 - padding)] {
+            let v = alphabet.iter().position(|a| a == c).ok_or(Error::InvalidBase64)?;
+            n = n << 6 | v as u32;
+        }
+        n <<= 6 * padding as u32;
+        if n & ((1 << (8 * padding as u32)) - 1) != 0 {
+            return Err(Error::InvalidBase64);
+        }
+
+        let bytes = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        ret.extend_from_slice(&bytes[..(3 - padding)]);
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, decode_url, encode, encode_url};
+    use Error;
+
+    #[test]
+    fn test_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for &(plain, encoded) in vectors.iter() {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded).unwrap(), plain.as_bytes());
+        }
+
+        let all: Vec<u8> = (0..=255u8).collect();
+        assert_eq!(decode(&encode(&all)).unwrap(), all);
+        assert_eq!(encode(&[0xfb, 0xff]), "+/8=");
+    }
+
+    #[test]
+    fn test_invalid() {
+        for bad in &["Zg=", "Zg", "Z===", "Zh==", "Zm9=", "Zg==Zg==", "Zm9v!A==", "=Zm9"] {
+            assert_eq!(decode(bad), Err(Error::InvalidBase64), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_url_safe() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg"),
+            ("fo", "Zm8"),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg"),
+            ("fooba", "Zm9vYmE"),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for &(plain, encoded) in vectors.iter() {
+            assert_eq!(encode_url(plain.as_bytes()), encoded);
+            assert_eq!(decode_url(encoded).unwrap(), plain.as_bytes());
+        }
+        assert_eq!(encode_url(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_url("-_8").unwrap(), [0xfb, 0xff]);
+
+        for bad in &["Zg==", "Z", "Zm9vY", "Zh", "Zm9", "+/8", "Zm9v Zg"] {
+            assert_eq!(decode_url(bad), Err(Error::InvalidBase64), "{}", bad);
+        }
+    }
+}
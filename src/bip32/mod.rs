@@ -0,0 +1,751 @@
+//! BIP-32 hierarchical deterministic keys: master key generation from a
+//! seed, hardened and non-hardened child derivation, derivation paths and
+//! the Base58Check `xprv`/`xpub` serialization.
+
+use bitcoin::{base58, hash160, Network};
+use secp256k1::{Error, PublicKey, Scalar, SecretKey};
+use sha2::{Digest, Sha512};
+use std::fmt;
+
+const HARDENED_BIT: u32 = 0x8000_0000;
+
+const VERSION_MAINNET_PRIVATE: [u8; 4] = [0x04, 0x88, 0xad, 0xe4];
+const VERSION_MAINNET_PUBLIC: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+const VERSION_TESTNET_PRIVATE: [u8; 4] = [0x04, 0x35, 0x83, 0x94];
+const VERSION_TESTNET_PUBLIC: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+
+/// Compute HMAC-SHA512 of the concatenation of `data` under `key`.
+pub fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+    let mut block = [0u8; 128];
+    if key.len() > block.len() {
+        block[..64].copy_from_slice(&Sha512::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha512::default();
+    let ipad: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    inner.input(&ipad);
+    for d in data {
+        inner.input(d);
+    }
+
+    let mut outer = Sha512::default();
+    let opad: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    outer.input(&opad);
+    outer.input(&inner.result());
+
+    let mut ret = [0u8; 64];
+    ret.copy_from_slice(&outer.result());
+    ret
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// The index of a child key. Hardened children can only be derived from
+/// an extended private key.
+pub enum ChildNumber {
+    Normal(u32),
+    Hardened(u32),
+}
+
+impl ChildNumber {
+    /// A non-hardened child number. The index must be below 2^31.
+    pub fn normal(index: u32) -> Result<ChildNumber, Error> {
+        if index & HARDENED_BIT != 0 {
+            return Err(Error::InvalidDerivationPath);
+        }
+        Ok(ChildNumber::Normal(index))
+    }
+
+    /// A hardened child number. The index must be below 2^31.
+    pub fn hardened(index: u32) -> Result<ChildNumber, Error> {
+        if index & HARDENED_BIT != 0 {
+            return Err(Error::InvalidDerivationPath);
+        }
+        Ok(ChildNumber::Hardened(index))
+    }
+
+    /// The child number for a serialized index, where indices of 2^31 and
+    /// above are hardened.
+    pub fn from_index(index: u32) -> ChildNumber {
+        if index & HARDENED_BIT != 0 {
+            ChildNumber::Hardened(index ^ HARDENED_BIT)
+        } else {
+            ChildNumber::Normal(index)
+        }
+    }
+
+    /// The serialized index, with the top bit set for hardened children.
+    pub fn index(&self) -> u32 {
+        match *self {
+            ChildNumber::Normal(index) => index,
+            ChildNumber::Hardened(index) => index | HARDENED_BIT,
+        }
+    }
+
+    pub fn is_hardened(&self) -> bool {
+        match *self {
+            ChildNumber::Normal(_) => false,
+            ChildNumber::Hardened(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for ChildNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ChildNumber::Normal(index) => write!(f, "{}", index),
+            ChildNumber::Hardened(index) => write!(f, "{}'", index),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+/// A sequence of child numbers leading from a master key to a descendant.
+pub struct DerivationPath(pub Vec<ChildNumber>);
+
+impl DerivationPath {
+    /// Parse a path such as `m/44'/0'/0'/0/5`. Hardened steps may be marked
+    /// with `'`, `h` or `H`.
+    pub fn parse(s: &str) -> Result<DerivationPath, Error> {
+        let mut parts = s.split('/');
+        if parts.next() != Some("m") {
+            return Err(Error::InvalidDerivationPath);
+        }
+
+        let mut path = Vec::new();
+        for part in parts {
+            let (digits, hardened) = match part.strip_suffix(|c| c == '\'' || c == 'h' || c == 'H') {
+                Some(digits) => (digits, true),
+                None => (part, false),
+            };
+            if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+                return Err(Error::InvalidDerivationPath);
+            }
+            let index = digits.parse::<u32>().or(Err(Error::InvalidDerivationPath))?;
+            path.push(if hardened {
+                ChildNumber::hardened(index)?
+            } else {
+                ChildNumber::normal(index)?
+            });
+        }
+        Ok(DerivationPath(path))
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("m")?;
+        for child in &self.0 {
+            write!(f, "/{}", child)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Vec<ChildNumber>> for DerivationPath {
+    fn from(path: Vec<ChildNumber>) -> DerivationPath {
+        DerivationPath(path)
+    }
+}
+
+/// The fields shared by extended private and public keys.
+fn serialize_header(
+    version: [u8; 4],
+    depth: u8,
+    parent_fingerprint: &[u8; 4],
+    child_number: ChildNumber,
+    chain_code: &[u8; 32],
+) -> [u8; 78] {
+    let mut ret = [0u8; 78];
+    ret[0..4].copy_from_slice(&version);
+    ret[4] = depth;
+    ret[5..9].copy_from_slice(parent_fingerprint);
+    ret[9..13].copy_from_slice(&child_number.index().to_be_bytes());
+    ret[13..45].copy_from_slice(chain_code);
+    ret
+}
+
+/// Check the header of a serialized extended key and return its depth,
+/// parent fingerprint, child number and chain code.
+fn parse_header(p: &[u8; 78]) -> Result<(u8, [u8; 4], ChildNumber, [u8; 32]), Error> {
+    let depth = p[4];
+    let parent_fingerprint = *array_ref!(p, 5, 4);
+    let index = u32::from_be_bytes(*array_ref!(p, 9, 4));
+    if depth == 0 && (parent_fingerprint != [0u8; 4] || index != 0) {
+        return Err(Error::InvalidExtendedKey);
+    }
+    Ok((depth, parent_fingerprint, ChildNumber::from_index(index), *array_ref!(p, 13, 32)))
+}
+
+fn decode_base58(s: &str) -> Result<[u8; 78], Error> {
+    let data = base58::decode_check(s)?;
+    if data.len() != 78 {
+        return Err(Error::InvalidExtendedKey);
+    }
+    Ok(*array_ref!(data, 0, 78))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A BIP-32 extended private key.
+pub struct ExtendedPrivKey {
+    pub network: Network,
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: ChildNumber,
+    pub chain_code: [u8; 32],
+    pub secret_key: SecretKey,
+}
+
+impl ExtendedPrivKey {
+    /// Generate the master key from a seed, which should be between 16 and
+    /// 64 bytes long.
+    pub fn new_master(network: Network, seed: &[u8]) -> Result<ExtendedPrivKey, Error> {
+        let i = hmac_sha512(b"Bitcoin seed", &[seed]);
+        Ok(ExtendedPrivKey {
+            network,
+            depth: 0,
+            parent_fingerprint: [0u8; 4],
+            child_number: ChildNumber::Normal(0),
+            chain_code: *array_ref!(i, 32, 32),
+            secret_key: SecretKey::parse(array_ref!(i, 0, 32))?,
+        })
+    }
+
+    /// Derive a child key. Fails, with negligible probability, if the
+    /// derived key is invalid, in which case the next index should be used.
+    pub fn derive_child(&self, child_number: ChildNumber) -> Result<ExtendedPrivKey, Error> {
+        let index = child_number.index().to_be_bytes();
+        let i = if child_number.is_hardened() {
+            hmac_sha512(&self.chain_code, &[&[0u8], &self.secret_key.serialize(), &index])
+        } else {
+            let pubkey = PublicKey::from_secret_key(&self.secret_key).serialize_compressed();
+            hmac_sha512(&self.chain_code, &[&pubkey, &index])
+        };
+
+        let mut tweak = Scalar::default();
+        if tweak.set_b32(array_ref!(i, 0, 32)) {
+            return Err(Error::InvalidTweak);
+        }
+        let child = tweak + self.secret_key.0;
+        if child.is_zero() {
+            return Err(Error::InvalidTweak);
+        }
+
+        Ok(ExtendedPrivKey {
+            network: self.network,
+            depth: self.depth.checked_add(1).ok_or(Error::InvalidDerivationPath)?,
+            parent_fingerprint: self.fingerprint(),
+            child_number,
+            chain_code: *array_ref!(i, 32, 32),
+            secret_key: SecretKey(child),
+        })
+    }
+
+    /// Derive the descendant at the given path, relative to this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<ExtendedPrivKey, Error> {
+        let mut key = self.clone();
+        for child_number in &path.0 {
+            key = key.derive_child(*child_number)?;
+        }
+        Ok(key)
+    }
+
+    /// The matching extended public key.
+    pub fn to_extended_pub_key(&self) -> ExtendedPubKey {
+        ExtendedPubKey {
+            network: self.network,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+            chain_code: self.chain_code,
+            public_key: PublicKey::from_secret_key(&self.secret_key),
+        }
+    }
+
+    /// The key identifier, HASH160 of the compressed public key.
+    pub fn identifier(&self) -> [u8; 20] {
+        self.to_extended_pub_key().identifier()
+    }
+
+    /// The first four bytes of the key identifier.
+    pub fn fingerprint(&self) -> [u8; 4] {
+        self.to_extended_pub_key().fingerprint()
+    }
+
+    /// Parse the 78-byte serialization. Regtest keys use the testnet
+    /// versions, so a `Network::Regtest` key parses back as
+    /// `Network::Testnet`.
+    pub fn parse(p: &[u8; 78]) -> Result<ExtendedPrivKey, Error> {
+        let version = *array_ref!(p, 0, 4);
+        let network = if version == VERSION_MAINNET_PRIVATE {
+            Network::Bitcoin
+        } else if version == VERSION_TESTNET_PRIVATE {
+            Network::Testnet
+        } else {
+            return Err(Error::InvalidExtendedKey);
+        };
+        let (depth, parent_fingerprint, child_number, chain_code) = parse_header(p)?;
+        if p[45] != 0 {
+            return Err(Error::InvalidExtendedKey);
+        }
+
+        Ok(ExtendedPrivKey {
+            network,
+            depth,
+            parent_fingerprint,
+            child_number,
+            chain_code,
+            secret_key: SecretKey::parse(array_ref!(p, 46, 32))?,
+        })
+    }
+
+    /// Serialize to 78 bytes: version, depth, parent fingerprint, child
+    /// number, chain code and the secret key prefixed by a zero byte.
+    pub fn serialize(&self) -> [u8; 78] {
+        let version = match self.network {
+            Network::Bitcoin => VERSION_MAINNET_PRIVATE,
+            Network::Testnet | Network::Regtest => VERSION_TESTNET_PRIVATE,
+        };
+        let mut ret = serialize_header(
+            version,
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+        );
+        ret[46..78].copy_from_slice(&self.secret_key.serialize());
+        ret
+    }
+
+    /// Parse an `xprv` or `tprv` string. `tprv` keys are reported as
+    /// testnet keys, even if they were encoded from a regtest key.
+    pub fn from_base58(s: &str) -> Result<ExtendedPrivKey, Error> {
+        ExtendedPrivKey::parse(&decode_base58(s)?)
+    }
+
+    /// Encode as an `xprv` or `tprv` string.
+    pub fn to_base58(&self) -> String {
+        base58::encode_check(&self.serialize())
+    }
+}
+
+impl fmt::Display for ExtendedPrivKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_base58())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A BIP-32 extended public key.
+pub struct ExtendedPubKey {
+    pub network: Network,
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: ChildNumber,
+    pub chain_code: [u8; 32],
+    pub public_key: PublicKey,
+}
+
+impl ExtendedPubKey {
+    /// Derive a non-hardened child key, computing the child public key as
+    /// `parent + I_L.G`. Fails, with negligible probability, if `I_L` is
+    /// not below the group order or the child is the point at infinity.
+    pub fn derive_child(&self, child_number: ChildNumber) -> Result<ExtendedPubKey, Error> {
+        if child_number.is_hardened() {
+            return Err(Error::InvalidDerivationPath);
+        }
+        let index = child_number.index().to_be_bytes();
+        let i = hmac_sha512(&self.chain_code, &[&self.public_key.serialize_compressed(), &index]);
+
+        // Only I_L >= n and a child at infinity are invalid; I_L = 0 is not.
+        let mut child = self.public_key;
+        child.tweak_add_assign(array_ref!(i, 0, 32))?;
+
+        Ok(ExtendedPubKey {
+            network: self.network,
+            depth: self.depth.checked_add(1).ok_or(Error::InvalidDerivationPath)?,
+            parent_fingerprint: self.fingerprint(),
+            child_number,
+            chain_code: *array_ref!(i, 32, 32),
+            public_key: child,
+        })
+    }
+
+    /// Derive the descendant at the given path, which must not contain
+    /// hardened steps.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<ExtendedPubKey, Error> {
+        let mut key = self.clone();
+        for child_number in &path.0 {
+            key = key.derive_child(*child_number)?;
+        }
+        Ok(key)
+    }
+
+    /// The key identifier, HASH160 of the compressed public key.
+    pub fn identifier(&self) -> [u8; 20] {
+        hash160(&self.public_key.serialize_compressed())
+    }
+
+    /// The first four bytes of the key identifier.
+    pub fn fingerprint(&self) -> [u8; 4] {
+        let identifier = self.identifier();
+        *array_ref!(identifier, 0, 4)
+    }
+
+    /// Parse the 78-byte serialization. As for private keys, regtest keys
+    /// parse back as `Network::Testnet`.
+    pub fn parse(p: &[u8; 78]) -> Result<ExtendedPubKey, Error> {
+        let version = *array_ref!(p, 0, 4);
+        let network = if version == VERSION_MAINNET_PUBLIC {
+            Network::Bitcoin
+        } else if version == VERSION_TESTNET_PUBLIC {
+            Network::Testnet
+        } else {
+            return Err(Error::InvalidExtendedKey);
+        };
+        let (depth, parent_fingerprint, child_number, chain_code) = parse_header(p)?;
+
+        Ok(ExtendedPubKey {
+            network,
+            depth,
+            parent_fingerprint,
+            child_number,
+            chain_code,
+            public_key: PublicKey::parse_compressed(array_ref!(p, 45, 33))?,
+        })
+    }
+
+    /// Serialize to 78 bytes: version, depth, parent fingerprint, child
+    /// number, chain code and the compressed public key.
+    pub fn serialize(&self) -> [u8; 78] {
+        let version = match self.network {
+            Network::Bitcoin => VERSION_MAINNET_PUBLIC,
+            Network::Testnet | Network::Regtest => VERSION_TESTNET_PUBLIC,
+        };
+        let mut ret = serialize_header(
+            version,
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+        );
+        ret[45..78].copy_from_slice(&self.public_key.serialize_compressed());
+        ret
+    }
+
+    /// Parse an `xpub` or `tpub` string. `tpub` keys are reported as
+    /// testnet keys, even if they were encoded from a regtest key.
+    pub fn from_base58(s: &str) -> Result<ExtendedPubKey, Error> {
+        ExtendedPubKey::parse(&decode_base58(s)?)
+    }
+
+    /// Encode as an `xpub` or `tpub` string.
+    pub fn to_base58(&self) -> String {
+        base58::encode_check(&self.serialize())
+    }
+}
+
+impl fmt::Display for ExtendedPubKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_base58())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{base58, Network};
+    use hex;
+    use super::{hmac_sha512, ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey};
+    use Error;
+
+    #[test]
+    fn test_hmac_sha512() {
+        // RFC 4231 test cases 1, 2 and 6.
+        assert_eq!(
+            &hex::encode(&hmac_sha512(&[0x0b; 20], &[b"Hi There"])[..]),
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+             daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"
+        );
+        assert_eq!(
+            &hex::encode(&hmac_sha512(b"Jefe", &[b"what do ya ", b"want for nothing?"])[..]),
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+             9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+        );
+        assert_eq!(
+            &hex::encode(&hmac_sha512(&[0xaa; 131], &[b"Test Using Larger Than Block-Size Key - Hash Key First"])[..]),
+            "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+             6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598"
+        );
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        let path = DerivationPath::parse("m/44'/0h/0H/0/5").unwrap();
+        assert_eq!(
+            path.0,
+            vec![
+                ChildNumber::Hardened(44),
+                ChildNumber::Hardened(0),
+                ChildNumber::Hardened(0),
+                ChildNumber::Normal(0),
+                ChildNumber::Normal(5),
+            ]
+        );
+        assert_eq!(path.to_string(), "m/44'/0'/0'/0/5");
+        assert_eq!(DerivationPath::parse("m").unwrap(), DerivationPath::default());
+        assert_eq!(
+            DerivationPath::parse("m/2147483647'").unwrap().0,
+            vec![ChildNumber::Hardened(2147483647)]
+        );
+
+        for bad in &["", "44'/0'", "m/", "m//1", "m/2147483648", "m/-1", "m/+1", "m/1''", "m/x", "n/1"] {
+            assert_eq!(DerivationPath::parse(bad), Err(Error::InvalidDerivationPath), "{}", bad);
+        }
+
+        assert_eq!(ChildNumber::from_index(0x8000_0005), ChildNumber::Hardened(5));
+        assert_eq!(ChildNumber::Hardened(5).index(), 0x8000_0005);
+        assert_eq!(ChildNumber::normal(0x8000_0000), Err(Error::InvalidDerivationPath));
+        assert_eq!(ChildNumber::hardened(0x7fff_ffff), Ok(ChildNumber::Hardened(0x7fff_ffff)));
+    }
+
+    /// Derive `path` from the master key of `seed` step by step, checking
+    /// every `(xprv, xpub)` pair on the way, and public derivation wherever
+    /// it is possible.
+    fn check_vector(seed: &str, vectors: &[(&str, &str, &str)]) {
+        let master = ExtendedPrivKey::new_master(Network::Bitcoin, &hex::decode(seed).unwrap()).unwrap();
+        let mut parent: Option<ExtendedPrivKey> = None;
+
+        for &(path, xprv, xpub) in vectors {
+            let path = DerivationPath::parse(path).unwrap();
+            let sk = master.derive_path(&path).unwrap();
+            let pk = sk.to_extended_pub_key();
+            assert_eq!(sk.to_base58(), xprv);
+            assert_eq!(pk.to_base58(), xpub);
+            assert_eq!(ExtendedPrivKey::from_base58(xprv).unwrap(), sk);
+            assert_eq!(ExtendedPubKey::from_base58(xpub).unwrap(), pk);
+
+            if let Some(parent) = parent {
+                let child_number = *path.0.last().unwrap();
+                assert_eq!(parent.derive_child(child_number).unwrap(), sk);
+                let parent_pk = parent.to_extended_pub_key();
+                if child_number.is_hardened() {
+                    assert_eq!(parent_pk.derive_child(child_number), Err(Error::InvalidDerivationPath));
+                } else {
+                    assert_eq!(parent_pk.derive_child(child_number).unwrap(), pk);
+                }
+            }
+            parent = Some(sk);
+        }
+    }
+
+    #[test]
+    fn test_vector_1() {
+        check_vector(
+            "000102030405060708090a0b0c0d0e0f",
+            &[
+                (
+                    "m",
+                    "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+                    "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+                ),
+                (
+                    "m/0'",
+                    "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7",
+                    "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+                ),
+                (
+                    "m/0'/1",
+                    "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs",
+                    "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ",
+                ),
+                (
+                    "m/0'/1/2'",
+                    "xprv9z4pot5VBttmtdRTWfWQmoH1taj2axGVzFqSb8C9xaxKymcFzXBDptWmT7FwuEzG3ryjH4ktypQSAewRiNMjANTtpgP4mLTj34bhnZX7UiM",
+                    "xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5",
+                ),
+                (
+                    "m/0'/1/2'/2",
+                    "xprvA2JDeKCSNNZky6uBCviVfJSKyQ1mDYahRjijr5idH2WwLsEd4Hsb2Tyh8RfQMuPh7f7RtyzTtdrbdqqsunu5Mm3wDvUAKRHSC34sJ7in334",
+                    "xpub6FHa3pjLCk84BayeJxFW2SP4XRrFd1JYnxeLeU8EqN3vDfZmbqBqaGJAyiLjTAwm6ZLRQUMv1ZACTj37sR62cfN7fe5JnJ7dh8zL4fiyLHV",
+                ),
+                (
+                    "m/0'/1/2'/2/1000000000",
+                    "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76",
+                    "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_vector_2() {
+        check_vector(
+            "fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542",
+            &[
+                (
+                    "m",
+                    "xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U",
+                    "xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB",
+                ),
+                (
+                    "m/0",
+                    "xprv9vHkqa6EV4sPZHYqZznhT2NPtPCjKuDKGY38FBWLvgaDx45zo9WQRUT3dKYnjwih2yJD9mkrocEZXo1ex8G81dwSM1fwqWpWkeS3v86pgKt",
+                    "xpub69H7F5d8KSRgmmdJg2KhpAK8SR3DjMwAdkxj3ZuxV27CprR9LgpeyGmXUbC6wb7ERfvrnKZjXoUmmDznezpbZb7ap6r1D3tgFxHmwMkQTPH",
+                ),
+                (
+                    "m/0/2147483647'",
+                    "xprv9wSp6B7kry3Vj9m1zSnLvN3xH8RdsPP1Mh7fAaR7aRLcQMKTR2vidYEeEg2mUCTAwCd6vnxVrcjfy2kRgVsFawNzmjuHc2YmYRmagcEPdU9",
+                    "xpub6ASAVgeehLbnwdqV6UKMHVzgqAG8Gr6riv3Fxxpj8ksbH9ebxaEyBLZ85ySDhKiLDBrQSARLq1uNRts8RuJiHjaDMBU4Zn9h8LZNnBC5y4a",
+                ),
+                (
+                    "m/0/2147483647'/1",
+                    "xprv9zFnWC6h2cLgpmSA46vutJzBcfJ8yaJGg8cX1e5StJh45BBciYTRXSd25UEPVuesF9yog62tGAQtHjXajPPdbRCHuWS6T8XA2ECKADdw4Ef",
+                    "xpub6DF8uhdarytz3FWdA8TvFSvvAh8dP3283MY7p2V4SeE2wyWmG5mg5EwVvmdMVCQcoNJxGoWaU9DCWh89LojfZ537wTfunKau47EL2dhHKon",
+                ),
+                (
+                    "m/0/2147483647'/1/2147483646'",
+                    "xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc",
+                    "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL",
+                ),
+                (
+                    "m/0/2147483647'/1/2147483646'/2",
+                    "xprvA2nrNbFZABcdryreWet9Ea4LvTJcGsqrMzxHx98MMrotbir7yrKCEXw7nadnHM8Dq38EGfSh6dqA9QWTyefMLEcBYJUuekgW4BYPJcr9E7j",
+                    "xpub6FnCn6nSzZAw5Tw7cgR9bi15UV96gLZhjDstkXXxvCLsUXBGXPdSnLFbdpq8p9HmGsApME5hQTZ3emM2rnY5agb9rXpVGyy3bdW6EEgAtqt",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_vector_3() {
+        // Retention of leading zeros in the secret key.
+        check_vector(
+            "4b381541583be4423346c643850da4b320e46a87ae3d2a4e6da11eba819cd4acba45d239319ac14f863b8d5ab5a0d0c64d2e8a1e7d1457df2e5a3c51c73235be",
+            &[
+                (
+                    "m",
+                    "xprv9s21ZrQH143K25QhxbucbDDuQ4naNntJRi4KUfWT7xo4EKsHt2QJDu7KXp1A3u7Bi1j8ph3EGsZ9Xvz9dGuVrtHHs7pXeTzjuxBrCmmhgC6",
+                    "xpub661MyMwAqRbcEZVB4dScxMAdx6d4nFc9nvyvH3v4gJL378CSRZiYmhRoP7mBy6gSPSCYk6SzXPTf3ND1cZAceL7SfJ1Z3GC8vBgp2epUt13",
+                ),
+                (
+                    "m/0'",
+                    "xprv9uPDJpEQgRQfDcW7BkF7eTya6RPxXeJCqCJGHuCJ4GiRVLzkTXBAJMu2qaMWPrS7AANYqdq6vcBcBUdJCVVFceUvJFjaPdGZ2y9WACViL4L",
+                    "xpub68NZiKmJWnxxS6aaHmn81bvJeTESw724CRDs6HbuccFQN9Ku14VQrADWgqbhhTHBaohPX4CjNLf9fq9MYo6oDaPPLPxSb7gwQN3ih19Zm4Y",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_vector_4() {
+        // Retention of leading zeros in the private key before hardened
+        // derivation.
+        check_vector(
+            "3ddd5602285899a946114506157c7997e5444528f3003f6134712147db19b678",
+            &[
+                (
+                    "m",
+                    "xprv9s21ZrQH143K48vGoLGRPxgo2JNkJ3J3fqkirQC2zVdk5Dgd5w14S7fRDyHH4dWNHUgkvsvNDCkvAwcSHNAQwhwgNMgZhLtQC63zxwhQmRv",
+                    "xpub661MyMwAqRbcGczjuMoRm6dXaLDEhW1u34gKenbeYqAix21mdUKJyuyu5F1rzYGVxyL6tmgBUAEPrEz92mBXjByMRiJdba9wpnN37RLLAXa",
+                ),
+                (
+                    "m/0'",
+                    "xprv9vB7xEWwNp9kh1wQRfCCQMnZUEG21LpbR9NPCNN1dwhiZkjjeGRnaALmPXCX7SgjFTiCTT6bXes17boXtjq3xLpcDjzEuGLQBM5ohqkao9G",
+                    "xpub69AUMk3qDBi3uW1sXgjCmVjJ2G6WQoYSnNHyzkmdCHEhSZ4tBok37xfFEqHd2AddP56Tqp4o56AePAgCjYdvpW2PU2jbUPFKsav5ut6Ch1m",
+                ),
+                (
+                    "m/0'/1'",
+                    "xprv9xJocDuwtYCMNAo3Zw76WENQeAS6WGXQ55RCy7tDJ8oALr4FWkuVoHJeHVAcAqiZLE7Je3vZJHxspZdFHfnBEjHqU5hG1Jaj32dVoS6XLT1",
+                    "xpub6BJA1jSqiukeaesWfxe6sNK9CCGaujFFSJLomWHprUL9DePQ4JDkM5d88n49sMGJxrhpjazuXYWdMf17C9T5XnxkopaeS7jGk1GyyVziaMt",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_vector_5() {
+        // Invalid extended keys, which must fail to parse as either kind.
+        let invalid = [
+            // Public key version with private key data.
+            "xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6LBpB85b3D2yc8sfvZU521AAwdZafEz7mnzBBsz4wKY5fTtTQBm",
+            // Private key version with public key data.
+            "xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGTQQD3dC4H2D5GBj7vWvSQaaBv5cxi9gafk7NF3pnBju6dwKvH",
+            // Invalid public key prefix 0x04.
+            "xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Txnt3siSujt9RCVYsx4qHZGc62TG4McvMGcAUjeuwZdduYEvFn",
+            // Invalid private key prefix 0x04.
+            "xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGpWnsj83BHtEy5Zt8CcDr1UiRXuWCmTQLxEK9vbz5gPstX92JQ",
+            // Invalid public key prefix 0x01.
+            "xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6N8ZMMXctdiCjxTNq964yKkwrkBJJwpzZS4HS2fxvyYUA4q2Xe4",
+            // Invalid private key prefix 0x01.
+            "xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD9y5gkZ6Eq3Rjuahrv17fEQ3Qen6J",
+            // Zero depth with a non-zero parent fingerprint.
+            "xprv9s2SPatNQ9Vc6GTbVMFPFo7jsaZySyzk7L8n2uqKXJen3KUmvQNTuLh3fhZMBoG3G4ZW1N2kZuHEPY53qmbZzCHshoQnNf4GvELZfqTUrcv",
+            // Zero depth with a non-zero parent fingerprint.
+            "xpub661no6RGEX3uJkY4bNnPcw4URcQTrSibUZ4NqJEw5eBkv7ovTwgiT91XX27VbEXGENhYRCf7hyEbWrR3FewATdCEebj6znwMfQkhRYHRLpJ",
+            // Zero depth with a non-zero index.
+            "xprv9s21ZrQH4r4TsiLvyLXqM9P7k1K3EYhA1kkD6xuquB5i39AU8KF42acDyL3qsDbU9NmZn6MsGSUYZEsuoePmjzsB3eFKSUEh3Gu1N3cqVUN",
+            // Zero depth with a non-zero index.
+            "xpub661MyMwAuDcm6CRQ5N4qiHKrJ39Xe1R1NyfouMKTTWcguwVcfrZJaNvhpebzGerh7gucBvzEQWRugZDuDXjNDRmXzSZe4c7mnTK97pTvGS8",
+            // Unknown extended key version.
+            "DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHGMQzT7ayAmfo4z3gY5KfbrZWZ6St24UVf2Qgo6oujFktLHdHY4",
+            // Unknown extended key version.
+            "DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHPmHJiEDXkTiJTVV9rHEBUem2mwVbbNfvT2MTcAqj3nesx8uBf9",
+            // Private key 0 is not in 1..n-1.
+            "xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzF93Y5wvzdUayhgkkFoicQZcP3y52uPPxFnfoLZB21Teqt1VvEHx",
+            // Private key n is not in 1..n-1.
+            "xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD5SDKr24z3aiUvKr9bJpdrcLg1y3G",
+            // Invalid public key 020000000000000000000000000000000000000000000000000000000000000007.
+            "xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Q5JXayek4PRsn35jii4veMimro1xefsM58PgBMrvdYre8QyULY",
+        ];
+        for key in invalid.iter() {
+            assert!(ExtendedPubKey::from_base58(key).is_err(), "{}", key);
+            assert!(ExtendedPrivKey::from_base58(key).is_err(), "{}", key);
+        }
+
+        // Invalid checksum.
+        assert_eq!(
+            ExtendedPrivKey::from_base58(
+                "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHL",
+            ),
+            Err(Error::InvalidChecksum)
+        );
+
+        // A valid checksum over a truncated serialization.
+        let private = ExtendedPrivKey::from_base58(
+            "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+        ).unwrap().serialize();
+        assert_eq!(
+            ExtendedPrivKey::from_base58(&base58::encode_check(&private[..77])),
+            Err(Error::InvalidExtendedKey)
+        );
+    }
+
+    #[test]
+    fn test_testnet_serialization() {
+        let master = ExtendedPrivKey::new_master(Network::Testnet, &hex::decode("000102030405060708090a0b0c0d0e0f").unwrap())
+            .unwrap();
+        let tprv = master.to_base58();
+        let tpub = master.to_extended_pub_key().to_base58();
+        assert!(tprv.starts_with("tprv"));
+        assert!(tpub.starts_with("tpub"));
+        assert_eq!(ExtendedPrivKey::from_base58(&tprv).unwrap(), master);
+        assert_eq!(ExtendedPubKey::from_base58(&tpub).unwrap().network, Network::Testnet);
+
+        // Regtest keys share the testnet versions, so the network does not
+        // survive a round trip.
+        let regtest = ExtendedPrivKey::new_master(Network::Regtest, &hex::decode("000102030405060708090a0b0c0d0e0f").unwrap())
+            .unwrap();
+        assert_eq!(regtest.to_base58(), tprv);
+        assert_eq!(regtest.to_extended_pub_key().to_base58(), tpub);
+        let parsed = ExtendedPrivKey::from_base58(&regtest.to_base58()).unwrap();
+        assert_eq!(parsed.network, Network::Testnet);
+        assert_eq!(parsed, master);
+        let parsed = ExtendedPubKey::from_base58(&regtest.to_extended_pub_key().to_base58()).unwrap();
+        assert_eq!(parsed.network, Network::Testnet);
+        assert_eq!(parsed, master.to_extended_pub_key());
+
+        assert_eq!(hex::encode(master.fingerprint()), "3442193e");
+        assert_eq!(hex::encode(master.identifier()), "3442193e1bb70916e914552172cd4e2dbc9df811");
+    }
+}
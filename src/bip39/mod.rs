@@ -0,0 +1,356 @@
+//! BIP-39 mnemonic seed phrases with the English wordlist.
+
+mod english;
+
+use bip32::hmac_sha512;
+use rand::Rng;
+use secp256k1::Error;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use self::english::WORDS;
+
+const PBKDF2_ROUNDS: u32 = 2048;
+
+/// PBKDF2 with HMAC-SHA512, producing a single 64-byte block.
+fn pbkdf2_hmac_sha512(password: &[u8], salt: &[u8], rounds: u32) -> [u8; 64] {
+    let mut u = hmac_sha512(password, &[salt, &1u32.to_be_bytes()]);
+    let mut ret = u;
+    for _ in 1..rounds {
+        u = hmac_sha512(password, &[&u]);
+        for (r, b) in ret.iter_mut().zip(u.iter()) {
+            *r ^= b;
+        }
+    }
+    ret
+}
+
+fn check_word_count(word_count: usize) -> Result<(), Error> {
+    match word_count {
+        12 | 15 | 18 | 21 | 24 => Ok(()),
+        _ => Err(Error::InvalidMnemonicLength),
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A mnemonic sentence encoding 128 to 256 bits of entropy.
+pub struct Mnemonic {
+    entropy: Vec<u8>,
+}
+
+impl Mnemonic {
+    /// Create the mnemonic for the given entropy, which must be 16, 20, 24,
+    /// 28 or 32 bytes long.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Mnemonic, Error> {
+        match entropy.len() {
+            16 | 20 | 24 | 28 | 32 => Ok(Mnemonic {
+                entropy: entropy.to_vec(),
+            }),
+            _ => Err(Error::InvalidEntropyLength),
+        }
+    }
+
+    /// Generate a random mnemonic with 12, 15, 18, 21 or 24 words.
+    pub fn random<R: Rng>(rng: &mut R, word_count: usize) -> Result<Mnemonic, Error> {
+        check_word_count(word_count)?;
+        let mut entropy = vec![0u8; word_count / 3 * 4];
+        rng.fill_bytes(&mut entropy);
+        Mnemonic::from_entropy(&entropy)
+    }
+
+    /// Parse a mnemonic sentence of whitespace separated English words.
+    /// Reports the position of the first unknown word, or a checksum error
+    /// if all words are known but do not form a valid mnemonic.
+    pub fn parse(s: &str) -> Result<Mnemonic, Error> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        check_word_count(words.len())?;
+
+        let mut bits = Vec::with_capacity(words.len() * 11);
+        for (position, word) in words.iter().enumerate() {
+            let index = WORDS
+                .binary_search(&&word.to_lowercase()[..])
+                .or(Err(Error::InvalidMnemonicWord(position)))?;
+            for i in (0..11).rev() {
+                bits.push((index >> i) & 1 == 1);
+            }
+        }
+
+        let checksum_len = words.len() / 3;
+        let entropy_len = bits.len() - checksum_len;
+        let mut entropy = vec![0u8; entropy_len / 8];
+        for (i, bit) in bits[..entropy_len].iter().enumerate() {
+            if *bit {
+                entropy[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+
+        let mnemonic = Mnemonic::from_entropy(&entropy)?;
+        if mnemonic.bits()[entropy_len..] != bits[entropy_len..] {
+            return Err(Error::InvalidChecksum);
+        }
+        Ok(mnemonic)
+    }
+
+    /// The entropy followed by the checksum, the first `entropy bits / 32`
+    /// bits of its SHA-256 hash.
+    fn bits(&self) -> Vec<bool> {
+        let hash = Sha256::digest(&self.entropy);
+        let checksum_len = self.entropy.len() / 4;
+        let mut bits = Vec::with_capacity(self.entropy.len() * 8 + checksum_len);
+        for byte in &self.entropy {
+            for i in (0..8).rev() {
+                bits.push((byte >> i) & 1 == 1);
+            }
+        }
+        for i in 0..checksum_len {
+            bits.push((hash[i / 8] >> (7 - i % 8)) & 1 == 1);
+        }
+        bits
+    }
+
+    pub fn entropy(&self) -> &[u8] {
+        &self.entropy
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.entropy.len() * 3 / 4
+    }
+
+    /// The words of the mnemonic sentence.
+    pub fn words(&self) -> Vec<&'static str> {
+        self.bits()
+            .chunks(11)
+            .map(|chunk| WORDS[chunk.iter().fold(0, |index, bit| index << 1 | *bit as usize)])
+            .collect()
+    }
+
+    /// Derive the 64-byte seed with PBKDF2-HMAC-SHA512 over 2048 rounds,
+    /// salted with `"mnemonic" || passphrase`. Non-ASCII passphrases must
+    /// be NFKD-normalized by the caller.
+    pub fn to_seed(&self, passphrase: &str) -> [u8; 64] {
+        let mut salt = b"mnemonic".to_vec();
+        salt.extend_from_slice(passphrase.as_bytes());
+        pbkdf2_hmac_sha512(self.to_string().as_bytes(), &salt, PBKDF2_ROUNDS)
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.words().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bip32::ExtendedPrivKey;
+    use bitcoin::Network;
+    use hex;
+    use rand::thread_rng;
+    use super::{pbkdf2_hmac_sha512, Mnemonic};
+    use Error;
+
+    #[test]
+    fn test_pbkdf2_hmac_sha512() {
+        assert_eq!(
+            &hex::encode(&pbkdf2_hmac_sha512(b"password", b"salt", 1)[..]),
+            "867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252\
+             c02d470a285a0501bad999bfe943c08f050235d7d68b1da55e63f73b60a57fce"
+        );
+        assert_eq!(
+            &hex::encode(&pbkdf2_hmac_sha512(b"password", b"salt", 2)[..]),
+            "e1d9c16aa681708a45f5c7c4e215ceb66e011a2e9f0040713f18aefdb866d53c\
+             f76cab2868a39b9f7840edce4fef5a82be67335c77a6068e04112754f27ccf4e"
+        );
+    }
+
+    #[test]
+    fn test_trezor_vectors() {
+        // (entropy, mnemonic, seed with passphrase "TREZOR")
+        let vectors = [
+            (
+                "00000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            ),
+            (
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+                "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+            ),
+            (
+                "80808080808080808080808080808080",
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+                "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8",
+            ),
+            (
+                "ffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+                "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+            ),
+            (
+                "000000000000000000000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon agent",
+                "035895f2f481b1b0f01fcf8c289c794660b289981a78f8106447707fdd9666ca06da5a9a565181599b79f53b844d8a71dd9f439c52a3d7b3e8a79c906ac845fa",
+            ),
+            (
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal will",
+                "f2b94508732bcbacbcc020faefecfc89feafa6649a5491b8c952cede496c214a0c7b3c392d168748f2d4a612bada0753b52a1c7ac53c1e93abd5c6320b9e95dd",
+            ),
+            (
+                "808080808080808080808080808080808080808080808080",
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter always",
+                "107d7c02a5aa6f38c58083ff74f04c607c2d2c0ecc55501dadd72d025b751bc27fe913ffb796f841c49b1d33b610cf0e91d3aa239027f5e99fe4ce9e5088cd65",
+            ),
+            (
+                "ffffffffffffffffffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo when",
+                "0cd6e5d827bb62eb8fc1e262254223817fd068a74b5b449cc2f667c3f1f985a76379b43348d952e2265b4cd129090758b3e3c2c49103b5051aac2eaeb890a528",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+                "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8",
+            ),
+            (
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth title",
+                "bc09fca1804f7e69da93c2f2028eb238c227f2e9dda30cd63699232578480a4021b146ad717fbb7e451ce9eb835f43620bf5c514db0f8add49f5d121449d3e87",
+            ),
+            (
+                "8080808080808080808080808080808080808080808080808080808080808080",
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic bless",
+                "c0c519bd0e91a2ed54357d9d1ebef6f5af218a153624cf4f2da911a0ed8f7a09e2ef61af0aca007096df430022f7a2b6fb91661a9589097069720d015e4e982f",
+            ),
+            (
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+                "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+            ),
+            (
+                "9e885d952ad362caeb4efe34a8e91bd2",
+                "ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic",
+                "274ddc525802f7c828d8ef7ddbcdc5304e87ac3535913611fbbfa986d0c9e5476c91689f9c8a54fd55bd38606aa6a8595ad213d4c9c9f9aca3fb217069a41028",
+            ),
+            (
+                "6610b25967cdcca9d59875f5cb50b0ea75433311869e930b",
+                "gravity machine north sort system female filter attitude volume fold club stay feature office ecology stable narrow fog",
+                "628c3827a8823298ee685db84f55caa34b5cc195a778e52d45f59bcf75aba68e4d7590e101dc414bc1bbd5737666fbbef35d1f1903953b66624f910feef245ac",
+            ),
+            (
+                "68a79eaca2324873eacc50cb9c6eca8cc68ea5d936f98787c60c7ebc74e6ce7c",
+                "hamster diagram private dutch cause delay private meat slide toddler razor book happy fancy gospel tennis maple dilemma loan word shrug inflict delay length",
+                "64c87cde7e12ecf6704ab95bb1408bef047c22db4cc7491c4271d170a1b213d20b385bc1588d9c7b38f1b39d415665b8a9030c9ec653d75e65f847d8fc1fc440",
+            ),
+            (
+                "c0ba5a8e914111210f2bd131f3d5e08d",
+                "scheme spot photo card baby mountain device kick cradle pact join borrow",
+                "ea725895aaae8d4c1cf682c1bfd2d358d52ed9f0f0591131b559e2724bb234fca05aa9c02c57407e04ee9dc3b454aa63fbff483a8b11de949624b9f1831a9612",
+            ),
+            (
+                "6d9be1ee6ebd27a258115aad99b7317b9c8d28b6d76431c3",
+                "horn tenant knee talent sponsor spell gate clip pulse soap slush warm silver nephew swap uncle crack brave",
+                "fd579828af3da1d32544ce4db5c73d53fc8acc4ddb1e3b251a31179cdb71e853c56d2fcb11aed39898ce6c34b10b5382772db8796e52837b54468aeb312cfc3d",
+            ),
+            (
+                "9f6a2878b2520799a44ef18bc7df394e7061a224d2c33cd015b157d746869863",
+                "panda eyebrow bullet gorilla call smoke muffin taste mesh discover soft ostrich alcohol speed nation flash devote level hobby quick inner drive ghost inside",
+                "72be8e052fc4919d2adf28d5306b5474b0069df35b02303de8c1729c9538dbb6fc2d731d5f832193cd9fb6aeecbc469594a70e3dd50811b5067f3b88b28c3e8d",
+            ),
+            (
+                "23db8160a31d3e0dca3688ed941adbf3",
+                "cat swing flag economy stadium alone churn speed unique patch report train",
+                "deb5f45449e615feff5640f2e49f933ff51895de3b4381832b3139941c57b59205a42480c52175b6efcffaa58a2503887c1e8b363a707256bdd2b587b46541f5",
+            ),
+            (
+                "8197a4a47f0425faeaa69deebc05ca29c0a5b5cc76ceacc0",
+                "light rule cinnamon wrap drastic word pride squirrel upgrade then income fatal apart sustain crack supply proud access",
+                "4cbdff1ca2db800fd61cae72a57475fdc6bab03e441fd63f96dabd1f183ef5b782925f00105f318309a7e9c3ea6967c7801e46c8a58082674c860a37b93eda02",
+            ),
+            (
+                "066dca1a2bb7e8a1db2832148ce9933eea0f3ac9548d793112d9a95c9407efad",
+                "all hour make first leader extend hole alien behind guard gospel lava path output census museum junior mass reopen famous sing advance salt reform",
+                "26e975ec644423f4a4c4f4215ef09b4bd7ef924e85d1d17c4cf3f136c2863cf6df0a475045652c57eb5fb41513ca2a2d67722b77e954b4b3fc11f7590449191d",
+            ),
+            (
+                "f30f8c1da665478f49b001d94c5fc452",
+                "vessel ladder alter error federal sibling chat ability sun glass valve picture",
+                "2aaa9242daafcee6aa9d7269f17d4efe271e1b9a529178d7dc139cd18747090bf9d60295d0ce74309a78852a9caadf0af48aae1c6253839624076224374bc63f",
+            ),
+            (
+                "c10ec20dc3cd9f652c7fac2f1230f7a3c828389a14392f05",
+                "scissors invite lock maple supreme raw rapid void congress muscle digital elegant little brisk hair mango congress clump",
+                "7b4a10be9d98e6cba265566db7f136718e1398c71cb581e1b2f464cac1ceedf4f3e274dc270003c670ad8d02c4558b2f8e39edea2775c9e232c7cb798b069e88",
+            ),
+            (
+                "f585c11aec520db57dd353c69554b21a89b20fb0650966fa0a9d6f74fd989d8f",
+                "void come effort suffer camp survey warrior heavy shoot primary clutch crush open amazing screen patrol group space point ten exist slush involve unfold",
+                "01f5bced59dec48e362f2c45b5de68b9fd6c92c6634f44d6d40aab69056506f0e35524a518034ddc1192e1dacd32c1ed3eaa3c3b131c88ed8e7e54c49a5d0998",
+            ),
+        ];
+        for &(entropy, phrase, seed) in vectors.iter() {
+            let entropy = hex::decode(entropy).unwrap();
+            let mnemonic = Mnemonic::from_entropy(&entropy).unwrap();
+            assert_eq!(mnemonic.to_string(), phrase);
+            assert_eq!(mnemonic.word_count(), phrase.split(' ').count());
+
+            let parsed = Mnemonic::parse(phrase).unwrap();
+            assert_eq!(parsed, mnemonic);
+            assert_eq!(parsed.entropy(), &entropy[..]);
+            assert_eq!(&hex::encode(&mnemonic.to_seed("TREZOR")[..]), seed);
+        }
+
+        let mnemonic = Mnemonic::parse("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").unwrap();
+        let master = ExtendedPrivKey::new_master(Network::Bitcoin, &mnemonic.to_seed("TREZOR")).unwrap();
+        assert_eq!(
+            master.to_base58(),
+            "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF"
+        );
+    }
+
+    #[test]
+    fn test_invalid_mnemonics() {
+        // The valid phrase is
+        // "letter advice cage absurd amount doctor acoustic avoid letter advice cage above".
+        assert_eq!(
+            Mnemonic::parse("getter advice cage absurd amount doctor acoustic avoid letter advice cage above"),
+            Err(Error::InvalidMnemonicWord(0))
+        );
+        assert_eq!(
+            Mnemonic::parse("letter advice cagex absurd amount doctor acoustic avoid letter advice cage above"),
+            Err(Error::InvalidMnemonicWord(2))
+        );
+        assert_eq!(
+            Mnemonic::parse("advice cage absurd amount doctor acoustic avoid letter advice cage above"),
+            Err(Error::InvalidMnemonicLength)
+        );
+        assert_eq!(
+            Mnemonic::parse("primary advice cage absurd amount doctor acoustic avoid letter advice cage above"),
+            Err(Error::InvalidChecksum)
+        );
+
+        // Extra whitespace and upper case are tolerated.
+        assert!(Mnemonic::parse("  Letter advice cage absurd amount doctor\tacoustic avoid letter advice cage ABOVE\n").is_ok());
+    }
+
+    #[test]
+    fn test_entropy_length() {
+        for len in &[0usize, 4, 15, 17, 36] {
+            assert_eq!(Mnemonic::from_entropy(&vec![0x42; *len]), Err(Error::InvalidEntropyLength));
+        }
+        for len in &[16usize, 20, 24, 28, 32] {
+            assert_eq!(Mnemonic::from_entropy(&vec![0x42; *len]).unwrap().word_count(), len * 3 / 4);
+        }
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = thread_rng();
+        for word_count in &[12usize, 15, 18, 21, 24] {
+            let mnemonic = Mnemonic::random(&mut rng, *word_count).unwrap();
+            assert_eq!(mnemonic.words().len(), *word_count);
+            assert_eq!(Mnemonic::parse(&mnemonic.to_string()).unwrap(), mnemonic);
+        }
+        assert_eq!(Mnemonic::random(&mut rng, 13), Err(Error::InvalidMnemonicLength));
+    }
+}
@@ -0,0 +1,321 @@
+//! Bitcoin addresses: Base58Check P2PKH and P2SH, and bech32/bech32m
+//! segwit addresses including P2WPKH and P2TR.
+
+use std::fmt;
+
+use super::{base58, bech32, hash160, taproot, Network};
+use secp256k1::{Error, PublicKey, XOnlyPublicKey};
+
+impl Network {
+    /// The human-readable part of segwit addresses.
+    pub fn hrp(&self) -> &'static str {
+        match *self {
+            Network::Bitcoin => "bc",
+            Network::Testnet => "tb",
+            Network::Regtest => "bcrt",
+        }
+    }
+
+    fn pubkey_hash_prefix(&self) -> u8 {
+        match *self {
+            Network::Bitcoin => 0x00,
+            Network::Testnet | Network::Regtest => 0x6f,
+        }
+    }
+
+    fn script_hash_prefix(&self) -> u8 {
+        match *self {
+            Network::Bitcoin => 0x05,
+            Network::Testnet | Network::Regtest => 0xc4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// What an address pays to.
+pub enum Payload {
+    /// The HASH160 of a public key.
+    PubkeyHash([u8; 20]),
+    /// The HASH160 of a redeem script.
+    ScriptHash([u8; 20]),
+    /// A segwit witness program of 2 to 40 bytes.
+    WitnessProgram { version: u8, program: Vec<u8> },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A Bitcoin address. Base58Check addresses do not distinguish testnet
+/// from regtest and parse as testnet.
+pub struct Address {
+    pub network: Network,
+    pub payload: Payload,
+}
+
+impl Address {
+    /// Pay to the compressed public key's hash.
+    pub fn p2pkh(pubkey: &PublicKey, network: Network) -> Address {
+        Address {
+            network,
+            payload: Payload::PubkeyHash(hash160(&pubkey.serialize_compressed())),
+        }
+    }
+
+    /// Pay to a P2WPKH script nested in P2SH.
+    pub fn p2sh_p2wpkh(pubkey: &PublicKey, network: Network) -> Address {
+        let mut script = vec![0x00, 0x14];
+        script.extend_from_slice(&hash160(&pubkey.serialize_compressed()));
+        Address {
+            network,
+            payload: Payload::ScriptHash(hash160(&script)),
+        }
+    }
+
+    /// Pay to the compressed public key's hash with a version 0 witness
+    /// program.
+    pub fn p2wpkh(pubkey: &PublicKey, network: Network) -> Address {
+        Address {
+            network,
+            payload: Payload::WitnessProgram {
+                version: 0,
+                program: hash160(&pubkey.serialize_compressed()).to_vec(),
+            },
+        }
+    }
+
+    /// Pay to the taproot output key obtained by tweaking the internal key
+    /// with the optional script tree merkle root, as in BIP-341.
+    pub fn p2tr(
+        internal_key: &XOnlyPublicKey,
+        merkle_root: Option<[u8; 32]>,
+        network: Network,
+    ) -> Result<Address, Error> {
+        let (output_key, _) = taproot::tweak_internal_key(internal_key, merkle_root)?;
+        Ok(Address::p2tr_tweaked(&output_key, network))
+    }
+
+    /// Pay to an already tweaked taproot output key.
+    pub fn p2tr_tweaked(output_key: &XOnlyPublicKey, network: Network) -> Address {
+        Address {
+            network,
+            payload: Payload::WitnessProgram {
+                version: 1,
+                program: output_key.serialize().to_vec(),
+            },
+        }
+    }
+
+    /// Parse a Base58Check or segwit address. Segwit addresses are
+    /// recognised by the `bc`, `tb` and `bcrt` human-readable parts, in
+    /// either case.
+    pub fn parse(s: &str) -> Result<Address, Error> {
+        let lower = s.to_lowercase();
+        for network in &[Network::Regtest, Network::Bitcoin, Network::Testnet] {
+            if lower.starts_with(&format!("{}1", network.hrp())) {
+                let (version, program) = bech32::decode_segwit(network.hrp(), s)?;
+                return Ok(Address {
+                    network: *network,
+                    payload: Payload::WitnessProgram { version, program },
+                });
+            }
+        }
+
+        let data = base58::decode_check(s)?;
+        if data.len() != 21 {
+            return Err(Error::InvalidAddress);
+        }
+        let hash = *array_ref!(data, 1, 20);
+        let (network, payload) = match data[0] {
+            0x00 => (Network::Bitcoin, Payload::PubkeyHash(hash)),
+            0x05 => (Network::Bitcoin, Payload::ScriptHash(hash)),
+            0x6f => (Network::Testnet, Payload::PubkeyHash(hash)),
+            0xc4 => (Network::Testnet, Payload::ScriptHash(hash)),
+            _ => return Err(Error::InvalidAddress),
+        };
+        Ok(Address { network, payload })
+    }
+
+    /// The witness version and program of a segwit address.
+    pub fn witness_program(&self) -> Option<(u8, &[u8])> {
+        match self.payload {
+            Payload::WitnessProgram { version, ref program } => Some((version, program)),
+            _ => None,
+        }
+    }
+
+    /// The output script paying to this address.
+    pub fn script_pubkey(&self) -> Vec<u8> {
+        match self.payload {
+            Payload::PubkeyHash(ref hash) => {
+                // OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG
+                let mut script = vec![0x76, 0xa9, 0x14];
+                script.extend_from_slice(hash);
+                script.extend_from_slice(&[0x88, 0xac]);
+                script
+            }
+            Payload::ScriptHash(ref hash) => {
+                // OP_HASH160 <hash> OP_EQUAL
+                let mut script = vec![0xa9, 0x14];
+                script.extend_from_slice(hash);
+                script.push(0x87);
+                script
+            }
+            Payload::WitnessProgram { version, ref program } => {
+                // OP_0 or OP_1 to OP_16, followed by a push of the program.
+                let mut script = vec![if version == 0 { 0x00 } else { 0x50 + version }];
+                script.push(program.len() as u8);
+                script.extend_from_slice(program);
+                script
+            }
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let encoded = match self.payload {
+            Payload::PubkeyHash(ref hash) | Payload::ScriptHash(ref hash) => {
+                let prefix = match self.payload {
+                    Payload::PubkeyHash(_) => self.network.pubkey_hash_prefix(),
+                    _ => self.network.script_hash_prefix(),
+                };
+                let mut data = vec![prefix];
+                data.extend_from_slice(hash);
+                base58::encode_check(&data)
+            }
+            Payload::WitnessProgram { version, ref program } => {
+                bech32::encode_segwit(self.network.hrp(), version, program).map_err(|_| fmt::Error)?
+            }
+        };
+        f.write_str(&encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bip32::{DerivationPath, ExtendedPrivKey};
+    use bip39::Mnemonic;
+    use bitcoin::Network;
+    use hex;
+    use super::{Address, Payload};
+    use {Error, PublicKey, SecretKey, XOnlyPublicKey};
+
+    #[test]
+    fn test_key_addresses() {
+        // Secret key 1.
+        let seckey = SecretKey::from_hex("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        let vectors = [
+            (Address::p2pkh(&pubkey, Network::Bitcoin), "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"),
+            (Address::p2pkh(&pubkey, Network::Testnet), "mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r"),
+            (Address::p2sh_p2wpkh(&pubkey, Network::Bitcoin), "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN"),
+            (Address::p2wpkh(&pubkey, Network::Bitcoin), "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+            (Address::p2wpkh(&pubkey, Network::Testnet), "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"),
+            (Address::p2wpkh(&pubkey, Network::Regtest), "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080"),
+        ];
+        for &(ref address, encoded) in vectors.iter() {
+            assert_eq!(&address.to_string(), encoded);
+            assert_eq!(&Address::parse(encoded).unwrap(), address);
+        }
+        assert_eq!(
+            hex::encode(Address::p2pkh(&pubkey, Network::Bitcoin).script_pubkey()),
+            "76a914751e76e8199196d454941c45d1b3a323f1433bd688ac"
+        );
+        assert_eq!(
+            hex::encode(Address::p2sh_p2wpkh(&pubkey, Network::Bitcoin).script_pubkey()),
+            "a914bcfeb728b584253d5f3f70bcb780e9ef218a68f487"
+        );
+
+        // Base58 regtest addresses are indistinguishable from testnet ones.
+        let regtest = Address::p2pkh(&pubkey, Network::Regtest);
+        assert_eq!(Address::parse(&regtest.to_string()).unwrap().network, Network::Testnet);
+    }
+
+    #[test]
+    fn test_p2tr() {
+        // BIP-341 wallet test vectors, scriptPubKey 0.
+        let internal_key =
+            XOnlyPublicKey::from_hex("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap();
+        let address = Address::p2tr(&internal_key, None, Network::Bitcoin).unwrap();
+        assert_eq!(
+            hex::encode(address.script_pubkey()),
+            "512053a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343"
+        );
+        assert_eq!(&address.to_string(), "bc1p2wsldez5mud2yam29q22wgfh9439spgduvct83k3pm50fcxa5dps59h4z5");
+    }
+
+    #[test]
+    fn test_bip86() {
+        let mnemonic = Mnemonic::parse(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        ).unwrap();
+        let root = ExtendedPrivKey::new_master(Network::Bitcoin, &mnemonic.to_seed("")).unwrap();
+        assert_eq!(
+            root.to_base58(),
+            "xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu"
+        );
+
+        let vectors = [
+            (
+                "m/86'/0'/0'/0/0",
+                "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            ),
+            (
+                "m/86'/0'/0'/0/1",
+                "83dfe85a3151d2517290da461fe2815591ef69f2b18a2ce63f01697a8b313145",
+                "bc1p4qhjn9zdvkux4e44uhx8tc55attvtyu358kutcqkudyccelu0was9fqzwh",
+            ),
+            (
+                "m/86'/0'/0'/1/0",
+                "399f1b2f4393f29a18c937859c5dd8a77350103157eb880f02e8c08214277cef",
+                "bc1p3qkhfews2uk44qtvauqyr2ttdsw7svhkl9nkm9s9c3x4ax5h60wqwruhk7",
+            ),
+        ];
+        for &(path, internal_key, encoded) in vectors.iter() {
+            let child = root.derive_path(&DerivationPath::parse(path).unwrap()).unwrap();
+            let (key, _) = PublicKey::from_secret_key(&child.secret_key).x_only();
+            assert_eq!(&key.to_hex(), internal_key);
+            let address = Address::p2tr(&key, None, Network::Bitcoin).unwrap();
+            assert_eq!(&address.to_string(), encoded);
+            assert_eq!(Address::parse(encoded).unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        // BIP-350
+        let address = Address::parse("BC1SW50QGDZ25J").unwrap();
+        assert_eq!(address.network, Network::Bitcoin);
+        assert_eq!(address.witness_program(), Some((16, &[0x75, 0x1e][..])));
+        assert_eq!(hex::encode(address.script_pubkey()), "6002751e");
+        assert_eq!(&address.to_string(), "bc1sw50qgdz25j");
+
+        let address = Address::parse("tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c").unwrap();
+        assert_eq!(address.network, Network::Testnet);
+        assert_eq!(
+            hex::encode(address.script_pubkey()),
+            "5120000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"
+        );
+
+        let address = Address::parse("3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN").unwrap();
+        assert_eq!(address.network, Network::Bitcoin);
+        let hash = hex::decode("bcfeb728b584253d5f3f70bcb780e9ef218a68f4").unwrap();
+        assert_eq!(address.payload, Payload::ScriptHash(*array_ref!(hash, 0, 20)));
+        assert_eq!(address.witness_program(), None);
+
+        // Bech32 checksum on a version 1 program.
+        assert_eq!(
+            Address::parse("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd"),
+            Err(Error::InvalidChecksum)
+        );
+        assert_eq!(
+            Address::parse("tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut"),
+            Err(Error::InvalidBase58)
+        );
+        assert_eq!(Address::parse("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMJ"), Err(Error::InvalidChecksum));
+        // A WIF key is valid Base58Check but not an address.
+        assert_eq!(
+            Address::parse("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn"),
+            Err(Error::InvalidAddress)
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Base58 and Base58Check encoding with the Bitcoin alphabet.
+
+use secp256k1::Error;
+use super::sha256d;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encode bytes as Base58. Each leading zero byte becomes a leading `1`.
+pub fn encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|b| **b == 0).count();
+
+    // Little-endian base-58 digits of the number.
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for b in &data[zeros..] {
+        let mut carry = u32::from(*b);
+        for d in digits.iter_mut() {
+            carry += u32::from(*d) << 8;
+            *d = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut ret = String::with_capacity(zeros + digits.len());
+    for _ in 0..zeros {
+        ret.push('1');
+    }
+    for d in digits.iter().rev() {
+        ret.push(ALPHABET[*d as usize] as char);
+    }
+    ret
+}
+
+/// Decode a Base58 string.
+pub fn decode(s: &str) -> Result<Vec<u8>, Error> {
+    let zeros = s.bytes().take_while(|c| *c == b'1').count();
+
+    // Little-endian base-256 digits of the number.
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len() * 733 / 1000 + 1);
+    for c in s.bytes().skip(zeros) {
+        let mut carry = ALPHABET.iter().position(|a| *a == c).ok_or(Error::InvalidBase58)? as u32;
+        for b in bytes.iter_mut() {
+            carry += u32::from(*b) * 58;
+            *b = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut ret = vec![0u8; zeros];
+    ret.extend(bytes.iter().rev());
+    Ok(ret)
+}
+
+/// Encode bytes followed by the first four bytes of their double SHA-256.
+pub fn encode_check(data: &[u8]) -> String {
+    let mut payload = data.to_vec();
+    payload.extend_from_slice(&sha256d(data)[..4]);
+    encode(&payload)
+}
+
+/// Decode a Base58Check string and verify its checksum.
+pub fn decode_check(s: &str) -> Result<Vec<u8>, Error> {
+    let mut payload = decode(s)?;
+    if payload.len() < 4 {
+        return Err(Error::InvalidChecksum);
+    }
+    let data_len = payload.len() - 4;
+    if sha256d(&payload[..data_len])[..4] != payload[data_len..] {
+        return Err(Error::InvalidChecksum);
+    }
+    payload.truncate(data_len);
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use hex;
+    use super::{decode, decode_check, encode, encode_check};
+    use Error;
+
+    #[test]
+    fn test_base58() {
+        // Bitcoin Core base58_encode_decode.json
+        let vectors = [
+            ("", ""),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("636363", "aPEr"),
+            ("73696d706c792061206c6f6e6720737472696e67", "2cFupjhnEsSn59qHXstmK2ffpLv2"),
+            ("00eb15231dfceb60925886b67d065299925915aeb172c06647", "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L"),
+            ("516b6fcd0f", "ABnLTmg"),
+            ("bf4f89001e670274dd", "3SEo3LWLoPntC"),
+            ("572e4794", "3EFU7m"),
+            ("ecac89cad93923c02321", "EJDM8drfXA6uyA"),
+            ("10c8511e", "Rt5zm"),
+            ("00000000000000000000", "1111111111"),
+        ];
+        for &(data, encoded) in vectors.iter() {
+            let data = hex::decode(data).unwrap();
+            assert_eq!(encode(&data), encoded);
+            assert_eq!(decode(encoded).unwrap(), data);
+        }
+
+        assert_eq!(decode("0OIl"), Err(Error::InvalidBase58));
+    }
+
+    #[test]
+    fn test_base58_check() {
+        let payload = hex::decode("00f54a5851e9372b87810a8e60cdd2e7cfd80b6e31").unwrap();
+        let encoded = encode_check(&payload);
+        assert_eq!(encoded, "1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs");
+        assert_eq!(decode_check(&encoded).unwrap(), payload);
+
+        assert_eq!(decode_check("1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAt"), Err(Error::InvalidChecksum));
+        assert_eq!(decode_check("111"), Err(Error::InvalidChecksum));
+    }
+}
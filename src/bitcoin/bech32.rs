@@ -0,0 +1,358 @@
+//! Bech32 (BIP-173) and Bech32m (BIP-350) encodings and segregated witness
+//! addresses.
+
+use secp256k1::Error;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc830a3;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The checksum flavour. Witness version 0 uses Bech32, later versions
+/// use Bech32m.
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => BECH32_CONST,
+            Variant::Bech32m => BECH32M_CONST,
+        }
+    }
+
+    fn for_witness_version(version: u8) -> Variant {
+        if version == 0 {
+            Variant::Bech32
+        } else {
+            Variant::Bech32m
+        }
+    }
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk = 1u32;
+    for v in values {
+        let top = chk >> 25;
+        chk = (chk & 0x1ffffff) << 5 ^ u32::from(*v);
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &[u8]) -> Vec<u8> {
+    let mut ret: Vec<u8> = hrp.iter().map(|c| c >> 5).collect();
+    ret.push(0);
+    ret.extend(hrp.iter().map(|c| c & 31));
+    ret
+}
+
+/// Encode a human-readable part and 5-bit data values with the checksum
+/// of the given variant.
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> String {
+    let hrp = hrp.to_lowercase();
+
+    let mut values = hrp_expand(hrp.as_bytes());
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0u8; 6]);
+    let checksum = polymod(&values) ^ variant.constant();
+
+    let mut ret = hrp;
+    ret.push('1');
+    for d in data {
+        ret.push(CHARSET[*d as usize] as char);
+    }
+    for i in 0..6 {
+        ret.push(CHARSET[((checksum >> (5 * (5 - i))) & 31) as usize] as char);
+    }
+    ret
+}
+
+/// Decode a Bech32 or Bech32m string into its lowercase human-readable
+/// part, 5-bit data values without the checksum, and checksum variant.
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), Error> {
+    if s.len() > 90 || s.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(Error::InvalidAddress);
+    }
+    let lower = s.to_lowercase();
+    if s != lower && s != s.to_uppercase() {
+        return Err(Error::InvalidAddress);
+    }
+
+    let separator = lower.rfind('1').ok_or(Error::InvalidAddress)?;
+    if separator == 0 || separator + 7 > lower.len() {
+        return Err(Error::InvalidAddress);
+    }
+    let hrp = &lower[..separator];
+
+    let mut data = Vec::with_capacity(lower.len() - separator - 1);
+    for c in lower[(separator + 1)..].bytes() {
+        let v = CHARSET.iter().position(|a| *a == c).ok_or(Error::InvalidAddress)?;
+        data.push(v as u8);
+    }
+
+    let mut values = hrp_expand(hrp.as_bytes());
+    values.extend_from_slice(&data);
+    let variant = match polymod(&values) {
+        BECH32_CONST => Variant::Bech32,
+        BECH32M_CONST => Variant::Bech32m,
+        _ => return Err(Error::InvalidChecksum),
+    };
+
+    let data_len = data.len() - 6;
+    data.truncate(data_len);
+    Ok((hrp.to_string(), data, variant))
+}
+
+/// Regroup a bit stream from `from`-bit to `to`-bit values. Without
+/// padding, leftover bits must be fewer than `from` and all zero.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Error> {
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    let max = (1u32 << to) - 1;
+    let mut ret = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for v in data {
+        acc = acc << from | u32::from(*v);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            ret.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            ret.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & max != 0 {
+        return Err(Error::InvalidAddress);
+    }
+    Ok(ret)
+}
+
+/// Check the witness version and program length rules of BIP-141.
+fn check_witness_program(version: u8, program: &[u8]) -> Result<(), Error> {
+    if version > 16 || !(2..=40).contains(&program.len()) {
+        return Err(Error::InvalidAddress);
+    }
+    if version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err(Error::InvalidAddress);
+    }
+    Ok(())
+}
+
+/// Encode a witness program as a segwit address, with Bech32 for version 0
+/// and Bech32m for later versions.
+pub fn encode_segwit(hrp: &str, version: u8, program: &[u8]) -> Result<String, Error> {
+    check_witness_program(version, program)?;
+    let mut data = vec![version];
+    data.extend(convert_bits(program, 8, 5, true)?);
+    Ok(encode(hrp, &data, Variant::for_witness_version(version)))
+}
+
+/// Decode a segwit address with the expected human-readable part into its
+/// witness version and program. A checksum variant that does not match the
+/// witness version is reported as a checksum error.
+pub fn decode_segwit(hrp: &str, address: &str) -> Result<(u8, Vec<u8>), Error> {
+    let (found, data, variant) = decode(address)?;
+    if found != hrp || data.is_empty() {
+        return Err(Error::InvalidAddress);
+    }
+    let version = data[0];
+    let program = convert_bits(&data[1..], 5, 8, false)?;
+    check_witness_program(version, &program)?;
+    if variant != Variant::for_witness_version(version) {
+        return Err(Error::InvalidChecksum);
+    }
+    Ok((version, program))
+}
+
+#[cfg(test)]
+mod tests {
+    use hex;
+    use super::{decode, decode_segwit, encode_segwit, Variant};
+    use Error;
+
+    #[test]
+    fn test_valid_checksums() {
+        // BIP-173
+        let valid = [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ];
+        for s in valid.iter() {
+            assert_eq!(decode(s).unwrap().2, Variant::Bech32, "{}", s);
+        }
+
+        // BIP-350
+        let valid = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "11llllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllludsr8",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+        for s in valid.iter() {
+            assert_eq!(decode(s).unwrap().2, Variant::Bech32m, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_invalid_checksums() {
+        // BIP-173
+        let invalid = [
+            "\u{20}1nwldj5",
+            "\u{7f}1axkwrx",
+            "\u{80}1eym55h",
+            "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx",
+            "pzry9x0s0muk",
+            "1pzry9x0s0muk",
+            "x1b4n0q5v",
+            "li1dgmt3",
+            "de1lg7wt\u{ff}",
+            "A1G7SGD8",
+            "10a06t8",
+            "1qzzfhee",
+            // BIP-350: checksum calculated with the uppercase form of the HRP.
+            "M1VUXWEZ",
+        ];
+        for s in invalid.iter() {
+            assert!(decode(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_segwit_v0_addresses() {
+        // BIP-173
+        let vectors = [
+            ("bc", "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", "751e76e8199196d454941c45d1b3a323f1433bd6"),
+            (
+                "tb",
+                "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+                "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+            ),
+            (
+                "tb",
+                "tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy",
+                "000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+            ),
+        ];
+        for &(hrp, address, program) in vectors.iter() {
+            let program = hex::decode(program).unwrap();
+            assert_eq!(decode_segwit(hrp, address).unwrap(), (0, program.clone()));
+            assert_eq!(encode_segwit(hrp, 0, &program).unwrap(), address.to_lowercase());
+        }
+
+        let invalid = [
+            ("bc", "tc1qw508d6qejxtdg4y5r3zarvary0c5xw7kg3g4ty"),
+            ("bc", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5"),
+            ("bc", "bc1rw5uspcuh"),
+            ("bc", "bc10w508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kw5rljs90"),
+            ("bc", "BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P"),
+            ("tb", "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sL5k7"),
+            ("bc", "bc1zw508d6qejxtdg4y5r3zarvaryvqyzf3du"),
+            ("tb", "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3pjxtptv"),
+            ("bc", "bc1gmk9yu"),
+        ];
+        for &(hrp, address) in invalid.iter() {
+            assert!(decode_segwit(hrp, address).is_err(), "{}", address);
+        }
+        assert_eq!(
+            decode_segwit("bc", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5"),
+            Err(Error::InvalidChecksum)
+        );
+    }
+
+    #[test]
+    fn test_segwit_addresses() {
+        // BIP-350, with the witness programs taken from the scriptPubKeys.
+        let vectors = [
+            ("bc", "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", 0, "751e76e8199196d454941c45d1b3a323f1433bd6"),
+            (
+                "tb",
+                "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+                0,
+                "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+            ),
+            (
+                "bc",
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+                1,
+                "751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6",
+            ),
+            ("bc", "BC1SW50QGDZ25J", 16, "751e"),
+            ("bc", "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs", 2, "751e76e8199196d454941c45d1b3a323"),
+            (
+                "tb",
+                "tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy",
+                0,
+                "000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+            ),
+            (
+                "tb",
+                "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c",
+                1,
+                "000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+            ),
+            (
+                "bc",
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+                1,
+                "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            ),
+        ];
+        for &(hrp, address, version, program) in vectors.iter() {
+            let program = hex::decode(program).unwrap();
+            assert_eq!(decode_segwit(hrp, address).unwrap(), (version, program.clone()));
+            assert_eq!(encode_segwit(hrp, version, &program).unwrap(), address.to_lowercase());
+        }
+
+        let invalid = [
+            // Invalid human-readable part
+            ("bc", "tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut"),
+            // Bech32 instead of Bech32m and the other way around
+            ("bc", "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd"),
+            ("tb", "tb1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf"),
+            ("bc", "BC1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL"),
+            ("bc", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh"),
+            ("tb", "tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47"),
+            // Invalid character in checksum
+            ("bc", "bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4"),
+            // Invalid witness version
+            ("bc", "BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R"),
+            // Invalid program lengths
+            ("bc", "bc1pw5dgrnzv"),
+            ("bc", "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v8n0nx0muaewav253zgeav"),
+            ("bc", "BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P"),
+            // Mixed case
+            ("tb", "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq"),
+            // Invalid padding
+            ("bc", "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf"),
+            ("tb", "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j"),
+            // Empty data section
+            ("bc", "bc1gmk9yu"),
+        ];
+        for &(hrp, address) in invalid.iter() {
+            assert!(decode_segwit(hrp, address).is_err(), "{}", address);
+        }
+        assert_eq!(
+            decode_segwit("bc", "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd"),
+            Err(Error::InvalidChecksum)
+        );
+        assert_eq!(encode_segwit("bc", 17, &[0; 32]), Err(Error::InvalidAddress));
+        assert_eq!(encode_segwit("bc", 1, &[0; 41]), Err(Error::InvalidAddress));
+    }
+}
@@ -0,0 +1,394 @@
+//! Bitcoin helpers: HASH160, Base58Check and bech32 encodings, addresses,
+//! WIF secret keys, and signed messages as produced by `signmessage` and
+//! BIP-137 wallets.
+
+mod address;
+pub mod base58;
+pub mod bech32;
+mod ripemd160;
+pub mod taproot;
+
+pub use self::address::{Address, Payload};
+pub use self::ripemd160::ripemd160;
+
+use base64;
+use secp256k1::{Error, Message, PublicKey, RecoverableSignature, SecretKey};
+use sha2::{Digest, Sha256};
+
+const MESSAGE_MAGIC: &[u8] = b"\x18Bitcoin Signed Message:\n";
+
+/// Compute the double SHA-256 of `data`.
+pub fn sha256d(data: &[u8]) -> [u8; 32] {
+    let mut ret = [0u8; 32];
+    ret.copy_from_slice(&Sha256::digest(&Sha256::digest(data)));
+    ret
+}
+
+/// Compute `RIPEMD160(SHA256(data))`.
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    ripemd160(&Sha256::digest(data))
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// The Bitcoin network a key or address belongs to.
+pub enum Network {
+    Bitcoin,
+    Testnet,
+    Regtest,
+}
+
+impl Network {
+    /// The version byte of WIF encoded secret keys. Testnet and regtest
+    /// share the same prefix.
+    fn wif_prefix(&self) -> u8 {
+        match *self {
+            Network::Bitcoin => 0x80,
+            Network::Testnet | Network::Regtest => 0xef,
+        }
+    }
+}
+
+impl SecretKey {
+    /// Encode the secret key in Wallet Import Format: Base58Check of the
+    /// network prefix, the key and a `0x01` suffix if the corresponding
+    /// public key is used in compressed form.
+    pub fn to_wif(&self, network: Network, compressed: bool) -> String {
+        let mut data = vec![network.wif_prefix()];
+        data.extend_from_slice(&self.serialize());
+        if compressed {
+            data.push(0x01);
+        }
+        base58::encode_check(&data)
+    }
+
+    /// Decode a WIF secret key, returning it together with its network and
+    /// whether the public key is compressed. Regtest keys are reported as
+    /// testnet keys.
+    pub fn from_wif(wif: &str) -> Result<(SecretKey, Network, bool), Error> {
+        let data = base58::decode_check(wif)?;
+        let compressed = match data.len() {
+            33 => false,
+            34 if data[33] == 0x01 => true,
+            _ => return Err(Error::InvalidSecretKey),
+        };
+        let network = match data[0] {
+            0x80 => Network::Bitcoin,
+            0xef => Network::Testnet,
+            _ => return Err(Error::InvalidSecretKey),
+        };
+        let seckey = SecretKey::parse(array_ref!(data, 1, 32))?;
+        Ok((seckey, network, compressed))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// The address type a message signature commits to through its header
+/// byte, as defined by BIP-137.
+pub enum AddressType {
+    /// P2PKH with an uncompressed public key.
+    P2pkhUncompressed,
+    /// P2PKH with a compressed public key.
+    P2pkh,
+    /// P2WPKH nested in P2SH.
+    P2shP2wpkh,
+    /// Native segwit P2WPKH.
+    P2wpkh,
+}
+
+impl AddressType {
+    /// What BIP-137 adds to the compressed key header of the compact form
+    /// (31..=34) for segwit addresses: 4 for P2SH-P2WPKH (35..=38) and 8
+    /// for P2WPKH (39..=42). The 27..=34 range itself is handled by
+    /// `RecoverableSignature::serialize_bitcoin` and `parse_bitcoin`; this
+    /// is the only place that extends it.
+    fn segwit_header_offset(&self) -> u8 {
+        match *self {
+            AddressType::P2pkhUncompressed | AddressType::P2pkh => 0,
+            AddressType::P2shP2wpkh => 4,
+            AddressType::P2wpkh => 8,
+        }
+    }
+
+    /// Serialize a signature to the 65-byte BIP-137 form `header || r || s`.
+    fn serialize_signature(&self, signature: &RecoverableSignature) -> [u8; 65] {
+        let mut ret = signature.serialize_bitcoin(*self != AddressType::P2pkhUncompressed);
+        ret[0] += self.segwit_header_offset();
+        ret
+    }
+
+    /// Parse the 65-byte BIP-137 form, returning the signature and the
+    /// address type its header commits to.
+    fn parse_signature(p: &[u8; 65]) -> Result<(RecoverableSignature, AddressType), Error> {
+        let segwit = match p[0] {
+            35..=38 => Some(AddressType::P2shP2wpkh),
+            39..=42 => Some(AddressType::P2wpkh),
+            _ => None,
+        };
+        let mut compact = *p;
+        if let Some(address_type) = segwit {
+            compact[0] -= address_type.segwit_header_offset();
+        }
+        let (signature, compressed) = RecoverableSignature::parse_bitcoin(&compact)?;
+        let address_type = match segwit {
+            Some(address_type) => address_type,
+            None if compressed => AddressType::P2pkh,
+            None => AddressType::P2pkhUncompressed,
+        };
+        Ok((signature, address_type))
+    }
+}
+
+/// Append `len` as a Bitcoin `CompactSize` variable length integer.
+fn write_compact_size(data: &mut Vec<u8>, len: u64) {
+    if len < 0xfd {
+        data.push(len as u8);
+    } else if len <= 0xffff {
+        data.push(0xfd);
+        data.extend_from_slice(&(len as u16).to_le_bytes());
+    } else if len <= 0xffff_ffff {
+        data.push(0xfe);
+        data.extend_from_slice(&(len as u32).to_le_bytes());
+    } else {
+        data.push(0xff);
+        data.extend_from_slice(&len.to_le_bytes());
+    }
+}
+
+/// The hash signed by `signmessage`: the double SHA-256 of the magic
+/// prefix, the varint-encoded message length and the message.
+pub fn signed_message_hash(text: &[u8]) -> Message {
+    let mut data = MESSAGE_MAGIC.to_vec();
+    write_compact_size(&mut data, text.len() as u64);
+    data.extend_from_slice(text);
+    Message::parse(&sha256d(&data))
+}
+
+/// Sign a message for an address of the given type, returning the base64
+/// encoded 65-byte `header || r || s` signature.
+pub fn sign_message(text: &[u8], seckey: &SecretKey, address_type: AddressType) -> Result<String, Error> {
+    let signature = Message::sign_recoverable(&signed_message_hash(text), seckey)?;
+    Ok(base64::encode(&address_type.serialize_signature(&signature)[..]))
+}
+
+/// Verify a base64 message signature against a P2PKH, P2SH-P2WPKH or
+/// P2WPKH address. Signatures with a compressed P2PKH header are accepted
+/// for segwit addresses too, as many wallets produced those before BIP-137.
+pub fn verify_message(text: &[u8], signature: &str, address: &str) -> bool {
+    let raw = match base64::decode(signature) {
+        Ok(ref raw) if raw.len() == 65 => *array_ref!(raw, 0, 65),
+        _ => return false,
+    };
+    let (signature, address_type) = match AddressType::parse_signature(&raw) {
+        Ok(v) => v,
+        Err(_) => return false,
+    };
+    let pubkey: PublicKey = match signature.recover(&signed_message_hash(text)) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return false,
+    };
+    let address = match Address::parse(address) {
+        Ok(address) => address,
+        Err(_) => return false,
+    };
+
+    let network = address.network;
+    let p2pkh = Address::p2pkh(&pubkey, network);
+    let p2sh_p2wpkh = Address::p2sh_p2wpkh(&pubkey, network);
+    let p2wpkh = Address::p2wpkh(&pubkey, network);
+    match address_type {
+        AddressType::P2pkhUncompressed => {
+            address.payload == Payload::PubkeyHash(hash160(&pubkey.serialize()[..]))
+        }
+        AddressType::P2pkh => address == p2pkh || address == p2sh_p2wpkh || address == p2wpkh,
+        AddressType::P2shP2wpkh => address == p2sh_p2wpkh,
+        AddressType::P2wpkh => address == p2wpkh,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64;
+    use hex;
+    use super::{base58, hash160, sign_message, signed_message_hash, verify_message, AddressType, Network};
+    use {Error, Message, PublicKey, RecoverableSignature, SecretKey};
+
+    #[test]
+    fn test_hash160() {
+        let seckey = SecretKey::from_hex("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        assert_eq!(
+            hex::encode(hash160(&pubkey.serialize_compressed()[..])),
+            "751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+        assert_eq!(
+            hex::encode(hash160(&pubkey.serialize()[..])),
+            "91b24bf9f5288532960ac687abb035127b1d28a5"
+        );
+    }
+
+    #[test]
+    fn test_signed_message_hash() {
+        // rust-bitcoin sign_message.rs, displayed there in reversed byte order.
+        let mut hash = signed_message_hash(b"test").serialize();
+        hash.reverse();
+        assert_eq!(
+            hex::encode(hash),
+            "a6f87fe6d58a032c320ff8d1541656f0282c2c7bfcc69d61af4c8e8ed528e49c"
+        );
+
+        // Lengths of 253 and more use the three-byte varint form.
+        let long = vec![b'a'; 300];
+        assert!(signed_message_hash(&long) != signed_message_hash(&long[..299]));
+    }
+
+    #[test]
+    fn test_sign_message_vector() {
+        // rust-bitcoin sign_message.rs
+        let raw = base64::decode("UuOGDsfLPr4HIMKQX0ipjJeRaj1geCq3yPUF2COP5ME=").unwrap();
+        let seckey = SecretKey::parse(array_ref!(raw, 0, 32)).unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        assert_eq!(
+            base64::encode(&pubkey.serialize_compressed()[..]),
+            "A1FTfMEntPpAty3qkEo0q2Dc1FEycI10a3jmwEFy+Qr6"
+        );
+
+        let message = b"rust-bitcoin MessageSignature test";
+        let signature = sign_message(message, &seckey, AddressType::P2pkh).unwrap();
+        assert_eq!(
+            signature,
+            "IAM2qX24tYx/bdBTIgVLhD8QEAjrPlJpmjB4nZHdRYGIBa4DmVulAcwjPnWe6Q5iEwXH6F0pUCJP/ZeHPWS1h1o="
+        );
+    }
+
+    #[test]
+    fn test_sign_verify_address_types() {
+        // Secret key 1.
+        let seckey = SecretKey::from_hex("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let uncompressed = "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm";
+        let p2pkh = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH";
+        let p2sh_p2wpkh = "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN";
+        let p2wpkh = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        let message = b"vires in numeris";
+
+        let signature = sign_message(message, &seckey, AddressType::P2pkhUncompressed).unwrap();
+        assert!(verify_message(message, &signature, uncompressed));
+        assert!(!verify_message(message, &signature, p2pkh));
+        assert!(!verify_message(message, &signature, p2wpkh));
+
+        let signature = sign_message(message, &seckey, AddressType::P2pkh).unwrap();
+        assert!(!verify_message(message, &signature, uncompressed));
+        assert!(verify_message(message, &signature, p2pkh));
+        assert!(verify_message(message, &signature, p2sh_p2wpkh));
+        assert!(verify_message(message, &signature, p2wpkh));
+        assert!(verify_message(message, &signature, &p2wpkh.to_uppercase()));
+
+        let signature = sign_message(message, &seckey, AddressType::P2shP2wpkh).unwrap();
+        assert!(verify_message(message, &signature, p2sh_p2wpkh));
+        assert!(!verify_message(message, &signature, p2pkh));
+        assert!(!verify_message(message, &signature, p2wpkh));
+
+        let signature = sign_message(message, &seckey, AddressType::P2wpkh).unwrap();
+        assert!(verify_message(message, &signature, p2wpkh));
+        assert!(!verify_message(message, &signature, p2sh_p2wpkh));
+        assert!(!verify_message(message, &signature, p2pkh));
+
+        assert!(!verify_message(b"vires in numeris.", &signature, p2wpkh));
+    }
+
+    #[test]
+    fn test_signature_headers() {
+        let seckey = SecretKey::from_hex("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let signature = Message::sign_recoverable(&signed_message_hash(b"headers"), &seckey).unwrap();
+        let recid = signature.recovery_id.serialize();
+        let types = [
+            (AddressType::P2pkhUncompressed, 27),
+            (AddressType::P2pkh, 31),
+            (AddressType::P2shP2wpkh, 35),
+            (AddressType::P2wpkh, 39),
+        ];
+        for &(address_type, base) in types.iter() {
+            let raw = address_type.serialize_signature(&signature);
+            assert_eq!(raw[0], base + recid);
+            assert_eq!(AddressType::parse_signature(&raw), Ok((signature.clone(), address_type)));
+        }
+
+        // The P2PKH headers are exactly the compact form of `RecoverableSignature`.
+        let compact = signature.serialize_bitcoin(true);
+        assert_eq!(AddressType::P2pkh.serialize_signature(&signature)[..], compact[..]);
+        let mut raw = compact;
+        for &header in [26u8, 43, 0xff].iter() {
+            raw[0] = header;
+            assert_eq!(AddressType::parse_signature(&raw), Err(Error::InvalidRecoveryId));
+            assert!(RecoverableSignature::parse_bitcoin(&raw).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verify_malformed() {
+        let seckey = SecretKey::from_hex("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let message = b"hello";
+        let signature = sign_message(message, &seckey, AddressType::P2pkh).unwrap();
+        let mut raw = base64::decode(&signature).unwrap();
+        let address = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH";
+        assert!(verify_message(message, &signature, address));
+
+        assert!(!verify_message(message, "not base64", address));
+        assert!(!verify_message(message, &base64::encode(&raw[..64]), address));
+        assert!(!verify_message(message, &signature, "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMJ"));
+        raw[0] = 43;
+        assert!(!verify_message(message, &base64::encode(&raw), address));
+        raw[0] = 26;
+        assert!(!verify_message(message, &base64::encode(&raw), address));
+    }
+
+    #[test]
+    fn test_wif() {
+        let one = SecretKey::from_hex("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let vectors = [
+            ("5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf", Network::Bitcoin, false),
+            ("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn", Network::Bitcoin, true),
+            ("91avARGdfge8E4tZfYLoxeJ5sGBdNJQH4kvjJoQFacbgwmaKkrx", Network::Testnet, false),
+            ("cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN87JcbXMTcA", Network::Testnet, true),
+        ];
+        for &(wif, network, compressed) in vectors.iter() {
+            assert_eq!(one.to_wif(network, compressed), wif);
+            assert_eq!(SecretKey::from_wif(wif).unwrap(), (one, network, compressed));
+        }
+        assert_eq!(one.to_wif(Network::Regtest, true), one.to_wif(Network::Testnet, true));
+
+        // rust-bitcoin key.rs
+        let (seckey, network, compressed) =
+            SecretKey::from_wif("5JYkZjmN7PVMjJUfJWfRFwtuXTGB439XV6faajeHPAM9Z2PT2R3").unwrap();
+        assert_eq!((network, compressed), (Network::Bitcoin, false));
+        assert_eq!(
+            PublicKey::from_secret_key(&seckey).to_hex(false),
+            "042e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af191923a2964c177f5b5923ae500fca49e99492d534aa3759d6b25a8bc971b133"
+        );
+        let (seckey, network, compressed) =
+            SecretKey::from_wif("cVt4o7BGAig1UXywgGSmARhxMdzP5qvQsxKkSsc1XEkw3tDTQFpy").unwrap();
+        assert_eq!((network, compressed), (Network::Testnet, true));
+        assert_eq!(seckey.to_wif(Network::Testnet, true), "cVt4o7BGAig1UXywgGSmARhxMdzP5qvQsxKkSsc1XEkw3tDTQFpy");
+    }
+
+    #[test]
+    fn test_wif_invalid() {
+        let mut data = vec![0x80];
+        data.extend_from_slice(&[0x42; 32]);
+
+        let mut bad_flag = data.clone();
+        bad_flag.push(0x02);
+        let mut bad_prefix = data.clone();
+        bad_prefix[0] = 0x81;
+        let mut zero = vec![0x80];
+        zero.extend_from_slice(&[0; 32]);
+        for payload in &[bad_flag, bad_prefix, zero, data[..32].to_vec()] {
+            assert_eq!(SecretKey::from_wif(&base58::encode_check(payload)), Err(Error::InvalidSecretKey));
+        }
+
+        assert_eq!(
+            SecretKey::from_wif("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWo"),
+            Err(Error::InvalidChecksum)
+        );
+        assert_eq!(SecretKey::from_wif("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoW0"), Err(Error::InvalidBase58));
+    }
+}
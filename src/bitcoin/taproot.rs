@@ -0,0 +1,310 @@
+//! Taproot output keys and script trees (BIP-341).
+
+use super::write_compact_size;
+use schnorr::tagged_hash;
+use secp256k1::{Error, Parity, PublicKey, SecretKey, XOnlyPublicKey};
+
+/// The leaf version of BIP-342 tapscript.
+pub const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+
+/// The leaf hash `hash_TapLeaf(leaf_version || compact_size(script) || script)`.
+pub fn tap_leaf_hash(leaf_version: u8, script: &[u8]) -> [u8; 32] {
+    let mut data = vec![leaf_version];
+    write_compact_size(&mut data, script.len() as u64);
+    data.extend_from_slice(script);
+    tagged_hash(b"TapLeaf", &[&data])
+}
+
+/// The branch hash `hash_TapBranch(min(a, b) || max(a, b))` of two child
+/// nodes. Children are sorted so the proof does not depend on their order.
+pub fn tap_branch_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        tagged_hash(b"TapBranch", &[a, b])
+    } else {
+        tagged_hash(b"TapBranch", &[b, a])
+    }
+}
+
+/// A binary tree of leaf scripts committed to by a taproot output.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ScriptTree {
+    Leaf { version: u8, script: Vec<u8> },
+    Branch(Box<ScriptTree>, Box<ScriptTree>),
+}
+
+impl ScriptTree {
+    /// A tapscript leaf.
+    pub fn leaf(script: Vec<u8>) -> ScriptTree {
+        ScriptTree::Leaf {
+            version: TAPSCRIPT_LEAF_VERSION,
+            script,
+        }
+    }
+
+    /// A branch joining two subtrees.
+    pub fn branch(left: ScriptTree, right: ScriptTree) -> ScriptTree {
+        ScriptTree::Branch(Box::new(left), Box::new(right))
+    }
+
+    /// The merkle root of the tree, to be passed to `tweak_internal_key`.
+    pub fn merkle_root(&self) -> [u8; 32] {
+        match *self {
+            ScriptTree::Leaf { version, ref script } => tap_leaf_hash(version, script),
+            ScriptTree::Branch(ref left, ref right) => tap_branch_hash(&left.merkle_root(), &right.merkle_root()),
+        }
+    }
+}
+
+/// The tweak `hash_TapTweak(P || merkle_root)` committing the internal key to
+/// an optional script tree. Without a script tree only `P` is hashed.
+pub fn tap_tweak_hash(internal_key: &XOnlyPublicKey, merkle_root: Option<[u8; 32]>) -> [u8; 32] {
+    let p = internal_key.serialize();
+    match merkle_root {
+        Some(root) => tagged_hash(b"TapTweak", &[&p, &root]),
+        None => tagged_hash(b"TapTweak", &[&p]),
+    }
+}
+
+/// Compute the output key `Q = P + hash_TapTweak(P || merkle_root).G` and
+/// the parity of its y-coordinate, which a script path spend reveals in the
+/// control block.
+pub fn tweak_internal_key(
+    internal_key: &XOnlyPublicKey,
+    merkle_root: Option<[u8; 32]>,
+) -> Result<(XOnlyPublicKey, Parity), Error> {
+    internal_key.tweak_add(&tap_tweak_hash(internal_key, merkle_root))
+}
+
+/// Tweak an internal secret key the same way as `tweak_internal_key`, giving
+/// the secret key that signs key path spends for the output key. The secret
+/// key is negated first if its public key has an odd y-coordinate.
+pub fn tweak_secret_key(seckey: &SecretKey, merkle_root: Option<[u8; 32]>) -> Result<SecretKey, Error> {
+    let (internal_key, parity) = PublicKey::from_secret_key(seckey).x_only();
+    let mut ret = *seckey;
+    if parity == Parity::Odd {
+        ret.negate();
+    }
+    ret.tweak_add_assign(&tap_tweak_hash(&internal_key, merkle_root))?;
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        tap_leaf_hash, tap_tweak_hash, tweak_internal_key, tweak_secret_key, ScriptTree, TAPSCRIPT_LEAF_VERSION,
+    };
+    use bitcoin::{Address, Network};
+    use hex;
+    use schnorr;
+    use {PublicKey, SecretKey, XOnlyPublicKey};
+
+    fn leaf(script: &str) -> ScriptTree {
+        ScriptTree::leaf(hex::decode(script).unwrap())
+    }
+
+    fn root_hex(tree: &ScriptTree) -> String {
+        hex::encode(tree.merkle_root())
+    }
+
+    #[test]
+    fn test_key_path_only() {
+        // BIP-341 wallet test vectors, scriptPubKey 0.
+        let internal_key =
+            XOnlyPublicKey::from_hex("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap();
+        assert_eq!(
+            hex::encode(tap_tweak_hash(&internal_key, None)),
+            "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70"
+        );
+        let (output, _) = tweak_internal_key(&internal_key, None).unwrap();
+        assert_eq!(&output.to_hex(), "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343");
+    }
+
+    #[test]
+    fn test_leaf_and_branch_hashes() {
+        let first = leaf("20387671353e273264c495656e27e39ba899ea8fee3bb69fb2a680e22093447d48ac");
+        let second = ScriptTree::Leaf {
+            version: 250,
+            script: hex::decode("06424950333431").unwrap(),
+        };
+        assert_eq!(
+            root_hex(&first),
+            "8ad69ec7cf41c2a4001fd1f738bf1e505ce2277acdcaa63fe4765192497f47a7"
+        );
+        assert_eq!(
+            root_hex(&second),
+            "f224a923cd0021ab202ab139cc56802ddb92dcfc172b9212261a539df79a112a"
+        );
+        let root = "6c2dc106ab816b73f9d07e3cd1ef2c8c1256f519748e0813e4edd2405d277bef";
+        assert_eq!(root_hex(&ScriptTree::branch(first.clone(), second.clone())), root);
+        assert_eq!(root_hex(&ScriptTree::branch(second, first)), root);
+
+        // A single byte length prefix up to 252 bytes, then 0xfd || u16.
+        assert_ne!(
+            tap_leaf_hash(TAPSCRIPT_LEAF_VERSION, &[0x51; 252]),
+            tap_leaf_hash(TAPSCRIPT_LEAF_VERSION, &[0x51; 253])
+        );
+    }
+
+    #[test]
+    fn test_script_pubkeys() {
+        // BIP-341 wallet test vectors, scriptPubKey 0 to 6.
+        let vectors = [
+            (
+                "d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d",
+                None,
+                "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343",
+                "bc1p2wsldez5mud2yam29q22wgfh9439spgduvct83k3pm50fcxa5dps59h4z5",
+            ),
+            (
+                "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+                Some((
+                    leaf("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac"),
+                    "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+                )),
+                "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+                "bc1pz37fc4cn9ah8anwm4xqqhvxygjf9rjf2resrw8h8w4tmvcs0863sa2e586",
+            ),
+            (
+                "93478e9488f956df2396be2ce6c5cced75f900dfa18e7dabd2428aae78451820",
+                Some((
+                    leaf("20b617298552a72ade070667e86ca63b8f5789a9fe8731ef91202a91c9f3459007ac"),
+                    "c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b",
+                )),
+                "e4d810fd50586274face62b8a807eb9719cef49c04177cc6b76a9a4251d5450e",
+                "bc1punvppl2stp38f7kwv2u2spltjuvuaayuqsthe34hd2dyy5w4g58qqfuag5",
+            ),
+            (
+                "ee4fe085983462a184015d1f782d6a5f8b9c2b60130aff050ce221ecf3786592",
+                Some((
+                    ScriptTree::branch(
+                        leaf("20387671353e273264c495656e27e39ba899ea8fee3bb69fb2a680e22093447d48ac"),
+                        ScriptTree::Leaf {
+                            version: 250,
+                            script: hex::decode("06424950333431").unwrap(),
+                        },
+                    ),
+                    "6c2dc106ab816b73f9d07e3cd1ef2c8c1256f519748e0813e4edd2405d277bef",
+                )),
+                "712447206d7a5238acc7ff53fbe94a3b64539ad291c7cdbc490b7577e4b17df5",
+                "bc1pwyjywgrd0ffr3tx8laflh6228dj98xkjj8rum0zfpd6h0e930h6saqxrrm",
+            ),
+            (
+                "f9f400803e683727b14f463836e1e78e1c64417638aa066919291a225f0e8dd8",
+                Some((
+                    ScriptTree::branch(
+                        leaf("2044b178d64c32c4a05cc4f4d1407268f764c940d20ce97abfd44db5c3592b72fdac"),
+                        leaf("07546170726f6f74"),
+                    ),
+                    "ab179431c28d3b68fb798957faf5497d69c883c6fb1e1cd9f81483d87bac90cc",
+                )),
+                "77e30a5522dd9f894c3f8b8bd4c4b2cf82ca7da8a3ea6a239655c39c050ab220",
+                "bc1pwl3s54fzmk0cjnpl3w9af39je7pv5ldg504x5guk2hpecpg2kgsqaqstjq",
+            ),
+            (
+                "e0dfe2300b0dd746a3f8674dfd4525623639042569d829c7f0eed9602d263e6f",
+                Some((
+                    ScriptTree::branch(
+                        leaf("2072ea6adcf1d371dea8fba1035a09f3d24ed5a059799bae114084130ee5898e69ac"),
+                        ScriptTree::branch(
+                            leaf("202352d137f2f3ab38d1eaa976758873377fa5ebb817372c71e2c542313d4abda8ac"),
+                            leaf("207337c0dd4253cb86f2c43a2351aadd82cccb12a172cd120452b9bb8324f2186aac"),
+                        ),
+                    ),
+                    "ccbd66c6f7e8fdab47b3a486f59d28262be857f30d4773f2d5ea47f7761ce0e2",
+                )),
+                "91b64d5324723a985170e4dc5a0f84c041804f2cd12660fa5dec09fc21783605",
+                "bc1pjxmy65eywgafs5tsunw95ruycpqcqnev6ynxp7jaasylcgtcxczs6n332e",
+            ),
+            (
+                "55adf4e8967fbd2e29f20ac896e60c3b0f1d5b0efa9d34941b5958c7b0a0312d",
+                Some((
+                    ScriptTree::branch(
+                        leaf("2071981521ad9fc9036687364118fb6ccd2035b96a423c59c5430e98310a11abe2ac"),
+                        ScriptTree::branch(
+                            leaf("20d5094d2dbe9b76e2c245a2b89b6006888952e2faa6a149ae318d69e520617748ac"),
+                            leaf("20c440b462ad48c7a77f94cd4532d8f2119dcebbd7c9764557e62726419b08ad4cac"),
+                        ),
+                    ),
+                    "2f6b2c5397b6d68ca18e09a3f05161668ffe93a988582d55c6f07bd5b3329def",
+                )),
+                "75169f4001aa68f15bbed28b218df1d0a62cbbcf1188c6665110c293c907b831",
+                "bc1pw5tf7sqp4f50zka7629jrr036znzew70zxyvvej3zrpf8jg8hqcssyuewe",
+            ),
+        ];
+
+        for &(internal, ref tree, output, address) in vectors.iter() {
+            let internal_key = XOnlyPublicKey::from_hex(internal).unwrap();
+            let merkle_root = tree.as_ref().map(|&(ref tree, root)| {
+                assert_eq!(root_hex(tree), root);
+                tree.merkle_root()
+            });
+            let (output_key, _) = tweak_internal_key(&internal_key, merkle_root).unwrap();
+            assert_eq!(&output_key.to_hex(), output);
+            assert_eq!(
+                Address::p2tr(&internal_key, merkle_root, Network::Bitcoin)
+                    .unwrap()
+                    .to_string(),
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn test_tweak_secret_key() {
+        // BIP-341 wallet test vectors, keyPathSpending internal and tweaked
+        // private keys.
+        let vectors = [
+            (
+                "6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa",
+                None,
+                "2405b971772ad26915c8dcdf10f238753a9b837e5f8e6a86fd7c0cce5b7296d9",
+            ),
+            (
+                "1e4da49f6aaf4e5cd175fe08a32bb5cb4863d963921255f33d3bc31e1343907f",
+                Some("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"),
+                "ea260c3b10e60f6de018455cd0278f2f5b7e454be1999572789e6a9565d26080",
+            ),
+            (
+                "d3c7af07da2d54f7a7735d3d0fc4f0a73164db638b2f2f7c43f711f6d4aa7e64",
+                Some("c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b"),
+                "97323385e57015b75b0339a549c56a948eb961555973f0951f555ae6039ef00d",
+            ),
+            (
+                "f36bb07a11e469ce941d16b63b11b9b9120a84d9d87cff2c84a8d4affb438f4e",
+                Some("ccbd66c6f7e8fdab47b3a486f59d28262be857f30d4773f2d5ea47f7761ce0e2"),
+                "a8e7aa924f0d58854185a490e6c41f6efb7b675c0f3331b7f14b549400b4d501",
+            ),
+            (
+                "415cfe9c15d9cea27d8104d5517c06e9de48e2f986b695e4f5ffebf230e725d8",
+                Some("2f6b2c5397b6d68ca18e09a3f05161668ffe93a988582d55c6f07bd5b3329def"),
+                "241c14f2639d0d7139282aa6abde28dd8a067baa9d633e4e7230287ec2d02901",
+            ),
+            (
+                "c7b0e81f0a9a0b0499e112279d718cca98e79a12e2f137c72ae5b213aad0d103",
+                Some("6c2dc106ab816b73f9d07e3cd1ef2c8c1256f519748e0813e4edd2405d277bef"),
+                "65b6000cd2bfa6b7cf736767a8955760e62b6649058cbc970b7c0871d786346b",
+            ),
+            (
+                "77863416be0d0665e517e1c375fd6f75839544eca553675ef7fdf4949518ebaa",
+                Some("ab179431c28d3b68fb798957faf5497d69c883c6fb1e1cd9f81483d87bac90cc"),
+                "ec18ce6af99f43815db543f47b8af5ff5df3b2cb7315c955aa4a86e8143d2bf5",
+            ),
+        ];
+
+        for &(internal, root, tweaked) in vectors.iter() {
+            let seckey = SecretKey::from_hex(internal).unwrap();
+            let merkle_root = root.map(|root| {
+                let root = hex::decode(root).unwrap();
+                *array_ref!(root, 0, 32)
+            });
+            let tweaked_seckey = tweak_secret_key(&seckey, merkle_root).unwrap();
+            assert_eq!(tweaked_seckey, SecretKey::from_hex(tweaked).unwrap());
+
+            // The tweaked secret key signs for the output key.
+            let (internal_key, _) = PublicKey::from_secret_key(&seckey).x_only();
+            let (output_key, _) = tweak_internal_key(&internal_key, merkle_root).unwrap();
+            let signature = schnorr::sign(b"key path", &tweaked_seckey, &[0u8; 32]).unwrap();
+            assert!(schnorr::verify(&signature, b"key path", &output_key));
+        }
+    }
+}
@@ -2,6 +2,9 @@ use secp256k1::Scalar;
 use secp256k1::group::{ Jacobian, Affine, AffineStorage, globalz_set_table_gej };
 use secp256k1::field::Field;
 
+mod multi;
+pub use self::multi::{ScratchSpace, ECMULT_PIPPENGER_THRESHOLD};
+
 pub const WINDOW_A: usize = 5;
 pub const WINDOW_G: usize = 16;
 pub const ECMULT_TABLE_SIZE_A: usize = 1 << (WINDOW_A - 2);
@@ -28,10 +31,10 @@ pub static ECMULT_CONTEXT: ECMultContext = ECMultContext {
 /// A static ECMultGen context.
 pub static ECMULT_GEN_CONTEXT: ECMultGenContext = ECMultGenContext {
     prec: include!("const_gen.rs"),
-    blind: Scalar([
-        2217680822, 850875797, 1046150361, 1330484644, 4015777837, 2466086288, 2052467175,
-        2084507480,
-    ]),
+    blind: scalar_const!(
+        2084507480, 2052467175, 2466086288, 4015777837, 1330484644, 1046150361, 850875797,
+        2217680822
+    ),
     initial: Jacobian {
         x: field_const_raw!(
             586608, 43357028, 207667908, 262670128, 142222828, 38529388, 267186148, 45417712,
@@ -182,6 +185,12 @@ pub fn ecmult_wnaf(wnaf: &mut [i32], a: &Scalar, w: usize) -> i32 {
 }
 
 pub fn ecmult_wnaf_const(wnaf: &mut [i32], a: &Scalar, w: usize) -> i32 {
+    ecmult_wnaf_const_bits(wnaf, a, w, WNAF_BITS)
+}
+
+/// Like `ecmult_wnaf_const`, for scalars of at most `size` bits, or whose
+/// negation is.
+fn ecmult_wnaf_const_bits(wnaf: &mut [i32], a: &Scalar, w: usize, size: usize) -> i32 {
     let mut s = a.clone();
     let mut word = 0;
 
@@ -218,7 +227,7 @@ pub fn ecmult_wnaf_const(wnaf: &mut [i32], a: &Scalar, w: usize) -> i32 {
 
     let mut u_last: i32 = s.shr_int(w) as i32;
     let mut u: i32 = 0;
-    while word * w < WNAF_BITS {
+    while word * w < size {
         u = s.shr_int(w) as i32;
         let even = (u & 1) == 0;
         let sign = 2 * (if u_last > 0 { 1 } else { 0 }) - 1;
@@ -233,13 +242,14 @@ pub fn ecmult_wnaf_const(wnaf: &mut [i32], a: &Scalar, w: usize) -> i32 {
     wnaf[word] = u * global_sign as i32;
 
     debug_assert!(s.is_zero());
-    let wnaf_size = (WNAF_BITS + w - 1) / w;
+    let wnaf_size = (size + w - 1) / w;
     debug_assert!(word == wnaf_size);
 
     skew
 }
 
 impl ECMultContext {
+    #[cfg(not(feature = "endomorphism"))]
     pub fn ecmult(&self, r: &mut Jacobian, a: &Jacobian, na: &Scalar, ng: &Scalar) {
         let mut tmpa = Affine::default();
         let mut pre_a: [Affine; ECMULT_TABLE_SIZE_A] = Default::default();
@@ -277,6 +287,67 @@ impl ECMultContext {
         }
     }
 
+    /// Like the default `ecmult`, with `na` and `ng` each split into two
+    /// halves of about 128 bits with the endomorphism, so that half as many
+    /// doublings are needed. The tables for `lambda.A` and `lambda.G` are
+    /// those for `A` and `G` with x multiplied by `beta`.
+    #[cfg(feature = "endomorphism")]
+    pub fn ecmult(&self, r: &mut Jacobian, a: &Jacobian, na: &Scalar, ng: &Scalar) {
+        let mut tmpa = Affine::default();
+        let mut pre_a: [Affine; ECMULT_TABLE_SIZE_A] = Default::default();
+        let mut pre_a_lam: [Affine; ECMULT_TABLE_SIZE_A] = Default::default();
+        let mut z = Field::default();
+        let mut wnaf_na_1 = [0i32; 130];
+        let mut wnaf_na_lam = [0i32; 130];
+        let mut wnaf_ng_1 = [0i32; 130];
+        let mut wnaf_ng_lam = [0i32; 130];
+
+        let (na_1, na_lam) = na.split_lambda();
+        let (ng_1, ng_lam) = ng.split_lambda();
+        let bits_na_1 = ecmult_wnaf(&mut wnaf_na_1, &na_1, WINDOW_A);
+        let bits_na_lam = ecmult_wnaf(&mut wnaf_na_lam, &na_lam, WINDOW_A);
+        let bits_ng_1 = ecmult_wnaf(&mut wnaf_ng_1, &ng_1, WINDOW_G);
+        let bits_ng_lam = ecmult_wnaf(&mut wnaf_ng_lam, &ng_lam, WINDOW_G);
+        let bits = bits_na_1.max(bits_na_lam).max(bits_ng_1).max(bits_ng_lam);
+
+        odd_multiples_table_globalz_windowa(&mut pre_a, &mut z, a);
+        for (lam, pre) in pre_a_lam.iter_mut().zip(pre_a.iter()) {
+            *lam = pre.mul_lambda();
+        }
+
+        r.set_infinity();
+        for i in (0..bits).rev() {
+            let mut n;
+            *r = r.double_var(None);
+
+            n = wnaf_na_1[i as usize];
+            if i < bits_na_1 && n != 0 {
+                table_get_ge(&mut tmpa, &pre_a, n, WINDOW_A);
+                *r = r.add_ge_var(&tmpa, None);
+            }
+            n = wnaf_na_lam[i as usize];
+            if i < bits_na_lam && n != 0 {
+                table_get_ge(&mut tmpa, &pre_a_lam, n, WINDOW_A);
+                *r = r.add_ge_var(&tmpa, None);
+            }
+            n = wnaf_ng_1[i as usize];
+            if i < bits_ng_1 && n != 0 {
+                table_get_ge_storage(&mut tmpa, &self.pre_g, n, WINDOW_G);
+                *r = r.add_zinv_var(&tmpa, &z);
+            }
+            n = wnaf_ng_lam[i as usize];
+            if i < bits_ng_lam && n != 0 {
+                table_get_ge_storage(&mut tmpa, &self.pre_g, n, WINDOW_G);
+                *r = r.add_zinv_var(&tmpa.mul_lambda(), &z);
+            }
+        }
+
+        if !r.is_infinity() {
+            r.z *= &z;
+        }
+    }
+
+    #[cfg(not(feature = "endomorphism"))]
     pub fn ecmult_const(&self, r: &mut Jacobian, a: &Affine, scalar: &Scalar) {
         const WNAF_SIZE: usize = (WNAF_BITS + (WINDOW_A - 1) - 1) / (WINDOW_A - 1);
 
@@ -343,6 +414,88 @@ impl ECMultContext {
         correction = correction.neg();
         *r = r.add_ge(&correction)
     }
+    /// Like the default `ecmult_const`, with the scalar split into two
+    /// halves of about 128 bits with the endomorphism, so that half as many
+    /// doublings are needed.
+    #[cfg(feature = "endomorphism")]
+    pub fn ecmult_const(&self, r: &mut Jacobian, a: &Affine, scalar: &Scalar) {
+        const WNAF_SIZE: usize = (128 + (WINDOW_A - 1) - 1) / (WINDOW_A - 1);
+
+        let mut tmpa = Affine::default();
+        let mut pre_a: [Affine; ECMULT_TABLE_SIZE_A] = Default::default();
+        let mut pre_a_lam: [Affine; ECMULT_TABLE_SIZE_A] = Default::default();
+        let mut z = Field::default();
+
+        let mut wnaf_1 = [0i32; 1 + WNAF_SIZE];
+        let mut wnaf_lam = [0i32; 1 + WNAF_SIZE];
+
+        let (q_1, q_lam) = scalar.split_lambda();
+        let skew_1 = ecmult_wnaf_const_bits(&mut wnaf_1, &q_1, WINDOW_A - 1, 128);
+        let skew_lam = ecmult_wnaf_const_bits(&mut wnaf_lam, &q_lam, WINDOW_A - 1, 128);
+
+        /* Calculate odd multiples of a, and of lambda.a, all brought to
+         * the same Z 'denominator'. */
+        r.set_ge(a);
+        odd_multiples_table_globalz_windowa(&mut pre_a, &mut z, r);
+        for i in 0..ECMULT_TABLE_SIZE_A {
+            pre_a[i].y.normalize_weak();
+            pre_a_lam[i] = pre_a[i].mul_lambda();
+        }
+
+        /* first loop iteration */
+        let i = wnaf_1[WNAF_SIZE];
+        debug_assert!(i != 0);
+        table_get_ge_const(&mut tmpa, &pre_a, i, WINDOW_A);
+        r.set_ge(&tmpa);
+        let i = wnaf_lam[WNAF_SIZE];
+        debug_assert!(i != 0);
+        table_get_ge_const(&mut tmpa, &pre_a_lam, i, WINDOW_A);
+        *r = r.add_ge(&tmpa);
+
+        /* remaining loop iterations */
+        for i in (0..WNAF_SIZE).rev() {
+            for _ in 0..(WINDOW_A - 1) {
+                let r2 = r.clone();
+                r.double_nonzero_in_place(&r2, None);
+            }
+
+            let n = wnaf_1[i];
+            table_get_ge_const(&mut tmpa, &pre_a, n, WINDOW_A);
+            debug_assert!(n != 0);
+            *r = r.add_ge(&tmpa);
+            let n = wnaf_lam[i];
+            table_get_ge_const(&mut tmpa, &pre_a_lam, n, WINDOW_A);
+            debug_assert!(n != 0);
+            *r = r.add_ge(&tmpa);
+        }
+
+        r.z *= &z;
+
+        /* Correct for wNAF skew */
+        let mut correction = a.clone();
+        let mut correction_1_stor: AffineStorage;
+        let mut correction_lam_stor: AffineStorage;
+        let a2_stor: AffineStorage;
+        let mut tmpj = Jacobian::default();
+        tmpj.set_ge(&correction);
+        tmpj = tmpj.double_var(None);
+        correction.set_gej(&tmpj);
+        correction_1_stor = a.clone().into();
+        correction_lam_stor = a.clone().into();
+        a2_stor = correction.into();
+
+        /* For odd numbers this is 2a (so replace it), for even ones a (so no-op) */
+        correction_1_stor.cmov(&a2_stor, skew_1 == 2);
+        correction_lam_stor.cmov(&a2_stor, skew_lam == 2);
+
+        /* Apply the correction */
+        correction = correction_1_stor.into();
+        correction = correction.neg();
+        *r = r.add_ge(&correction);
+        correction = correction_lam_stor.into();
+        correction = correction.neg().mul_lambda();
+        *r = r.add_ge(&correction)
+    }
 }
 
 impl ECMultGenContext {
@@ -370,3 +523,85 @@ impl ECMultGenContext {
         gnb.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ECMULT_CONTEXT, ECMULT_GEN_CONTEXT};
+    use rand::thread_rng;
+    use secp256k1::group::{Affine, Jacobian, AFFINE_G};
+    use secp256k1::Scalar;
+    use {PublicKey, SecretKey};
+
+    fn to_affine(p: &Jacobian) -> Affine {
+        let mut ret = Affine::default();
+        ret.set_gej_var(p);
+        ret
+    }
+
+    fn lambda() -> Scalar {
+        let mut ret = Scalar::default();
+        let mut b32 = [0u8; 32];
+        b32.copy_from_slice(&::hex::decode("5363AD4CC05C30E0A5261C028812645A122E22EA20816678DF02967C1B23BD72").unwrap());
+        ret.set_b32(&b32);
+        ret
+    }
+
+    fn is_128_bits(s: &Scalar) -> bool {
+        (8..16).all(|i| s.bits(16 * i, 16) == 0) || (8..16).all(|i| s.neg().bits(16 * i, 16) == 0)
+    }
+
+    #[test]
+    fn test_split_lambda() {
+        let mut one = Scalar::default();
+        one.set_int(1);
+        let mut scalars = vec![Scalar::default(), one, one.neg(), lambda(), lambda().neg()];
+        scalars.extend((0..64).map(|_| -> Scalar { SecretKey::random(&mut thread_rng()).into() }));
+        for k in scalars.iter() {
+            let (k1, k2) = k.split_lambda();
+            assert_eq!(k1 + k2 * lambda(), *k);
+            assert!(is_128_bits(&k1) && is_128_bits(&k2));
+        }
+    }
+
+    #[test]
+    fn test_mul_lambda() {
+        let mut r = Jacobian::default();
+        ECMULT_GEN_CONTEXT.ecmult_gen(&mut r, &lambda());
+        let mut g_lam = AFFINE_G.mul_lambda();
+        g_lam.x.normalize();
+        assert_eq!(to_affine(&r), g_lam);
+    }
+
+    #[test]
+    fn test_ecmult_consistency() {
+        let mut one = Scalar::default();
+        one.set_int(1);
+        let mut scalars = vec![one, one.neg(), lambda(), lambda().neg()];
+        scalars.extend((0..16).map(|_| -> Scalar { SecretKey::random(&mut thread_rng()).into() }));
+        for na in scalars.iter() {
+            let a: Affine = PublicKey::from_secret_key(&SecretKey::random(&mut thread_rng())).into();
+            let ng: Scalar = SecretKey::random(&mut thread_rng()).into();
+            let mut aj = Jacobian::default();
+            aj.set_ge(&a);
+
+            // na.A + ng.G against ecmult_const and ecmult_gen.
+            let mut r = Jacobian::default();
+            ECMULT_CONTEXT.ecmult(&mut r, &aj, na, &ng);
+            let mut expected = Jacobian::default();
+            ECMULT_CONTEXT.ecmult_const(&mut expected, &a, na);
+            let mut g = Jacobian::default();
+            ECMULT_GEN_CONTEXT.ecmult_gen(&mut g, &ng);
+            expected = expected.add_var(&g, None);
+            assert_eq!(to_affine(&r), to_affine(&expected));
+
+            // na.G with both variable-base multiplications.
+            let mut gj = Jacobian::default();
+            gj.set_ge(&AFFINE_G);
+            ECMULT_CONTEXT.ecmult(&mut r, &gj, &Scalar::default(), na);
+            ECMULT_CONTEXT.ecmult_const(&mut expected, &AFFINE_G, na);
+            ECMULT_GEN_CONTEXT.ecmult_gen(&mut g, na);
+            assert_eq!(to_affine(&r), to_affine(&g));
+            assert_eq!(to_affine(&expected), to_affine(&g));
+        }
+    }
+}
@@ -0,0 +1,608 @@
+use std::cmp::max;
+use std::mem::size_of;
+
+use super::{
+    ecmult_wnaf, odd_multiples_table, table_get_ge, table_get_ge_storage, ECMultContext, ECMULT_TABLE_SIZE_A,
+    WINDOW_A, WINDOW_G, WNAF_BITS,
+};
+use secp256k1::field::Field;
+use secp256k1::group::{set_table_gej_var, Affine, Jacobian, AFFINE_G};
+use secp256k1::Scalar;
+
+/// Minimum number of points for which Pippenger's algorithm is faster than
+/// Strauss'.
+pub const ECMULT_PIPPENGER_THRESHOLD: usize = 88;
+
+const PIPPENGER_MAX_BUCKET_WINDOW: usize = 12;
+const ECMULT_MAX_POINTS_PER_BATCH: usize = 5_000_000;
+
+/// Memory for `ecmult_multi_with_scratch`, reused from one call to the next
+/// and never larger than a given number of bytes.
+pub struct ScratchSpace {
+    max_size: usize,
+    affines: Vec<Affine>,
+    ints: Vec<i32>,
+    jacobians: Vec<Jacobian>,
+}
+
+impl ScratchSpace {
+    /// Create a scratch space that never holds more than `max_size` bytes.
+    pub fn new(max_size: usize) -> ScratchSpace {
+        ScratchSpace {
+            max_size,
+            affines: Vec::new(),
+            ints: Vec::new(),
+            jacobians: Vec::new(),
+        }
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Number of bytes currently held.
+    pub fn size(&self) -> usize {
+        Self::size_of(self.affines.capacity(), self.ints.capacity(), self.jacobians.capacity())
+    }
+
+    fn size_of(affines: usize, ints: usize, jacobians: usize) -> usize {
+        affines
+            .saturating_mul(size_of::<Affine>())
+            .saturating_add(ints.saturating_mul(size_of::<i32>()))
+            .saturating_add(jacobians.saturating_mul(size_of::<Jacobian>()))
+    }
+
+    /// Make room for the given numbers of elements, all set to their default
+    /// value. Returns false if they do not fit.
+    fn alloc(&mut self, affines: usize, ints: usize, jacobians: usize) -> bool {
+        if Self::size_of(affines, ints, jacobians) > self.max_size {
+            return false;
+        }
+        self.affines.clear();
+        self.ints.clear();
+        self.jacobians.clear();
+        let grown = Self::size_of(
+            max(affines, self.affines.capacity()),
+            max(ints, self.ints.capacity()),
+            max(jacobians, self.jacobians.capacity()),
+        );
+        if grown > self.max_size {
+            self.affines.shrink_to_fit();
+            self.ints.shrink_to_fit();
+            self.jacobians.shrink_to_fit();
+        }
+        self.affines.reserve_exact(affines);
+        self.ints.reserve_exact(ints);
+        self.jacobians.reserve_exact(jacobians);
+        self.affines.resize(affines, Affine::default());
+        self.ints.resize(ints, 0);
+        self.jacobians.resize(jacobians, Jacobian::default());
+        true
+    }
+}
+
+/// Number of words of a scalar in `wnaf_fixed` form with window `w`.
+fn wnaf_size(w: usize) -> usize {
+    WNAF_BITS.div_ceil(w)
+}
+
+/// Write a nonzero scalar as `wnaf_size(w)` words, each either zero or odd
+/// and between `-2^w` and `2^w`, such that `sum(wnaf[i].2^(w.i)) - skew` is
+/// the scalar, and return the skew, which is 0 or 1.
+fn wnaf_fixed(wnaf: &mut [i32], s: &Scalar, w: usize) -> i32 {
+    let size = wnaf_size(w);
+    debug_assert!(wnaf.len() == size);
+    if s.is_zero() {
+        for word in wnaf.iter_mut() {
+            *word = 0;
+        }
+        return 0;
+    }
+
+    let skew = if s.is_even() { 1 } else { 0 };
+    wnaf[0] = s.bits_var(0, w) as i32 + skew;
+    // The last window is shorter when w does not divide the number of bits.
+    let last_w = WNAF_BITS - (size - 1) * w;
+    let window = |pos: usize| s.bits_var(pos * w, if pos == size - 1 { last_w } else { w }) as i32;
+
+    // Skip the leading zero words.
+    let mut max_pos = size - 1;
+    while max_pos > 0 && window(max_pos) == 0 {
+        wnaf[max_pos] = 0;
+        max_pos -= 1;
+    }
+
+    for pos in 1..=max_pos {
+        let val = window(pos);
+        if val & 1 == 0 {
+            wnaf[pos - 1] -= 1 << w;
+            wnaf[pos] = val + 1;
+        } else {
+            wnaf[pos] = val;
+        }
+        // Replace 1 after a negative word (or -1 after a positive one) by
+        // zero. Only earlier words are changed, since the code above needs
+        // wnaf[pos - 1] to be odd.
+        if pos >= 2 && ((wnaf[pos - 1] == 1 && wnaf[pos - 2] < 0) || (wnaf[pos - 1] == -1 && wnaf[pos - 2] > 0)) {
+            if wnaf[pos - 1] == 1 {
+                wnaf[pos - 2] += 1 << w;
+            } else {
+                wnaf[pos - 2] -= 1 << w;
+            }
+            wnaf[pos - 1] = 0;
+        }
+    }
+    skew
+}
+
+/// Scratch space taken by each point in Strauss' algorithm: its table of odd
+/// multiples and its wNAF.
+fn strauss_point_size() -> usize {
+    ScratchSpace::size_of(ECMULT_TABLE_SIZE_A, WNAF_BITS, 0)
+}
+
+fn strauss_max_points(scratch: &ScratchSpace) -> usize {
+    scratch.max_size / strauss_point_size()
+}
+
+/// The best bucket window, that is the number of bits of a scalar handled by
+/// one set of buckets, for a number of points.
+fn pippenger_bucket_window(n: usize) -> usize {
+    match n {
+        0..=1 => 1,
+        2..=4 => 2,
+        5..=20 => 3,
+        21..=57 => 4,
+        58..=136 => 5,
+        137..=235 => 6,
+        236..=1260 => 7,
+        1261..=4420 => 9,
+        4421..=7880 => 10,
+        7881..=16050 => 11,
+        _ => PIPPENGER_MAX_BUCKET_WINDOW,
+    }
+}
+
+/// The largest number of points for which a bucket window is the best one.
+fn pippenger_bucket_window_inv(bucket_window: usize) -> usize {
+    match bucket_window {
+        1 => 1,
+        2 => 4,
+        3 => 20,
+        4 => 57,
+        5 => 136,
+        6 => 235,
+        7 | 8 => 1260,
+        9 => 4420,
+        10 => 7880,
+        11 => 16050,
+        _ => usize::MAX,
+    }
+}
+
+/// Scratch space taken by each point in Pippenger's algorithm: a copy of the
+/// point and its wNAF followed by its skew.
+fn pippenger_entry_size(bucket_window: usize) -> usize {
+    ScratchSpace::size_of(1, wnaf_size(bucket_window + 1) + 1, 0)
+}
+
+/// The largest number of points, besides G, that Pippenger's algorithm can
+/// use with a scratch space. Any smaller number of points fits too.
+fn pippenger_max_points(scratch: &ScratchSpace) -> usize {
+    let mut ret = 0;
+    for bucket_window in 1..=PIPPENGER_MAX_BUCKET_WINDOW {
+        let max_points = pippenger_bucket_window_inv(bucket_window);
+        let entry_size = pippenger_entry_size(bucket_window);
+        let overhead = ScratchSpace::size_of(0, 0, 1 << bucket_window) + entry_size;
+        if overhead > scratch.max_size {
+            break;
+        }
+        let n_points = ((scratch.max_size - overhead) / entry_size).min(max_points);
+        if n_points > ret {
+            ret = n_points;
+        }
+        // A larger window may fit more points, but then not every smaller
+        // number of points would be guaranteed to fit.
+        if n_points < max_points {
+            break;
+        }
+    }
+    ret
+}
+
+/// The size of batches of at most `max_points` points splitting `n` points
+/// evenly, or None if no point fits.
+fn batch_size(max_points: usize, n: usize) -> Option<usize> {
+    if max_points == 0 {
+        return None;
+    }
+    let max_points = max_points.min(ECMULT_MAX_POINTS_PER_BATCH);
+    let n_batches = n.div_ceil(max_points);
+    Some(n.div_ceil(n_batches))
+}
+
+impl ECMultContext {
+    /// Compute `sum(s_i.P_i) + ng.G` in variable time, with Strauss'
+    /// algorithm for fewer than `ECMULT_PIPPENGER_THRESHOLD` points and
+    /// Pippenger's above. Points at infinity and zero scalars are skipped.
+    /// Memory use grows with the number of points; use
+    /// `ecmult_multi_with_scratch` to bound it.
+    pub fn ecmult_multi(&self, r: &mut Jacobian, points: &[(Scalar, Affine)], ng: &Scalar) {
+        let mut scratch = ScratchSpace::new(usize::MAX);
+        self.ecmult_multi_with_scratch(r, &mut scratch, points, ng);
+    }
+
+    /// Compute `sum(s_i.P_i) + ng.G` like `ecmult_multi`, with memory taken
+    /// from a scratch space. The points are split into batches that fit in
+    /// it, and if not even one point fits, each one is multiplied on its own.
+    pub fn ecmult_multi_with_scratch(
+        &self,
+        r: &mut Jacobian,
+        scratch: &mut ScratchSpace,
+        points: &[(Scalar, Affine)],
+        ng: &Scalar,
+    ) {
+        if points.is_empty() {
+            self.ecmult_multi_simple(r, points, Some(ng));
+            return;
+        }
+
+        // Pippenger's algorithm needs less space than Strauss', so if it
+        // does not fit either will not.
+        let mut n_batch_points = match batch_size(pippenger_max_points(scratch), points.len()) {
+            Some(n) => n,
+            None => return self.ecmult_multi_simple(r, points, Some(ng)),
+        };
+        let pippenger = n_batch_points >= ECMULT_PIPPENGER_THRESHOLD;
+        if !pippenger {
+            n_batch_points = match batch_size(strauss_max_points(scratch), points.len()) {
+                Some(n) => n,
+                None => return self.ecmult_multi_simple(r, points, Some(ng)),
+            };
+        }
+
+        r.set_infinity();
+        for (i, batch) in points.chunks(n_batch_points).enumerate() {
+            let g = if i == 0 { Some(ng) } else { None };
+            let mut tmp = Jacobian::default();
+            let done = if pippenger {
+                self.ecmult_pippenger(&mut tmp, scratch, batch, g)
+            } else {
+                self.ecmult_strauss(&mut tmp, scratch, batch, g)
+            };
+            if !done {
+                self.ecmult_multi_simple(&mut tmp, batch, g);
+            }
+            *r = r.add_var(&tmp, None);
+        }
+    }
+
+    /// Compute `sum(s_i.P_i) + ng.G` with one `ecmult` per point, without
+    /// any scratch space.
+    fn ecmult_multi_simple(&self, r: &mut Jacobian, points: &[(Scalar, Affine)], ng: Option<&Scalar>) {
+        let zero = Scalar::default();
+        r.set_infinity();
+        if let Some(ng) = ng {
+            let mut gj = Jacobian::default();
+            gj.set_ge(&AFFINE_G);
+            self.ecmult(r, &gj, &zero, ng);
+        }
+        for (na, a) in points {
+            if a.is_infinity() || na.is_zero() {
+                continue;
+            }
+            let mut aj = Jacobian::default();
+            aj.set_ge(a);
+            let mut tmp = Jacobian::default();
+            self.ecmult(&mut tmp, &aj, na, &zero);
+            *r = r.add_var(&tmp, None);
+        }
+    }
+
+    /// Strauss' algorithm: every scalar is written in wNAF form, and one
+    /// chain of doublings is shared by all points, each with its own table
+    /// of odd multiples. Returns false if the points do not fit in the
+    /// scratch space.
+    fn ecmult_strauss(
+        &self,
+        r: &mut Jacobian,
+        scratch: &mut ScratchSpace,
+        points: &[(Scalar, Affine)],
+        ng: Option<&Scalar>,
+    ) -> bool {
+        if !scratch.alloc(points.len() * ECMULT_TABLE_SIZE_A, points.len() * WNAF_BITS, 0) {
+            return false;
+        }
+        let mut bits = 0;
+        let mut no = 0;
+        for (na, a) in points {
+            if a.is_infinity() || na.is_zero() {
+                continue;
+            }
+            let wnaf = &mut scratch.ints[no * WNAF_BITS..(no + 1) * WNAF_BITS];
+            let bits_na = ecmult_wnaf(wnaf, na, WINDOW_A);
+            if bits_na > bits {
+                bits = bits_na;
+            }
+
+            let mut aj = Jacobian::default();
+            aj.set_ge(a);
+            let mut prej: [Jacobian; ECMULT_TABLE_SIZE_A] = Default::default();
+            let mut zr: [Field; ECMULT_TABLE_SIZE_A] = Default::default();
+            odd_multiples_table(&mut prej, &mut zr, &aj);
+            let pre_a = &mut scratch.affines[no * ECMULT_TABLE_SIZE_A..(no + 1) * ECMULT_TABLE_SIZE_A];
+            set_table_gej_var(pre_a, &prej, &zr);
+            no += 1;
+        }
+
+        let mut wnaf_ng = [0i32; WNAF_BITS];
+        let mut bits_ng = 0;
+        if let Some(ng) = ng {
+            bits_ng = ecmult_wnaf(&mut wnaf_ng, ng, WINDOW_G);
+            if bits_ng > bits {
+                bits = bits_ng;
+            }
+        }
+
+        let wnafs = scratch.ints[..no * WNAF_BITS].chunks(WNAF_BITS);
+        let tables = scratch.affines[..no * ECMULT_TABLE_SIZE_A].chunks(ECMULT_TABLE_SIZE_A);
+        let mut tmpa = Affine::default();
+        r.set_infinity();
+        for i in (0..bits as usize).rev() {
+            *r = r.double_var(None);
+            for (wnaf, pre_a) in wnafs.clone().zip(tables.clone()) {
+                let n = wnaf[i];
+                if n != 0 {
+                    table_get_ge(&mut tmpa, pre_a, n, WINDOW_A);
+                    *r = r.add_ge_var(&tmpa, None);
+                }
+            }
+            let n = wnaf_ng[i];
+            if (i as i32) < bits_ng && n != 0 {
+                table_get_ge_storage(&mut tmpa, &self.pre_g, n, WINDOW_G);
+                *r = r.add_ge_var(&tmpa, None);
+            }
+        }
+        true
+    }
+
+    /// Pippenger's algorithm: every scalar is written in fixed-window wNAF
+    /// form, and for each window from the top, every point is added to the
+    /// bucket of its digit, then the buckets are summed with their weights
+    /// `1, 3, 5, ...` into the result. G is handled as one more point.
+    /// Returns false if the points do not fit in the scratch space.
+    fn ecmult_pippenger(
+        &self,
+        r: &mut Jacobian,
+        scratch: &mut ScratchSpace,
+        points: &[(Scalar, Affine)],
+        ng: Option<&Scalar>,
+    ) -> bool {
+        let bucket_window = pippenger_bucket_window(points.len());
+        let n_wnaf = wnaf_size(bucket_window + 1);
+        let stride = n_wnaf + 1;
+        let entries = points.len() + 1;
+        if !scratch.alloc(entries, entries * stride, 1 << bucket_window) {
+            return false;
+        }
+
+        let g = ng.map(|ng| (*ng, AFFINE_G));
+        let mut no = 0;
+        for (na, a) in g.iter().chain(points.iter()) {
+            if a.is_infinity() || na.is_zero() {
+                continue;
+            }
+            scratch.affines[no] = *a;
+            let wnaf = &mut scratch.ints[no * stride..(no + 1) * stride];
+            wnaf[n_wnaf] = wnaf_fixed(&mut wnaf[..n_wnaf], na, bucket_window + 1);
+            no += 1;
+        }
+
+        r.set_infinity();
+        let buckets = &mut scratch.jacobians;
+        for i in (0..n_wnaf).rev() {
+            for bucket in buckets.iter_mut() {
+                bucket.set_infinity();
+            }
+
+            for (a, wnaf) in scratch.affines[..no].iter().zip(scratch.ints.chunks(stride)) {
+                // Correct for the skew.
+                if i == 0 && wnaf[n_wnaf] != 0 {
+                    buckets[0] = buckets[0].add_ge_var(&a.neg(), None);
+                }
+                let n = wnaf[i];
+                if n > 0 {
+                    let idx = ((n - 1) / 2) as usize;
+                    buckets[idx] = buckets[idx].add_ge_var(a, None);
+                } else if n < 0 {
+                    let idx = (-(n + 1) / 2) as usize;
+                    buckets[idx] = buckets[idx].add_ge_var(&a.neg(), None);
+                }
+            }
+
+            for _ in 0..bucket_window {
+                *r = r.double_var(None);
+            }
+
+            // bucket[0] + 3.bucket[1] + 5.bucket[2] + ... is the running sum
+            // bucket[0] + bucket[1] + bucket[2] + ... plus twice
+            // bucket[1] + 2.bucket[2] + 3.bucket[3] + ..., where the doubling
+            // is done by deferring the last doubling of the window.
+            let mut running_sum = Jacobian::default();
+            running_sum.set_infinity();
+            for bucket in buckets[1..].iter().rev() {
+                running_sum = running_sum.add_var(bucket, None);
+                *r = r.add_var(&running_sum, None);
+            }
+            running_sum = running_sum.add_var(&buckets[0], None);
+            *r = r.double_var(None);
+            *r = r.add_var(&running_sum, None);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        batch_size, pippenger_bucket_window, pippenger_max_points, strauss_max_points, wnaf_fixed, wnaf_size,
+        ScratchSpace, ECMULT_PIPPENGER_THRESHOLD,
+    };
+    use ecmult::{ECMULT_CONTEXT, ECMULT_GEN_CONTEXT};
+    use rand::thread_rng;
+    use secp256k1::group::{Affine, Jacobian, AFFINE_INFINITY};
+    use secp256k1::Scalar;
+    use {PublicKey, SecretKey};
+
+    fn to_affine(p: &Jacobian) -> Affine {
+        let mut ret = Affine::default();
+        ret.set_gej_var(p);
+        ret
+    }
+
+    fn random_scalar() -> Scalar {
+        SecretKey::random(&mut thread_rng()).into()
+    }
+
+    fn random_point() -> Affine {
+        PublicKey::from_secret_key(&SecretKey::random(&mut thread_rng())).into()
+    }
+
+    fn random_points(n: usize) -> Vec<(Scalar, Affine)> {
+        (0..n).map(|_| (random_scalar(), random_point())).collect()
+    }
+
+    /// `sum(s_i.P_i) + ng.G` with one `ecmult` per point.
+    fn ecmult_naive(points: &[(Scalar, Affine)], ng: &Scalar) -> Affine {
+        let mut r = Jacobian::default();
+        ECMULT_GEN_CONTEXT.ecmult_gen(&mut r, ng);
+        for (s, p) in points {
+            if p.is_infinity() {
+                continue;
+            }
+            let mut pj = Jacobian::default();
+            pj.set_ge(p);
+            let mut t = Jacobian::default();
+            ECMULT_CONTEXT.ecmult(&mut t, &pj, s, &Scalar::default());
+            r = r.add_var(&t, None);
+        }
+        to_affine(&r)
+    }
+
+    fn small_scalar(n: i32) -> Scalar {
+        let mut ret = Scalar::default();
+        ret.set_int(n.unsigned_abs());
+        if n < 0 {
+            ret.neg()
+        } else {
+            ret
+        }
+    }
+
+    #[test]
+    fn test_wnaf_fixed() {
+        let mut one = Scalar::default();
+        one.set_int(1);
+        let scalars = [one, one.neg(), small_scalar(2), small_scalar(-2), random_scalar(), random_scalar().neg()];
+        for s in scalars.iter() {
+            for w in 2..14 {
+                let mut wnaf = vec![0i32; wnaf_size(w)];
+                let skew = wnaf_fixed(&mut wnaf, s, w);
+                let mut t = Scalar::default();
+                for word in wnaf.iter().rev() {
+                    assert!(*word == 0 || (word.abs() % 2 == 1 && word.abs() < 1 << w));
+                    t *= small_scalar(1 << w);
+                    t += small_scalar(*word);
+                }
+                t += small_scalar(-skew);
+                assert_eq!(t, *s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ecmult_multi() {
+        for n in 0..12 {
+            let points = random_points(n);
+            let ng = if n % 3 == 0 { Scalar::default() } else { random_scalar() };
+            let mut r = Jacobian::default();
+            ECMULT_CONTEXT.ecmult_multi(&mut r, &points, &ng);
+            assert_eq!(to_affine(&r), ecmult_naive(&points, &ng));
+        }
+    }
+
+    #[test]
+    fn test_ecmult_multi_edge_cases() {
+        let p = random_point();
+        let s = random_scalar();
+
+        // Zero scalars, infinity and cancelling terms.
+        let points = [(s, p), (Scalar::default(), random_point()), (random_scalar(), AFFINE_INFINITY), (s, p.neg())];
+        let mut r = Jacobian::default();
+        ECMULT_CONTEXT.ecmult_multi(&mut r, &points, &Scalar::default());
+        assert!(r.is_infinity());
+        let mut scratch = ScratchSpace::new(usize::MAX);
+        ECMULT_CONTEXT.ecmult_pippenger(&mut r, &mut scratch, &points, Some(&Scalar::default()));
+        assert!(r.is_infinity());
+
+        // Small and high scalars.
+        let one = small_scalar(1);
+        let points = [(one, p), (one.neg(), random_point()), (s.neg(), p), (small_scalar(2), p)];
+        let mut r = Jacobian::default();
+        ECMULT_CONTEXT.ecmult_multi(&mut r, &points, &one);
+        assert_eq!(to_affine(&r), ecmult_naive(&points, &one));
+        ECMULT_CONTEXT.ecmult_pippenger(&mut r, &mut scratch, &points, Some(&one));
+        assert_eq!(to_affine(&r), ecmult_naive(&points, &one));
+    }
+
+    #[test]
+    fn test_ecmult_strauss_pippenger() {
+        let mut scratch = ScratchSpace::new(usize::MAX);
+        let all = random_points(ECMULT_PIPPENGER_THRESHOLD + 60);
+        let ng = random_scalar();
+        for &n in [0, 1, 2, 5, 21, 58, ECMULT_PIPPENGER_THRESHOLD - 1, ECMULT_PIPPENGER_THRESHOLD, all.len()].iter() {
+            let points = &all[..n];
+            let expected = ecmult_naive(points, &ng);
+            let mut r = Jacobian::default();
+            assert!(ECMULT_CONTEXT.ecmult_strauss(&mut r, &mut scratch, points, Some(&ng)));
+            assert_eq!(to_affine(&r), expected);
+            assert!(ECMULT_CONTEXT.ecmult_pippenger(&mut r, &mut scratch, points, Some(&ng)));
+            assert_eq!(to_affine(&r), expected);
+            ECMULT_CONTEXT.ecmult_multi(&mut r, points, &ng);
+            assert_eq!(to_affine(&r), expected);
+        }
+    }
+
+    #[test]
+    fn test_ecmult_multi_scratch() {
+        let points = random_points(200);
+        let ng = random_scalar();
+        let expected = ecmult_naive(&points, &ng);
+
+        // No room for a single point, room for a few points with either
+        // algorithm, and room for Pippenger batches of more than 88 points.
+        for &max_size in [0, 1000, 5000, 20000, 30000].iter() {
+            let mut scratch = ScratchSpace::new(max_size);
+            let mut r = Jacobian::default();
+            ECMULT_CONTEXT.ecmult_multi_with_scratch(&mut r, &mut scratch, &points, &ng);
+            assert_eq!(to_affine(&r), expected);
+            assert!(scratch.size() <= max_size);
+            ECMULT_CONTEXT.ecmult_multi_with_scratch(&mut r, &mut scratch, &points[..10], &ng);
+            assert_eq!(to_affine(&r), ecmult_naive(&points[..10], &ng));
+            assert!(scratch.size() <= max_size);
+        }
+        assert_eq!(pippenger_max_points(&ScratchSpace::new(0)), 0);
+        assert!(pippenger_max_points(&ScratchSpace::new(30000)) >= ECMULT_PIPPENGER_THRESHOLD);
+        assert!(strauss_max_points(&ScratchSpace::new(5000)) > 0);
+    }
+
+    #[test]
+    fn test_batch_size() {
+        assert_eq!(batch_size(0, 10), None);
+        assert_eq!(batch_size(10, 10), Some(10));
+        assert_eq!(batch_size(10, 11), Some(6));
+        assert_eq!(batch_size(4, 9), Some(3));
+        assert_eq!(pippenger_bucket_window(88), 5);
+        assert_eq!(pippenger_bucket_window(100000), 12);
+    }
+}
@@ -0,0 +1,636 @@
+//! EIP-712 hashing and signing of typed structured data.
+//!
+//! A typed data document, as accepted by `eth_signTypedData_v4`, is parsed
+//! with `TypedData::from_json`. Its digest
+//! `keccak256(0x19 0x01 || domainSeparator || hashStruct(message))` is
+//! returned as a `Message` ready for signing.
+
+use ethereum::{keccak256, Address, Keccak256};
+use hex;
+use json::{Value, MAX_DEPTH};
+use secp256k1::{Error, Message, RecoverableSignature, SecretKey};
+use std::collections::{BTreeMap, BTreeSet};
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Fields of `EIP712Domain` in their canonical order, used when the
+/// document does not declare the domain type itself.
+const DOMAIN_FIELDS: [(&str, &str); 5] = [
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+    ("salt", "bytes32"),
+];
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A member of a struct type.
+pub struct Field {
+    pub name: String,
+    pub ty: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// An EIP-712 typed data document.
+pub struct TypedData {
+    pub types: BTreeMap<String, Vec<Field>>,
+    pub primary_type: String,
+    pub(crate) domain: Value,
+    pub(crate) message: Value,
+}
+
+impl TypedData {
+    /// Parse a JSON document with `types`, `primaryType`, `domain` and
+    /// `message` members.
+    pub fn from_json(s: &str) -> Result<TypedData, Error> {
+        let document = Value::parse(s)?;
+
+        let mut types = BTreeMap::new();
+        let declared = document
+            .get("types")
+            .and_then(Value::as_object)
+            .ok_or(Error::InvalidTypedData)?;
+        for (name, fields) in declared {
+            let fields = fields.as_array().ok_or(Error::InvalidTypedData)?;
+            let mut parsed = Vec::with_capacity(fields.len());
+            for field in fields {
+                parsed.push(Field {
+                    name: field.get("name").and_then(Value::as_str).ok_or(Error::InvalidTypedData)?.to_string(),
+                    ty: field.get("type").and_then(Value::as_str).ok_or(Error::InvalidTypedData)?.to_string(),
+                });
+            }
+            types.insert(name.clone(), parsed);
+        }
+
+        let primary_type = document
+            .get("primaryType")
+            .and_then(Value::as_str)
+            .ok_or(Error::InvalidTypedData)?
+            .to_string();
+        let domain = document.get("domain").cloned().ok_or(Error::InvalidTypedData)?;
+        let message = document.get("message").cloned().ok_or(Error::InvalidTypedData)?;
+
+        let mut typed_data = TypedData {
+            types,
+            primary_type,
+            domain,
+            message,
+        };
+        if !typed_data.types.contains_key(DOMAIN_TYPE) {
+            let fields = DOMAIN_FIELDS
+                .iter()
+                .filter(|f| typed_data.domain.get(f.0).is_some())
+                .map(|f| Field {
+                    name: f.0.to_string(),
+                    ty: f.1.to_string(),
+                })
+                .collect();
+            typed_data.types.insert(DOMAIN_TYPE.to_string(), fields);
+        }
+        if !typed_data.types.contains_key(&typed_data.primary_type) {
+            return Err(Error::InvalidTypedData);
+        }
+
+        Ok(typed_data)
+    }
+
+    fn fields(&self, ty: &str) -> Result<&[Field], Error> {
+        self.types
+            .get(ty)
+            .map(|fields| &fields[..])
+            .ok_or(Error::InvalidTypedData)
+    }
+
+    fn dependencies(&self, ty: &str, found: &mut BTreeSet<String>) -> Result<(), Error> {
+        for field in self.fields(ty)? {
+            let base = strip_arrays(&field.ty);
+            if self.types.contains_key(base) && !found.contains(base) {
+                found.insert(base.to_string());
+                self.dependencies(base, found)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `encodeType`: the struct signature followed by those of all
+    /// referenced struct types, sorted by name.
+    pub fn encode_type(&self, ty: &str) -> Result<String, Error> {
+        let mut deps = BTreeSet::new();
+        self.dependencies(ty, &mut deps)?;
+        deps.remove(ty);
+
+        let mut ret = String::new();
+        for name in Some(ty).into_iter().chain(deps.iter().map(|d| &d[..])) {
+            ret.push_str(name);
+            ret.push('(');
+            let fields: Vec<String> = self
+                .fields(name)?
+                .iter()
+                .map(|f| format!("{} {}", f.ty, f.name))
+                .collect();
+            ret.push_str(&fields.join(","));
+            ret.push(')');
+        }
+        Ok(ret)
+    }
+
+    /// `typeHash`: the Keccak-256 hash of `encodeType`.
+    pub fn type_hash(&self, ty: &str) -> Result<[u8; 32], Error> {
+        Ok(keccak256(self.encode_type(ty)?.as_bytes()))
+    }
+
+    /// `hashStruct`: the hash of the type hash and the encoded members.
+    pub(crate) fn hash_struct(&self, ty: &str, data: &Value) -> Result<[u8; 32], Error> {
+        self.hash_struct_at(ty, data, 0)
+    }
+
+    /// `hashStruct` of a value nested `depth` arrays and structs deep.
+    /// Values nested deeper than `MAX_DEPTH` are rejected, as they would
+    /// be by the parser.
+    fn hash_struct_at(&self, ty: &str, data: &Value, depth: usize) -> Result<[u8; 32], Error> {
+        if data.as_object().is_none() {
+            return Err(Error::InvalidTypedData);
+        }
+
+        let mut hasher = Keccak256::new();
+        hasher.update(&self.type_hash(ty)?);
+        for field in self.fields(ty)? {
+            let value = data.get(&field.name).ok_or(Error::InvalidTypedData)?;
+            hasher.update(&self.encode_value(&field.ty, value, depth + 1)?);
+        }
+        Ok(hasher.finalize())
+    }
+
+    fn encode_value(&self, ty: &str, value: &Value, depth: usize) -> Result<[u8; 32], Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::InvalidTypedData);
+        }
+
+        if ty.ends_with(']') {
+            let open = ty.rfind('[').ok_or(Error::InvalidTypedData)?;
+            let values = value.as_array().ok_or(Error::InvalidTypedData)?;
+            let size = &ty[(open + 1)..(ty.len() - 1)];
+            if !size.is_empty() && size.parse::<usize>().ok() != Some(values.len()) {
+                return Err(Error::InvalidTypedData);
+            }
+
+            let mut hasher = Keccak256::new();
+            for v in values {
+                hasher.update(&self.encode_value(&ty[..open], v, depth + 1)?);
+            }
+            return Ok(hasher.finalize());
+        }
+
+        if self.types.contains_key(ty) {
+            return self.hash_struct_at(ty, value, depth);
+        }
+
+        encode_atomic(ty, value)
+    }
+
+    /// The domain separator, `hashStruct(EIP712Domain, domain)`.
+    pub fn domain_separator(&self) -> Result<[u8; 32], Error> {
+        self.hash_struct(DOMAIN_TYPE, &self.domain)
+    }
+
+    /// `hashStruct` of the message under the primary type.
+    pub fn message_hash(&self) -> Result<[u8; 32], Error> {
+        self.hash_struct(&self.primary_type, &self.message)
+    }
+
+    /// The digest to be signed.
+    pub fn digest(&self) -> Result<Message, Error> {
+        let mut hasher = Keccak256::new();
+        hasher.update(&[0x19, 0x01]);
+        hasher.update(&self.domain_separator()?);
+        hasher.update(&self.message_hash()?);
+        Ok(Message::parse(&hasher.finalize()))
+    }
+
+    /// Sign the typed data.
+    pub fn sign(&self, seckey: &SecretKey) -> Result<RecoverableSignature, Error> {
+        Message::sign_recoverable(&self.digest()?, seckey)
+    }
+
+    /// Recover the address that signed the typed data.
+    pub fn recover(&self, signature: &RecoverableSignature) -> Result<Address, Error> {
+        signature
+            .recover(&self.digest()?)
+            .map(|pubkey| pubkey.to_eth_address())
+    }
+}
+
+fn strip_arrays(ty: &str) -> &str {
+    match ty.find('[') {
+        Some(i) => &ty[..i],
+        None => ty,
+    }
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, Error> {
+    let s = if s.starts_with("0x") || s.starts_with("0X") { &s[2..] } else { s };
+    hex::decode(s).or(Err(Error::InvalidTypedData))
+}
+
+/// Parse a decimal or `0x` hexadecimal integer, from a JSON number or
+/// string, into its 256-bit two's complement form.
+fn parse_integer(value: &Value) -> Result<([u8; 32], bool), Error> {
+    let text = match *value {
+        Value::Number(ref s) | Value::String(ref s) => s.trim(),
+        _ => return Err(Error::InvalidTypedData),
+    };
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+
+    let mut ret = [0u8; 32];
+    if digits.starts_with("0x") || digits.starts_with("0X") {
+        let mut hex_digits = digits[2..].to_string();
+        if hex_digits.len() % 2 == 1 {
+            hex_digits.insert(0, '0');
+        }
+        let bytes = hex::decode(&hex_digits).or(Err(Error::InvalidTypedData))?;
+        if bytes.is_empty() || bytes.len() > 32 {
+            return Err(Error::InvalidTypedData);
+        }
+        ret[(32 - bytes.len())..].copy_from_slice(&bytes);
+    } else {
+        if digits.is_empty() {
+            return Err(Error::InvalidTypedData);
+        }
+        for c in digits.chars() {
+            let mut carry = c.to_digit(10).ok_or(Error::InvalidTypedData)?;
+            for b in ret.iter_mut().rev() {
+                let v = u32::from(*b) * 10 + carry;
+                *b = v as u8;
+                carry = v >> 8;
+            }
+            if carry != 0 {
+                return Err(Error::InvalidTypedData);
+            }
+        }
+    }
+
+    if negative {
+        let mut carry = 1u16;
+        for b in ret.iter_mut().rev() {
+            let v = u16::from(!*b) + carry;
+            *b = v as u8;
+            carry = v >> 8;
+        }
+    }
+    Ok((ret, negative))
+}
+
+/// Check that the top `count` bits of `v` all equal `bit`.
+fn top_bits(v: &[u8; 32], count: usize, bit: bool) -> bool {
+    let fill = if bit { 0xff } else { 0x00 };
+    let full = count / 8;
+    if v[..full].iter().any(|b| *b != fill) {
+        return false;
+    }
+    let rest = count % 8;
+    if rest == 0 {
+        return true;
+    }
+    let mask = 0xffu8 << (8 - rest);
+    v[full] & mask == fill & mask
+}
+
+fn type_size(ty: &str, prefix: &str) -> Option<usize> {
+    if !ty.starts_with(prefix) {
+        return None;
+    }
+    let size = &ty[prefix.len()..];
+    if size.is_empty() {
+        return None;
+    }
+    size.parse::<usize>().ok()
+}
+
+fn encode_atomic(ty: &str, value: &Value) -> Result<[u8; 32], Error> {
+    let mut ret = [0u8; 32];
+    match ty {
+        "string" => {
+            let s = value.as_str().ok_or(Error::InvalidTypedData)?;
+            return Ok(keccak256(s.as_bytes()));
+        }
+        "bytes" => {
+            let s = value.as_str().ok_or(Error::InvalidTypedData)?;
+            return Ok(keccak256(&decode_hex(s)?));
+        }
+        "bool" => {
+            match *value {
+                Value::Bool(b) => ret[31] = b as u8,
+                _ => return Err(Error::InvalidTypedData),
+            }
+            return Ok(ret);
+        }
+        "address" => {
+            let s = value.as_str().ok_or(Error::InvalidTypedData)?;
+            let bytes = decode_hex(s)?;
+            if bytes.len() != 20 {
+                return Err(Error::InvalidTypedData);
+            }
+            ret[12..].copy_from_slice(&bytes);
+            return Ok(ret);
+        }
+        _ => (),
+    }
+
+    if let Some(size) = type_size(ty, "bytes") {
+        let s = value.as_str().ok_or(Error::InvalidTypedData)?;
+        let bytes = decode_hex(s)?;
+        if size == 0 || size > 32 || bytes.len() != size {
+            return Err(Error::InvalidTypedData);
+        }
+        ret[..size].copy_from_slice(&bytes);
+        return Ok(ret);
+    }
+
+    if let Some(bits) = type_size(ty, "uint") {
+        if bits == 0 || bits > 256 || bits % 8 != 0 {
+            return Err(Error::InvalidTypedData);
+        }
+        let (v, negative) = parse_integer(value)?;
+        if negative || !top_bits(&v, 256 - bits, false) {
+            return Err(Error::InvalidTypedData);
+        }
+        return Ok(v);
+    }
+
+    if let Some(bits) = type_size(ty, "int") {
+        if bits == 0 || bits > 256 || bits % 8 != 0 {
+            return Err(Error::InvalidTypedData);
+        }
+        let (v, negative) = parse_integer(value)?;
+        // The sign bit and everything above it must agree.
+        if !top_bits(&v, 256 - bits + 1, negative) {
+            return Err(Error::InvalidTypedData);
+        }
+        return Ok(v);
+    }
+
+    Err(Error::InvalidTypedData)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethereum::{keccak256, Address};
+    use hex;
+    use json::{Value, MAX_DEPTH};
+    use super::TypedData;
+    use {Error, PublicKey, SecretKey};
+
+    // The `Mail` example from the EIP-712 specification.
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    fn typed_data(types: &str, primary_type: &str, message: &str) -> TypedData {
+        TypedData::from_json(&format!(
+            r#"{{ "types": {}, "primaryType": "{}", "domain": {{ "name": "Test" }}, "message": {} }}"#,
+            types, primary_type, message
+        )).unwrap()
+    }
+
+    #[test]
+    fn test_mail() {
+        let typed_data = TypedData::from_json(MAIL).unwrap();
+
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(typed_data.type_hash("Mail").unwrap()),
+            "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"
+        );
+        assert_eq!(
+            hex::encode(typed_data.domain_separator().unwrap()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            hex::encode(typed_data.message_hash().unwrap()),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            hex::encode(typed_data.digest().unwrap().serialize()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+
+        let seckey = SecretKey::parse(&keccak256(b"cow")).unwrap();
+        let address = PublicKey::from_secret_key(&seckey).to_eth_address();
+        assert_eq!(address, Address::from_hex("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826").unwrap());
+
+        let sig = typed_data.sign(&seckey).unwrap();
+        assert_eq!(sig.ethereum_v(None), Ok(28));
+        assert_eq!(
+            hex::encode(&sig.serialize()[..64]),
+            "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d\
+             07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562"
+        );
+        assert_eq!(typed_data.recover(&sig).unwrap(), address);
+    }
+
+    #[test]
+    fn test_implicit_domain_type() {
+        // Without an `EIP712Domain` declaration the type is derived from
+        // the domain members in canonical order.
+        let explicit = TypedData::from_json(MAIL).unwrap();
+        let json = MAIL.replacen(
+            r#""EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],"#,
+            "",
+            1,
+        );
+        assert!(json != MAIL);
+        let implicit = TypedData::from_json(&json).unwrap();
+        assert_eq!(implicit, explicit);
+        assert_eq!(implicit.digest(), explicit.digest());
+    }
+
+    #[test]
+    fn test_encode_type_dependencies() {
+        let typed_data = typed_data(
+            r#"{
+                "Transaction": [
+                    { "name": "to", "type": "Zebra[]" },
+                    { "name": "asset", "type": "Asset" }
+                ],
+                "Zebra": [{ "name": "asset", "type": "Asset" }],
+                "Asset": [{ "name": "id", "type": "uint8" }]
+            }"#,
+            "Transaction",
+            r#"{ "to": [], "asset": { "id": 1 } }"#,
+        );
+        assert_eq!(
+            typed_data.encode_type("Transaction").unwrap(),
+            "Transaction(Zebra[] to,Asset asset)Asset(uint8 id)Zebra(Asset asset)"
+        );
+        assert_eq!(typed_data.encode_type("Asset").unwrap(), "Asset(uint8 id)");
+        assert_eq!(typed_data.encode_type("Missing"), Err(Error::InvalidTypedData));
+    }
+
+    #[test]
+    fn test_atomic_values() {
+        let types = r#"{ "T": [{ "name": "v", "type": "TYPE" }] }"#;
+        let encode = |ty: &str, v: &str| -> Result<[u8; 32], Error> {
+            let typed_data = typed_data(&types.replace("TYPE", ty), "T", &format!(r#"{{ "v": {} }}"#, v));
+            let value = typed_data.message.get("v").unwrap();
+            typed_data.encode_value(ty, value, 0)
+        };
+        let word = |s: &str| {
+            let mut ret = [0u8; 32];
+            ret.copy_from_slice(&hex::decode(s).unwrap());
+            ret
+        };
+
+        assert_eq!(encode("bool", "true").unwrap()[31], 1);
+        assert_eq!(encode("bool", "false"), Ok([0u8; 32]));
+        assert_eq!(encode("uint8", "255").unwrap()[31], 0xff);
+        assert_eq!(encode("uint8", "\"0xff\"").unwrap()[31], 0xff);
+        assert_eq!(encode("uint8", "256"), Err(Error::InvalidTypedData));
+        assert_eq!(encode("uint8", "-1"), Err(Error::InvalidTypedData));
+        assert_eq!(encode("uint256", "\"-0x1\""), Err(Error::InvalidTypedData));
+        assert_eq!(
+            encode("uint256", "\"115792089237316195423570985008687907853269984665640564039457584007913129639935\""),
+            Ok([0xff; 32])
+        );
+        assert_eq!(
+            encode("uint256", "115792089237316195423570985008687907853269984665640564039457584007913129639936"),
+            Err(Error::InvalidTypedData)
+        );
+        assert_eq!(encode("int8", "-128"), Ok(word("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff80")));
+        assert_eq!(encode("int8", "127").unwrap()[31], 0x7f);
+        assert_eq!(encode("int8", "128"), Err(Error::InvalidTypedData));
+        assert_eq!(encode("int8", "-129"), Err(Error::InvalidTypedData));
+        assert_eq!(encode("int256", "-1"), Ok([0xff; 32]));
+        assert_eq!(encode("uint7", "1"), Err(Error::InvalidTypedData));
+        assert_eq!(encode("uint1.5", "1"), Err(Error::InvalidTypedData));
+
+        assert_eq!(
+            encode("address", "\"0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826\""),
+            Ok(word("000000000000000000000000cd2a3d9f938e13cd947ec05abc7fe734df8dd826"))
+        );
+        assert_eq!(encode("address", "\"0x1234\""), Err(Error::InvalidTypedData));
+        assert_eq!(
+            encode("bytes4", "\"0xdeadbeef\""),
+            Ok(word("deadbeef00000000000000000000000000000000000000000000000000000000"))
+        );
+        assert_eq!(encode("bytes4", "\"0xdead\""), Err(Error::InvalidTypedData));
+        assert_eq!(encode("bytes33", "\"0x00\""), Err(Error::InvalidTypedData));
+        assert_eq!(encode("bytes", "\"0xdeadbeef\""), Ok(keccak256(&[0xde, 0xad, 0xbe, 0xef])));
+        assert_eq!(encode("string", "\"Hello\""), Ok(keccak256(b"Hello")));
+        assert_eq!(encode("string", "1"), Err(Error::InvalidTypedData));
+        assert_eq!(encode("float", "1"), Err(Error::InvalidTypedData));
+    }
+
+    #[test]
+    fn test_arrays() {
+        let typed_data = typed_data(
+            r#"{
+                "Group": [
+                    { "name": "members", "type": "Person[]" },
+                    { "name": "scores", "type": "uint16[2][]" }
+                ],
+                "Person": [{ "name": "name", "type": "string" }]
+            }"#,
+            "Group",
+            r#"{ "members": [{ "name": "a" }, { "name": "b" }], "scores": [[1, 2], [3, 4]] }"#,
+        );
+
+        let members = typed_data.message.get("members").unwrap();
+        let mut concatenated = Vec::new();
+        for m in members.as_array().unwrap() {
+            concatenated.extend_from_slice(&typed_data.hash_struct("Person", m).unwrap());
+        }
+        assert_eq!(typed_data.encode_value("Person[]", members, 0), Ok(keccak256(&concatenated)));
+
+        let scores = typed_data.message.get("scores").unwrap();
+        let mut rows = Vec::new();
+        for row in &[[1u8, 2], [3, 4]] {
+            let mut words = [0u8; 64];
+            words[31] = row[0];
+            words[63] = row[1];
+            rows.extend_from_slice(&keccak256(&words));
+        }
+        assert_eq!(typed_data.encode_value("uint16[2][]", scores, 0), Ok(keccak256(&rows)));
+        assert_eq!(typed_data.encode_value("uint16[3][]", scores, 0), Err(Error::InvalidTypedData));
+        assert_eq!(typed_data.encode_value("uint16[]", scores, 0), Err(Error::InvalidTypedData));
+        assert!(typed_data.digest().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_documents() {
+        assert_eq!(TypedData::from_json("[]"), Err(Error::InvalidTypedData));
+        assert_eq!(TypedData::from_json("{"), Err(Error::InvalidTypedData));
+        // Unknown primary type.
+        assert_eq!(
+            TypedData::from_json(r#"{ "types": {}, "primaryType": "Mail", "domain": {}, "message": {} }"#),
+            Err(Error::InvalidTypedData)
+        );
+        // Missing message member.
+        let typed_data = typed_data(r#"{ "T": [{ "name": "v", "type": "uint8" }] }"#, "T", "{}");
+        assert_eq!(typed_data.message_hash(), Err(Error::InvalidTypedData));
+        assert_eq!(typed_data.digest(), Err(Error::InvalidTypedData));
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let mut typed_data = typed_data(r#"{ "Node": [{ "name": "next", "type": "Node[]" }] }"#, "Node", r#"{ "next": [] }"#);
+        // Each level is a struct holding a one element array, two levels of nesting.
+        let nested = |levels: usize| {
+            let mut value = Value::Object(vec![("next".to_string(), Value::Array(vec![]))]);
+            for _ in 0..levels {
+                value = Value::Object(vec![("next".to_string(), Value::Array(vec![value]))]);
+            }
+            value
+        };
+
+        typed_data.message = nested(MAX_DEPTH / 2 - 1);
+        assert!(typed_data.message_hash().is_ok());
+        typed_data.message = nested(MAX_DEPTH / 2);
+        assert_eq!(typed_data.message_hash(), Err(Error::InvalidTypedData));
+
+        let deep = format!(r#"{{ "next": {}{{ "next": [] }}{} }}"#, "[{ \"next\": [".repeat(1000), "] }]".repeat(1000));
+        let document = format!(
+            r#"{{ "types": {{ "Node": [{{ "name": "next", "type": "Node[]" }}] }}, "primaryType": "Node", "domain": {{}}, "message": {} }}"#,
+            deep
+        );
+        assert_eq!(TypedData::from_json(&document), Err(Error::InvalidTypedData));
+    }
+}
@@ -0,0 +1,172 @@
+//! Keccak-256, the original Keccak submission as used by Ethereum. This
+//! differs from the standardized SHA3-256 only in its padding byte.
+
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+const ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI_LANES: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// The Keccak-f[1600] permutation.
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS.iter() {
+        // Theta
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[5 * y + x] ^= d;
+            }
+        }
+
+        // Rho and pi
+        let mut last = a[1];
+        for (rotation, lane) in ROTATIONS.iter().zip(PI_LANES.iter()) {
+            let tmp = a[*lane];
+            a[*lane] = last.rotate_left(*rotation);
+            last = tmp;
+        }
+
+        // Chi
+        for y in 0..5 {
+            let mut row = [0u64; 5];
+            row.copy_from_slice(&a[(5 * y)..(5 * y + 5)]);
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        a[0] ^= *rc;
+    }
+}
+
+#[derive(Clone)]
+/// Incremental Keccak-256 hasher.
+pub struct Keccak256 {
+    state: [u64; 25],
+    buffer: [u8; RATE],
+    buffered: usize,
+}
+
+impl Default for Keccak256 {
+    fn default() -> Keccak256 {
+        Keccak256 {
+            state: [0u64; 25],
+            buffer: [0u8; RATE],
+            buffered: 0,
+        }
+    }
+}
+
+impl Keccak256 {
+    pub fn new() -> Keccak256 {
+        Keccak256::default()
+    }
+
+    fn absorb_buffer(&mut self) {
+        for (lane, chunk) in self.state.iter_mut().zip(self.buffer.chunks(8)) {
+            *lane ^= u64::from(chunk[0])
+                | u64::from(chunk[1]) << 8
+                | u64::from(chunk[2]) << 16
+                | u64::from(chunk[3]) << 24
+                | u64::from(chunk[4]) << 32
+                | u64::from(chunk[5]) << 40
+                | u64::from(chunk[6]) << 48
+                | u64::from(chunk[7]) << 56;
+        }
+        keccak_f(&mut self.state);
+        self.buffered = 0;
+    }
+
+    /// Feed more data into the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            let take = ::std::cmp::min(RATE - self.buffered, data.len());
+            self.buffer[self.buffered..(self.buffered + take)].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered == RATE {
+                self.absorb_buffer();
+            }
+        }
+    }
+
+    /// Finish hashing and return the 32-byte digest.
+    pub fn finalize(mut self) -> [u8; 32] {
+        for b in self.buffer[self.buffered..].iter_mut() {
+            *b = 0;
+        }
+        self.buffer[self.buffered] ^= 0x01;
+        self.buffer[RATE - 1] ^= 0x80;
+        self.absorb_buffer();
+
+        let mut ret = [0u8; 32];
+        for (chunk, lane) in ret.chunks_mut(8).zip(self.state.iter()) {
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (lane >> (8 * i)) as u8;
+            }
+        }
+        ret
+    }
+}
+
+/// Compute the Keccak-256 digest of `data`.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use hex;
+    use super::{keccak256, Keccak256};
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            hex::encode(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex::encode(keccak256(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+        assert_eq!(
+            hex::encode(keccak256(b"The quick brown fox jumps over the lazy dog")),
+            "4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15"
+        );
+    }
+
+    #[test]
+    fn test_incremental() {
+        // Cross the 136-byte rate boundary in uneven pieces.
+        let data: Vec<u8> = (0..500u32).map(|i| i as u8).collect();
+        let expected = keccak256(&data);
+        for step in &[1, 7, 135, 136, 137, 499] {
+            let mut hasher = Keccak256::new();
+            for chunk in data.chunks(*step) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), expected);
+        }
+    }
+}
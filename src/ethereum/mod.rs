@@ -0,0 +1,228 @@
+//! Ethereum helpers: Keccak-256, address derivation with EIP-55 checksums,
+//! EIP-191 `personal_sign` message hashing and `ecrecover`.
+
+mod keccak;
+
+pub use self::keccak::{keccak256, Keccak256};
+
+use ecmult::ECMULT_CONTEXT;
+use hex;
+use secp256k1::{Error, Message, PublicKey, RecoverableSignature, RecoveryId, Scalar, SecretKey};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// A 20-byte Ethereum account address.
+pub struct Address(pub [u8; 20]);
+
+impl Address {
+    /// Parse a hex address, with or without the `0x` prefix. Mixed-case
+    /// input must carry a valid EIP-55 checksum.
+    pub fn from_hex(h: &str) -> Result<Address, Error> {
+        let h = if h.starts_with("0x") || h.starts_with("0X") { &h[2..] } else { h };
+        if h.len() != 40 {
+            return Err(Error::InvalidHex);
+        }
+        let bytes = hex::decode(h).or(Err(Error::InvalidHex))?;
+        let address = Address(*array_ref!(bytes, 0, 20));
+
+        let lower = h.to_lowercase();
+        let upper = h.to_uppercase();
+        if h != lower && h != upper && h != &address.to_checksum()[2..] {
+            return Err(Error::InvalidChecksum);
+        }
+        Ok(address)
+    }
+
+    /// The address as `0x`-prefixed hex with EIP-55 mixed-case checksum.
+    pub fn to_checksum(&self) -> String {
+        let lower = hex::encode(self.0);
+        let hash = keccak256(lower.as_bytes());
+
+        let mut ret = String::with_capacity(42);
+        ret.push_str("0x");
+        for (i, c) in lower.chars().enumerate() {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                ret.extend(c.to_uppercase());
+            } else {
+                ret.push(c);
+            }
+        }
+        ret
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_checksum())
+    }
+}
+
+impl PublicKey {
+    /// The Ethereum address of this key: the last 20 bytes of the Keccak-256
+    /// hash of the uncompressed key without its tag byte.
+    pub fn to_eth_address(&self) -> Address {
+        let hash = keccak256(&self.serialize()[1..]);
+        Address(*array_ref!(hash, 12, 20))
+    }
+}
+
+/// Hash a message the way `personal_sign` and `eth_sign` do (EIP-191
+/// version 0x45): `keccak256("\x19Ethereum Signed Message:\n" || len || msg)`.
+pub fn hash_message(msg: &[u8]) -> Message {
+    let mut hasher = Keccak256::new();
+    hasher.update(b"\x19Ethereum Signed Message:\n");
+    hasher.update(msg.len().to_string().as_bytes());
+    hasher.update(msg);
+    Message::parse(&hasher.finalize())
+}
+
+/// Sign a message with `personal_sign` semantics.
+pub fn personal_sign(msg: &[u8], seckey: &SecretKey) -> Result<RecoverableSignature, Error> {
+    Message::sign_recoverable(&hash_message(msg), seckey)
+}
+
+/// Recover the address that produced a `personal_sign` signature.
+pub fn personal_recover(msg: &[u8], signature: &RecoverableSignature) -> Result<Address, Error> {
+    signature
+        .recover(&hash_message(msg))
+        .map(|pubkey| pubkey.to_eth_address())
+}
+
+/// Recover the signer's address from a 32-byte hash and a signature split
+/// into `v`, `r` and `s`, like the `ecrecover` precompile. `v` may be 27/28,
+/// the raw recovery id, or an EIP-155 value.
+pub fn ecrecover(hash: &[u8; 32], v: u64, r: &[u8; 32], s: &[u8; 32]) -> Result<Address, Error> {
+    let (recovery_id, _) = RecoveryId::from_ethereum_v(v)?;
+
+    let mut sigr = Scalar::default();
+    let mut sigs = Scalar::default();
+    if sigr.set_b32(r) || sigs.set_b32(s) {
+        return Err(Error::InvalidSignature);
+    }
+    let message = Message::parse(hash);
+
+    ECMULT_CONTEXT
+        .recover_raw(&sigr, &sigs, recovery_id.serialize(), &message.0)
+        .map(|p| PublicKey(p).to_eth_address())
+}
+
+#[cfg(test)]
+mod tests {
+    use hex;
+    use super::{ecrecover, hash_message, keccak256, personal_recover, personal_sign, Address};
+    use {Error, Message, PublicKey, RecoverableSignature, SecretKey};
+
+    fn decode32(s: &str) -> [u8; 32] {
+        let mut ret = [0u8; 32];
+        ret.copy_from_slice(&hex::decode(s).unwrap());
+        ret
+    }
+
+    #[test]
+    fn test_eip55_checksum() {
+        let vectors = [
+            // All caps
+            "0x52908400098527886E0F7030069857D2E4169EE7",
+            "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+            // All lower
+            "0xde709f2102306220921060314715629080e2fb77",
+            "0x27b1fdb04752bbc536007a920d24acb045561c26",
+            // Normal
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ];
+        for v in vectors.iter() {
+            let address = Address::from_hex(v).unwrap();
+            assert_eq!(&address.to_checksum(), v);
+            assert_eq!(&format!("{}", address), v);
+        }
+
+        assert_eq!(
+            Address::from_hex("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap(),
+            Address::from_hex("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap()
+        );
+        assert_eq!(
+            Address::from_hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(Error::InvalidChecksum)
+        );
+        assert_eq!(Address::from_hex("0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea"), Err(Error::InvalidHex));
+        assert_eq!(Address::from_hex("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaeg"), Err(Error::InvalidHex));
+    }
+
+    #[test]
+    fn test_to_eth_address() {
+        // go-ethereum crypto_test.go
+        let seckey = SecretKey::from_hex("289c2857d4598e37fb9647507e47a309d6133539bf21a8b9cb6df88fd5232032").unwrap();
+        let address = PublicKey::from_secret_key(&seckey).to_eth_address();
+        assert_eq!(hex::encode(address.0), "970e8128ab834e8eac17ab8e3812f010678cf791");
+
+        // web3.js / ethers wallet example key
+        let seckey = SecretKey::from_hex("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+        let address = PublicKey::from_secret_key(&seckey).to_eth_address();
+        assert_eq!(address.to_checksum(), "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23");
+    }
+
+    #[test]
+    fn test_personal_sign() {
+        let seckey = SecretKey::from_hex("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+
+        let hash = hash_message(b"Some data");
+        assert_eq!(
+            hex::encode(hash.serialize()),
+            "1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655"
+        );
+
+        let sig = personal_sign(b"Some data", &seckey).unwrap();
+        assert_eq!(
+            hex::encode(&sig.serialize_ethereum().unwrap()[..]),
+            "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c"
+        );
+        assert_eq!(
+            personal_recover(b"Some data", &sig).unwrap(),
+            PublicKey::from_secret_key(&seckey).to_eth_address()
+        );
+        assert!(personal_recover(b"Other data", &sig).unwrap() != PublicKey::from_secret_key(&seckey).to_eth_address());
+    }
+
+    #[test]
+    fn test_transaction_signature() {
+        // ethers-rs private_key.rs signing example
+        let seckey = SecretKey::from_hex("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+        let tx = hex::decode("e9808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca0080018080").unwrap();
+        let hash = keccak256(&tx);
+
+        let sig = Message::sign_recoverable(&Message::parse(&hash), &seckey).unwrap();
+        assert_eq!(
+            hex::encode(&sig.serialize()[..64]),
+            "c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68"
+        );
+        assert_eq!(sig.recovery_id.serialize(), 0);
+        assert_eq!(sig.ethereum_v(Some(1)), Ok(37));
+    }
+
+    #[test]
+    fn test_ecrecover() {
+        // go-ethereum signature_test.go
+        let hash = decode32("ce0677bb30baa8cf067c88db9811f4333d131bf8bcf12fe7065d211dce971008");
+        let sig = hex::decode("90f27b8b488db00b00606796d2987f6a5f59ae62ea05effe84fef5b8b0e549984a691139ad57a3f0b906637673aa2f63d1f55cb1a69199d4009eea23ceaddc9301").unwrap();
+        let pubkey = PublicKey::from_hex("04e32df42865e97135acfb65f3bae71bdc86f4d49150ad6a440b6f15878109880a0a2b2667f7e725ceea70c673093bf67663e0312623c8e091b13cf2c0f11ef652").unwrap();
+
+        let r = array_ref!(sig, 0, 32);
+        let s = array_ref!(sig, 32, 32);
+        let address = ecrecover(&hash, 27 + sig[64] as u64, r, s).unwrap();
+        assert_eq!(address, pubkey.to_eth_address());
+        assert_eq!(ecrecover(&hash, sig[64] as u64, r, s).unwrap(), address);
+        assert_eq!(ecrecover(&hash, 37 + sig[64] as u64, r, s).unwrap(), address);
+
+        let mut raw = [0u8; 65];
+        raw.copy_from_slice(&sig);
+        let recovered = RecoverableSignature::parse(&raw).unwrap().recover(&Message::parse(&hash)).unwrap();
+        assert_eq!(recovered, pubkey);
+
+        assert_eq!(ecrecover(&hash, 29, r, s), Err(Error::InvalidRecoveryId));
+        assert_eq!(ecrecover(&hash, 27, r, &[0xff; 32]), Err(Error::InvalidSignature));
+    }
+}
@@ -0,0 +1,451 @@
+use std::collections::BTreeMap;
+
+use super::keys::{commit_polynomial, evaluate_commitment, evaluate_polynomial};
+use super::{schnorr_check, Ciphersuite, Identifier, KeyPackage, PublicKeyPackage, Signature};
+use rand::Rng;
+use secp256k1::group::Jacobian;
+use secp256k1::{Error, PublicKey, Scalar, SecretKey};
+use util::{mul_gen, to_affine};
+
+/// A participant's secret state after the first round of the distributed
+/// key generation: its secret polynomial. It is cleared when dropped.
+pub struct Round1Secret {
+    identifier: Identifier,
+    polynomial: Vec<Scalar>,
+    commitment: Vec<PublicKey>,
+    max_signers: u16,
+}
+
+/// What a participant broadcasts in the first round: the commitment to its
+/// secret polynomial, and a Schnorr proof of knowledge of the constant term
+/// so that no participant can choose its commitment as a function of the
+/// others'.
+#[derive(Debug, Clone)]
+pub struct Round1Package {
+    pub commitment: Vec<PublicKey>,
+    pub proof_of_knowledge: Signature,
+}
+
+/// A participant's secret state after the second round: its own share
+/// `f_i(i)` and commitment. It is cleared when dropped.
+pub struct Round2Secret {
+    identifier: Identifier,
+    share: Scalar,
+    commitment: Vec<PublicKey>,
+    max_signers: u16,
+}
+
+/// The share `f_i(j)` participant `i` sends to participant `j` over a
+/// private channel in the second round.
+#[derive(Debug, Clone)]
+pub struct Round2Package {
+    pub signing_share: Scalar,
+}
+
+impl Drop for Round1Secret {
+    fn drop(&mut self) {
+        for a in self.polynomial.iter_mut() {
+            a.clear();
+        }
+    }
+}
+
+impl Drop for Round2Secret {
+    fn drop(&mut self) {
+        self.share.clear();
+    }
+}
+
+/// Start a distributed key generation for participants `1..=max_signers`,
+/// any `min_signers` of which will be able to sign: pick a random secret
+/// polynomial and commit to it. The package goes to every other participant
+/// and the secret is kept for `dkg_round2`. Fails unless
+/// `2 <= min_signers <= max_signers`.
+pub fn dkg_round1<R: Rng>(
+    suite: Ciphersuite,
+    rng: &mut R,
+    identifier: Identifier,
+    min_signers: u16,
+    max_signers: u16,
+) -> Result<(Round1Secret, Round1Package), Error> {
+    if min_signers < 2 || min_signers > max_signers {
+        return Err(Error::InvalidThreshold);
+    }
+    let polynomial: Vec<Scalar> = (0..min_signers).map(|_| SecretKey::random(rng).0).collect();
+    let secret = Round1Secret {
+        identifier,
+        commitment: commit_polynomial(&polynomial),
+        polynomial,
+        max_signers,
+    };
+
+    let mut k = SecretKey::random(rng).0;
+    let proof_of_knowledge = prove_knowledge(suite, &secret, &k);
+    k.clear();
+    let package = Round1Package {
+        commitment: secret.commitment.clone(),
+        proof_of_knowledge,
+    };
+    Ok((secret, package))
+}
+
+/// Check the first-round packages of all other participants, keyed by their
+/// identifiers, and compute the shares to send to each of them.
+///
+/// Fails if a package is missing or unexpected, its commitment has the wrong
+/// length, or its proof of knowledge is invalid.
+pub fn dkg_round2(
+    suite: Ciphersuite,
+    secret: Round1Secret,
+    round1_packages: &BTreeMap<Identifier, Round1Package>,
+) -> Result<(Round2Secret, BTreeMap<Identifier, Round2Package>), Error> {
+    check_participants(&secret.identifier, secret.max_signers, round1_packages.keys())?;
+
+    let mut packages = BTreeMap::new();
+    for (id, package) in round1_packages.iter() {
+        if package.commitment.len() != secret.commitment.len() {
+            return Err(Error::InvalidThreshold);
+        }
+        let r = &package.proof_of_knowledge.r;
+        let c = proof_challenge(suite, id, &package.commitment[0], r);
+        if !schnorr_check(r, &package.proof_of_knowledge.z, &c, &package.commitment[0]) {
+            return Err(Error::InvalidProof);
+        }
+        let signing_share = evaluate_polynomial(&secret.polynomial, id);
+        packages.insert(*id, Round2Package { signing_share });
+    }
+
+    let secret = Round2Secret {
+        identifier: secret.identifier,
+        share: evaluate_polynomial(&secret.polynomial, &secret.identifier),
+        commitment: secret.commitment.clone(),
+        max_signers: secret.max_signers,
+    };
+    Ok((secret, packages))
+}
+
+/// Check the shares received in the second round against the senders'
+/// commitments and combine them into the participant's key package and the
+/// public key package everyone agrees on.
+///
+/// Fails if a package is missing or unexpected, a commitment has the wrong
+/// length, or a share does not match its sender's commitment.
+pub fn dkg_finish(
+    secret: &Round2Secret,
+    round1_packages: &BTreeMap<Identifier, Round1Package>,
+    round2_packages: &BTreeMap<Identifier, Round2Package>,
+) -> Result<(KeyPackage, PublicKeyPackage), Error> {
+    check_participants(&secret.identifier, secret.max_signers, round1_packages.keys())?;
+    check_participants(&secret.identifier, secret.max_signers, round2_packages.keys())?;
+    // The packages may not be the ones checked by `dkg_round2`.
+    if round1_packages.values().any(|p| p.commitment.len() != secret.commitment.len()) {
+        return Err(Error::InvalidThreshold);
+    }
+
+    let mut signing_share = secret.share;
+    for (id, package) in round2_packages.iter() {
+        let commitment = &round1_packages.get(id).ok_or(Error::InvalidIdentifier)?.commitment;
+        let expected = to_affine(&evaluate_commitment(commitment, &secret.identifier));
+        if expected.is_infinity() || to_affine(&mul_gen(&package.signing_share)) != expected {
+            return Err(Error::InvalidShare);
+        }
+        signing_share += package.signing_share;
+    }
+    if signing_share.is_zero() {
+        return Err(Error::InvalidShare);
+    }
+
+    let mut commitments: Vec<&[PublicKey]> = round1_packages.values().map(|p| &p.commitment[..]).collect();
+    commitments.push(&secret.commitment);
+    let verifying_key = PublicKey::combine(&commitments.iter().map(|c| c[0]).collect::<Vec<_>>())?;
+
+    // The verifying share of participant j is the sum over all commitments
+    // evaluated at j.
+    let mut ids: Vec<Identifier> = round1_packages.keys().cloned().collect();
+    ids.push(secret.identifier);
+    let mut verifying_shares = BTreeMap::new();
+    for id in ids {
+        let mut yj = Jacobian::default();
+        yj.set_infinity();
+        for c in commitments.iter() {
+            yj = yj.add_var(&evaluate_commitment(c, &id), None);
+        }
+        if yj.is_infinity() {
+            return Err(Error::InvalidShare);
+        }
+        verifying_shares.insert(id, PublicKey(to_affine(&yj)));
+    }
+
+    let min_signers = secret.commitment.len() as u16;
+    let key_package = KeyPackage {
+        identifier: secret.identifier,
+        signing_share,
+        verifying_share: verifying_shares[&secret.identifier],
+        verifying_key,
+        min_signers,
+    };
+    let public_key_package = PublicKeyPackage {
+        verifying_shares,
+        verifying_key,
+        min_signers,
+    };
+    Ok((key_package, public_key_package))
+}
+
+/// Check that the packages come from exactly the other `max_signers - 1`
+/// participants.
+fn check_participants<'a, I: ExactSizeIterator<Item = &'a Identifier>>(
+    own: &Identifier,
+    max_signers: u16,
+    mut ids: I,
+) -> Result<(), Error> {
+    if ids.len() != max_signers as usize - 1 {
+        return Err(Error::InvalidThreshold);
+    }
+    if ids.any(|id| id == own) {
+        return Err(Error::InvalidIdentifier);
+    }
+    Ok(())
+}
+
+/// Prove knowledge of the constant term `a_0` of the secret polynomial with
+/// the nonce `k`: `(R, mu) = (k.G, k + a_0.c)`.
+fn prove_knowledge(suite: Ciphersuite, secret: &Round1Secret, k: &Scalar) -> Signature {
+    let r = PublicKey(to_affine(&mul_gen(k)));
+    let c = proof_challenge(suite, &secret.identifier, &secret.commitment[0], &r);
+    Signature {
+        r,
+        z: *k + secret.polynomial[0] * c,
+    }
+}
+
+/// The proof of knowledge challenge `HDKG(id || C_0 || R)`.
+fn proof_challenge(suite: Ciphersuite, id: &Identifier, c0: &PublicKey, r: &PublicKey) -> Scalar {
+    suite.hash_to_scalar(
+        b"dkg",
+        &[&id.serialize(), &c0.serialize_compressed(), &r.serialize_compressed()],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{dkg_finish, dkg_round1, dkg_round2, prove_knowledge, Round1Package, Round1Secret};
+    use frost::keys::commit_polynomial;
+    use frost::{Ciphersuite, Identifier, Signature};
+    use hex;
+    use rand::thread_rng;
+    use util::{decode32, scalar_from_b32};
+    use Error;
+
+    // Test vectors for the DKG of the FROST(secp256k1, SHA-256) reference
+    // implementation; RFC 9591 does not specify a DKG and has none of its
+    // own: signing key, coefficient, proof of knowledge, the
+    // shares received from the other two participants, and the resulting
+    // verifying share and signing share.
+    type Participant = (&'static str, &'static str, &'static str, [&'static str; 2], &'static str, &'static str);
+
+    static PARTICIPANTS: [Participant; 3] = [
+        (
+            "e7a3cf1fdb1e17d4c3e8a7f663803ef305d03bdfdc930b824b0664c6b853156d",
+            "819adb51466d687c3944f8dad799a09551af9c083c918a50d9a24a883ae86e2a",
+            "0304df6af7f67b0d5f49ea2116f2d561a0a535c184836779f0f0677ff0838740ce20a0cb076384312f8817e030ca20379bab9247ee56fc3576b0b092f01c005691",
+            [
+                "3c4ae6fe69d55280cb06a0551f8563e526ee6f133a99433addcbb722a4c6f438",
+                "e2454ec522749fc08388fed9c120b6ada8e1fd1e00026624c95b273f94dbf8a8",
+            ],
+            "02b2597e19a037ba2eef224402a50652be93c1ab5bbd6195fc07ae6f6ecfa1304d",
+            "87cee034add572924bbd40001bbffa1db1f28a4bf52efebb4c2ad0978c71edf5",
+        ),
+        (
+            "ea163e297661aadf460b3de39a7550bd9b8fb2d07f1e1db5af098720156591a5",
+            "5234a8d4f373a7a184fb627185101326460d99296ac3c5c0ee948e8f5f97a3d4",
+            "02afffa1f80fd46f2bac01bf7967649014a3a5236a62f32f98ce11fec20ee7229072c534d89a6b7b4c16129780404e172c3bdb527a77d40d760b80cc6538bcd4c4",
+            [
+                "ead985c267f8e8cd367299ac12b3801eee809709a66d7fe83e789b4a5dedb080",
+                "39ee690094ac23a2373b35714ae7d3dc0e07e380bf547bf71758903d291a3e0b",
+            ],
+            "03037adc4e0f796b96fc639ac194c1e167ccc5dd57505c813b0533b2bcd6d6ddaa",
+            "b3477e9659ee0691bdafd1e40230cb07aed5a5e05bd6649f625f12acbb304556",
+        ),
+        (
+            "8a9c3489b03d1bdecfd6c84237599980890d39d49167b016bb8b5fb530677204",
+            "57a91a3b723783e1b3b2369789c71d2d1fd4c3496e9ab60e0dcfc78a647486a4",
+            "02ad586ef180cda6bae1d2144ee090d277c77b789c8261349a247073626373cd8723b0ea6a62e8bc37372567ab4ef221d5e0a6c46d57d3746f6e5fde863298a542",
+            [
+                "6c746113ae6651496fb79286ea4d20b58581562b33b669fd58488745c89fdd69",
+                "e0b438a850bca1c3d4fd653829a58a31b309a1661020cebcbaf4d44163f63be0",
+            ],
+            "02f2198ff3f1e1de2249cdc59eb4ec926936892fa39fc1582861ad2e84681624b3",
+            "dec01cf806069a912fa263c7e8a19bf1abb8c174c27dca83789354c1e9ee9cb7",
+        ),
+    ];
+
+    fn id(n: u16) -> Identifier {
+        Identifier::new(n).unwrap()
+    }
+
+    fn round1(n: u16) -> (Round1Secret, Round1Package) {
+        let p = &PARTICIPANTS[n as usize - 1];
+        let polynomial = vec![scalar_from_b32(&decode32(p.0)), scalar_from_b32(&decode32(p.1))];
+        let secret = Round1Secret {
+            identifier: id(n),
+            commitment: commit_polynomial(&polynomial),
+            polynomial,
+            max_signers: 3,
+        };
+        let mut proof = [0u8; 65];
+        proof.copy_from_slice(&hex::decode(p.2).unwrap());
+        let package = Round1Package {
+            commitment: secret.commitment.clone(),
+            proof_of_knowledge: Signature::parse(&proof).unwrap(),
+        };
+        (secret, package)
+    }
+
+    #[test]
+    fn test_dkg_vectors() {
+        let suite = Ciphersuite::Secp256k1Sha256;
+        let mut secrets = Vec::new();
+        let mut packages = BTreeMap::new();
+        for n in 1..4 {
+            let (secret, package) = round1(n);
+            secrets.push(secret);
+            packages.insert(id(n), package);
+        }
+
+        let mut round2_secrets = Vec::new();
+        let mut sent = BTreeMap::new();
+        for (i, secret) in secrets.into_iter().enumerate() {
+            let own = id(i as u16 + 1);
+            let mut others = packages.clone();
+            others.remove(&own);
+            let (round2_secret, round2_packages) = dkg_round2(suite, secret, &others).unwrap();
+            for (to, package) in round2_packages {
+                sent.insert((own, to), package);
+            }
+            round2_secrets.push(round2_secret);
+        }
+
+        for (i, secret) in round2_secrets.iter().enumerate() {
+            let own = id(i as u16 + 1);
+            let mut others = packages.clone();
+            others.remove(&own);
+            let received: BTreeMap<_, _> = sent
+                .iter()
+                .filter(|&(&(_, to), _)| to == own)
+                .map(|(&(from, _), package)| (from, package.clone()))
+                .collect();
+            for (package, expected) in received.values().zip(PARTICIPANTS[i].3.iter()) {
+                assert_eq!(hex::encode(package.signing_share.b32()), *expected);
+            }
+            let (key_package, public_key_package) = dkg_finish(secret, &others, &received).unwrap();
+            assert_eq!(hex::encode(key_package.signing_share.b32()), PARTICIPANTS[i].5);
+            assert_eq!(key_package.verifying_share.to_hex(true), PARTICIPANTS[i].4);
+            assert_eq!(
+                key_package.verifying_key.to_hex(true),
+                "037b5b0c4b6c91a16fb78499e8a74cc792f9ea79cb94860fcb90f801472930de47"
+            );
+            for (j, p) in PARTICIPANTS.iter().enumerate() {
+                assert_eq!(public_key_package.verifying_shares[&id(j as u16 + 1)].to_hex(true), p.4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dkg_invalid() {
+        let suite = Ciphersuite::Secp256k1Sha256;
+        let (secret, _) = round1(1);
+        let (_, package2) = round1(2);
+        let (_, package3) = round1(3);
+
+        // A proof of knowledge for a different commitment.
+        let mut others = BTreeMap::new();
+        others.insert(id(2), package2.clone());
+        let mut forged = package3.clone();
+        forged.proof_of_knowledge = package2.proof_of_knowledge.clone();
+        others.insert(id(3), forged);
+        assert_eq!(dkg_round2(suite, secret, &others).err(), Some(Error::InvalidProof));
+
+        // The proof is bound to the ciphersuite and the identifier.
+        let (secret, _) = round1(1);
+        let proof = prove_knowledge(Ciphersuite::Secp256k1Sha256Tr, &secret, &scalar_from_b32(&[7u8; 32]));
+        let mut wrong_suite = package3.clone();
+        wrong_suite.proof_of_knowledge = proof;
+        others.insert(id(3), wrong_suite);
+        assert_eq!(dkg_round2(suite, secret, &others).err(), Some(Error::InvalidProof));
+
+        // Missing or own packages.
+        let (secret, _) = round1(1);
+        others.remove(&id(3));
+        assert_eq!(dkg_round2(suite, secret, &others).err(), Some(Error::InvalidThreshold));
+        let (secret, package1) = round1(1);
+        others.insert(id(1), package1);
+        assert_eq!(dkg_round2(suite, secret, &others).err(), Some(Error::InvalidIdentifier));
+
+        // A share that does not match the sender's commitment.
+        let (secret, _) = round1(1);
+        others.remove(&id(1));
+        others.insert(id(3), package3);
+        let (round2_secret, mut shares) = dkg_round2(suite, secret, &others).unwrap();
+        let share = shares[&id(2)].clone();
+        shares.insert(id(3), share);
+        assert_eq!(dkg_finish(&round2_secret, &others, &shares).err(), Some(Error::InvalidShare));
+
+        // Commitments of the wrong length, not seen by `dkg_round2`.
+        let (_, shares) = dkg_round2(suite, round1(1).0, &others).unwrap();
+        for len in 0..2 {
+            let mut malformed = others.clone();
+            malformed.get_mut(&id(3)).unwrap().commitment.truncate(len);
+            assert_eq!(dkg_finish(&round2_secret, &malformed, &shares).err(), Some(Error::InvalidThreshold));
+        }
+        let mut malformed = others.clone();
+        let extra = malformed[&id(2)].commitment[1];
+        malformed.get_mut(&id(3)).unwrap().commitment.push(extra);
+        assert_eq!(dkg_finish(&round2_secret, &malformed, &shares).err(), Some(Error::InvalidThreshold));
+    }
+
+    #[test]
+    fn test_dkg_random() {
+        let suite = Ciphersuite::Secp256k1Sha256Tr;
+        let mut rng = thread_rng();
+        assert_eq!(dkg_round1(suite, &mut rng, id(1), 4, 3).err(), Some(Error::InvalidThreshold));
+
+        let ids: Vec<Identifier> = (1..5).map(id).collect();
+        let mut round1_secrets = Vec::new();
+        let mut round1_packages = BTreeMap::new();
+        for own in ids.iter() {
+            let (secret, package) = dkg_round1(suite, &mut rng, *own, 3, 4).unwrap();
+            round1_secrets.push(secret);
+            round1_packages.insert(*own, package);
+        }
+
+        let mut round2_secrets = Vec::new();
+        let mut sent = BTreeMap::new();
+        for (own, secret) in ids.iter().zip(round1_secrets) {
+            let mut others = round1_packages.clone();
+            others.remove(own);
+            let (round2_secret, packages) = dkg_round2(suite, secret, &others).unwrap();
+            for (to, package) in packages {
+                sent.insert((*own, to), package);
+            }
+            round2_secrets.push(round2_secret);
+        }
+
+        let mut verifying_key = None;
+        for (own, secret) in ids.iter().zip(round2_secrets.iter()) {
+            let mut others = round1_packages.clone();
+            others.remove(own);
+            let received = sent
+                .iter()
+                .filter(|&(&(_, to), _)| to == *own)
+                .map(|(&(from, _), package)| (from, package.clone()))
+                .collect();
+            let (key_package, public_key_package) = dkg_finish(secret, &others, &received).unwrap();
+            assert_eq!(key_package.min_signers, 3);
+            assert_eq!(public_key_package.verifying_shares.len(), 4);
+            assert_eq!(*verifying_key.get_or_insert(key_package.verifying_key), key_package.verifying_key);
+        }
+    }
+}
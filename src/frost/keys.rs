@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use super::Identifier;
+use rand::Rng;
+use secp256k1::group::Jacobian;
+use secp256k1::{Error, PublicKey, Scalar, SecretKey};
+use util::{mul_gen, point_mul, to_affine};
+
+/// A participant's share `f(id)` of the group secret key, where `f` is the
+/// dealer's secret polynomial with `f(0)` the group secret key, together
+/// with the commitment `C_k = a_k.G` to the coefficients of `f` that the share
+/// can be checked against.
+#[derive(Debug, Clone)]
+pub struct SecretShare {
+    pub identifier: Identifier,
+    pub value: Scalar,
+    pub commitment: Vec<PublicKey>,
+}
+
+impl SecretShare {
+    /// Check `f(id).G = C_0 + id.C_1 + ... + id^(t-1).C_(t-1)`, so that the
+    /// share is consistent with the shares of the other participants.
+    pub fn verify(&self) -> bool {
+        if self.value.is_zero() {
+            return false;
+        }
+        let lhs = to_affine(&mul_gen(&self.value));
+        let rhs = to_affine(&evaluate_commitment(&self.commitment, &self.identifier));
+        !rhs.is_infinity() && lhs == rhs
+    }
+}
+
+/// Everything a participant needs to sign: its identifier and signing
+/// share, its verifying share `s.G`, the group key and the threshold.
+#[derive(Debug, Clone)]
+pub struct KeyPackage {
+    pub identifier: Identifier,
+    pub signing_share: Scalar,
+    pub verifying_share: PublicKey,
+    pub verifying_key: PublicKey,
+    pub min_signers: u16,
+}
+
+impl KeyPackage {
+    /// Check a share from a trusted dealer and derive the key package from
+    /// it. Fails if the share does not match its commitment.
+    pub fn from_secret_share(share: &SecretShare) -> Result<KeyPackage, Error> {
+        if !share.verify() {
+            return Err(Error::InvalidShare);
+        }
+        Ok(KeyPackage {
+            identifier: share.identifier,
+            signing_share: share.value,
+            verifying_share: PublicKey(to_affine(&mul_gen(&share.value))),
+            verifying_key: share.commitment[0],
+            min_signers: share.commitment.len() as u16,
+        })
+    }
+}
+
+/// The public part of a key generation: the group key and the verifying
+/// shares of all participants, used to check signature shares.
+#[derive(Debug, Clone)]
+pub struct PublicKeyPackage {
+    pub verifying_shares: BTreeMap<Identifier, PublicKey>,
+    pub verifying_key: PublicKey,
+    pub min_signers: u16,
+}
+
+/// Generate a random group key and split it into shares for participants
+/// `1..=max_signers`, any `min_signers` of which can sign. Fails unless
+/// `2 <= min_signers <= max_signers`.
+pub fn trusted_dealer_keygen<R: Rng>(
+    rng: &mut R,
+    min_signers: u16,
+    max_signers: u16,
+) -> Result<(BTreeMap<Identifier, SecretShare>, PublicKeyPackage), Error> {
+    let secret = SecretKey::random(rng);
+    let coefficients: Vec<Scalar> = (1..min_signers).map(|_| SecretKey::random(rng).0).collect();
+    split_secret(&secret, &coefficients, max_signers)
+}
+
+/// Split `secret` into shares for participants `1..=max_signers` with the
+/// polynomial `f(x) = secret + a_1.x + ... + a_(t-1).x^(t-1)`, so that any
+/// `t = coefficients.len() + 1` of them can sign. Fails if a coefficient is
+/// zero or unless `2 <= t <= max_signers`.
+pub fn split_secret(
+    secret: &SecretKey,
+    coefficients: &[Scalar],
+    max_signers: u16,
+) -> Result<(BTreeMap<Identifier, SecretShare>, PublicKeyPackage), Error> {
+    let min_signers = coefficients.len() + 1;
+    if min_signers < 2 || min_signers > max_signers as usize {
+        return Err(Error::InvalidThreshold);
+    }
+    if coefficients.iter().any(|a| a.is_zero()) {
+        return Err(Error::InvalidSecretKey);
+    }
+
+    let mut polynomial = vec![secret.0];
+    polynomial.extend_from_slice(coefficients);
+    let commitment = commit_polynomial(&polynomial);
+
+    let mut shares = BTreeMap::new();
+    let mut verifying_shares = BTreeMap::new();
+    for n in 1..=max_signers {
+        let identifier = Identifier::new(n)?;
+        let value = evaluate_polynomial(&polynomial, &identifier);
+        if value.is_zero() {
+            return Err(Error::InvalidSecretKey);
+        }
+        verifying_shares.insert(identifier, PublicKey(to_affine(&mul_gen(&value))));
+        shares.insert(
+            identifier,
+            SecretShare {
+                identifier,
+                value,
+                commitment: commitment.clone(),
+            },
+        );
+    }
+    for a in polynomial.iter_mut() {
+        a.clear();
+    }
+
+    let public_key_package = PublicKeyPackage {
+        verifying_shares,
+        verifying_key: commitment[0],
+        min_signers: min_signers as u16,
+    };
+    Ok((shares, public_key_package))
+}
+
+/// The commitment `C_k = a_k.G` to each coefficient of a polynomial with
+/// nonzero coefficients.
+pub(super) fn commit_polynomial(polynomial: &[Scalar]) -> Vec<PublicKey> {
+    polynomial.iter().map(|a| PublicKey(to_affine(&mul_gen(a)))).collect()
+}
+
+/// Evaluate `a_0 + a_1.x + ... + a_(t-1).x^(t-1)` at an identifier.
+pub(super) fn evaluate_polynomial(polynomial: &[Scalar], x: &Identifier) -> Scalar {
+    let mut ret = Scalar::default();
+    for a in polynomial.iter().rev() {
+        ret *= x.0;
+        ret += *a;
+    }
+    ret
+}
+
+/// Evaluate `C_0 + x.C_1 + ... + x^(t-1).C_(t-1)` at an identifier, giving
+/// the verifying share of that participant for a single polynomial.
+pub(super) fn evaluate_commitment(commitment: &[PublicKey], x: &Identifier) -> Jacobian {
+    let mut ret = Jacobian::default();
+    ret.set_infinity();
+    for c in commitment.iter().rev() {
+        ret = point_mul(&to_affine(&ret), &x.0).add_ge_var(&c.0, None);
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_secret, trusted_dealer_keygen, KeyPackage};
+    use frost::{lagrange_coefficient, Identifier};
+    use hex;
+    use rand::thread_rng;
+    use util::{decode32, scalar_from_b32};
+    use {Error, PublicKey, SecretKey};
+
+    #[test]
+    fn test_split_secret() {
+        // RFC 9591 FROST(secp256k1, SHA-256) test vectors.
+        let secret = SecretKey::from_hex("0d004150d27c3bf2a42f312683d35fac7394b1e9e318249c1bfe7f0795a83114").unwrap();
+        let coefficient = scalar_from_b32(&decode32("fbf85eadae3058ea14f19148bb72b45e4399c0b16028acaf0395c9b03c823579"));
+        let (shares, public_key_package) = split_secret(&secret, &[coefficient], 3).unwrap();
+
+        let expected = [
+            "08f89ffe80ac94dcb920c26f3f46140bfc7f95b493f8310f5fc1ea2b01f4254c",
+            "04f0feac2edcedc6ce1253b7fab8c86b856a797f44d83d82a385554e6e401984",
+            "00e95d59dd0d46b0e303e500b62b7ccb0e555d49f5b849f5e748c071da8c0dbc",
+        ];
+        assert_eq!(shares.len(), 3);
+        for (share, expected) in shares.values().zip(expected.iter()) {
+            assert_eq!(hex::encode(share.value.b32()), *expected);
+            let key_package = KeyPackage::from_secret_share(share).unwrap();
+            assert_eq!(key_package.min_signers, 2);
+            assert_eq!(public_key_package.verifying_shares[&share.identifier], key_package.verifying_share);
+        }
+        assert_eq!(
+            public_key_package.verifying_key.to_hex(true),
+            "02f37c34b66ced1fb51c34a90bdae006901f10625cc06c4f64663b0eae87d87b4f"
+        );
+    }
+
+    #[test]
+    fn test_trusted_dealer_keygen() {
+        let (shares, public_key_package) = trusted_dealer_keygen(&mut thread_rng(), 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert_eq!(public_key_package.min_signers, 3);
+
+        // Any three shares interpolate to the group secret key.
+        let ids: Vec<Identifier> = [1, 3, 4].iter().map(|n| Identifier::new(*n).unwrap()).collect();
+        let mut secret = scalar_from_b32(&[0u8; 32]);
+        for id in ids.iter() {
+            secret += lagrange_coefficient(id, &ids).unwrap() * shares[id].value;
+        }
+        assert_eq!(PublicKey::from_secret_key(&SecretKey(secret)), public_key_package.verifying_key);
+
+        // A tampered share no longer matches the commitment.
+        let mut share = shares[&ids[0]].clone();
+        share.value = shares[&ids[1]].value;
+        assert!(!share.verify());
+        assert_eq!(KeyPackage::from_secret_share(&share).err(), Some(Error::InvalidShare));
+    }
+
+    #[test]
+    fn test_invalid_threshold() {
+        let mut rng = thread_rng();
+        assert_eq!(trusted_dealer_keygen(&mut rng, 1, 3).err(), Some(Error::InvalidThreshold));
+        assert_eq!(trusted_dealer_keygen(&mut rng, 4, 3).err(), Some(Error::InvalidThreshold));
+
+        let secret = SecretKey::random(&mut rng);
+        let zero = scalar_from_b32(&[0u8; 32]);
+        assert_eq!(split_secret(&secret, &[zero], 3).err(), Some(Error::InvalidSecretKey));
+    }
+}
@@ -0,0 +1,272 @@
+//! FROST threshold Schnorr signatures (RFC 9591) over secp256k1: key
+//! generation with a trusted dealer or a distributed key generation, two-round
+//! signing with binding-factor nonce commitments, verification of signature
+//! shares and their aggregation.
+//!
+//! Any `t` of the `n` participants holding a share of the group key can sign
+//! together. Every participant calls `commit` and sends its
+//! `SigningCommitments` to the coordinator, which picks at least `t` of them
+//! and hands them out together with the message. Each signer creates a
+//! `Session` from them and produces a `SignatureShare` with its
+//! `SigningNonces`, and the coordinator checks and combines the shares with
+//! `Session::aggregate`.
+//!
+//! Two ciphersuites are supported. `Ciphersuite::Secp256k1Sha256` is the one
+//! from the RFC. `Ciphersuite::Secp256k1Sha256Tr` hashes the challenge as
+//! BIP-340 does and negates keys and nonces so that the group commitment and
+//! group key have even y-coordinates, which makes the aggregate signature an
+//! ordinary BIP-340 signature for the x-only group key.
+
+mod dkg;
+mod keys;
+mod session;
+
+pub use self::dkg::{dkg_finish, dkg_round1, dkg_round2, Round1Package, Round1Secret, Round2Package, Round2Secret};
+pub use self::keys::{split_secret, trusted_dealer_keygen, KeyPackage, PublicKeyPackage, SecretShare};
+pub use self::session::{commit, Session, Signature, SignatureShare, SigningCommitments, SigningNonces};
+
+use std::cmp::Ordering;
+
+use ecmult::ECMULT_CONTEXT;
+use schnorr::tagged_hash;
+use secp256k1::group::Jacobian;
+use secp256k1::{Error, PublicKey, Scalar};
+use sha2::{Digest, Sha256};
+use util::{scalar_from_b32, scalar_one, to_affine};
+
+/// A FROST ciphersuite over secp256k1 with SHA-256.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Ciphersuite {
+    /// FROST(secp256k1, SHA-256) as specified in RFC 9591.
+    Secp256k1Sha256,
+    /// The same with a BIP-340 challenge and even-y keys and nonces, for
+    /// signatures that verify as BIP-340 Schnorr signatures.
+    Secp256k1Sha256Tr,
+}
+
+impl Ciphersuite {
+    fn context(&self) -> &'static [u8] {
+        match *self {
+            Ciphersuite::Secp256k1Sha256 => b"FROST-secp256k1-SHA256-v1",
+            Ciphersuite::Secp256k1Sha256Tr => b"FROST-secp256k1-SHA256-TR-v1",
+        }
+    }
+
+    /// Whether keys and nonces are negated to even y-coordinates.
+    fn even_y(&self) -> bool {
+        *self == Ciphersuite::Secp256k1Sha256Tr
+    }
+
+    /// `hash_to_field` with the domain separation tag `context || tag`,
+    /// used for H1 ("rho"), H3 ("nonce"), HDKG ("dkg") and HID ("id").
+    fn hash_to_scalar(&self, tag: &[u8], msg: &[&[u8]]) -> Scalar {
+        let mut dst = self.context().to_vec();
+        dst.extend_from_slice(tag);
+        let uniform = expand_message_xmd(msg, &dst);
+
+        // Reduce the 48-byte big-endian integer hi.2^256 + lo modulo the
+        // group order, with 2^256 = (2^256 - 1) + 1.
+        let mut hi = [0u8; 32];
+        hi[16..].copy_from_slice(&uniform[..16]);
+        let lo = array_ref!(uniform, 16, 32);
+        let mut shift = scalar_from_b32(&[0xff; 32]);
+        shift += scalar_one();
+        scalar_from_b32(&hi) * shift + scalar_from_b32(lo)
+    }
+
+    /// `SHA256(context || tag || msg)`, used for H4 ("msg") and H5 ("com").
+    fn hash(&self, tag: &[u8], msg: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::default();
+        hasher.input(self.context());
+        hasher.input(tag);
+        hasher.input(msg);
+        let mut ret = [0u8; 32];
+        ret.copy_from_slice(&hasher.result());
+        ret
+    }
+
+    /// The challenge H2 for a group commitment, group key and message.
+    fn challenge(&self, r: &PublicKey, pubkey: &PublicKey, msg: &[u8]) -> Scalar {
+        match *self {
+            Ciphersuite::Secp256k1Sha256 => {
+                let (r, pk) = (r.serialize_compressed(), pubkey.serialize_compressed());
+                self.hash_to_scalar(b"chal", &[&r, &pk, msg])
+            }
+            Ciphersuite::Secp256k1Sha256Tr => {
+                let (r, pk) = (r.x_only().0.serialize(), pubkey.x_only().0.serialize());
+                scalar_from_b32(&tagged_hash(b"BIP0340/challenge", &[&r, &pk, msg]))
+            }
+        }
+    }
+}
+
+/// A participant identifier, a nonzero scalar. Identifiers are ordered by
+/// their big-endian encoding.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Identifier(Scalar);
+
+impl Identifier {
+    /// The identifier for participant number `n`. Fails if `n` is zero.
+    pub fn new(n: u16) -> Result<Identifier, Error> {
+        if n == 0 {
+            return Err(Error::InvalidIdentifier);
+        }
+        let mut s = Scalar::default();
+        s.set_int(u32::from(n));
+        Ok(Identifier(s))
+    }
+
+    /// Derive an identifier from arbitrary bytes such as a participant name,
+    /// with the hash HID.
+    pub fn derive(suite: Ciphersuite, s: &[u8]) -> Result<Identifier, Error> {
+        let id = suite.hash_to_scalar(b"id", &[s]);
+        if id.is_zero() {
+            return Err(Error::InvalidIdentifier);
+        }
+        Ok(Identifier(id))
+    }
+
+    /// Parse a 32-byte identifier. Fails if it is zero or not below the
+    /// group order.
+    pub fn parse(p: &[u8; 32]) -> Result<Identifier, Error> {
+        let mut s = Scalar::default();
+        if s.set_b32(p) || s.is_zero() {
+            return Err(Error::InvalidIdentifier);
+        }
+        Ok(Identifier(s))
+    }
+
+    pub fn serialize(&self) -> [u8; 32] {
+        self.0.b32()
+    }
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Identifier) -> Ordering {
+        self.serialize().cmp(&other.serialize())
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Identifier) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The Lagrange coefficient of `id` for interpolating at zero from the
+/// shares of `ids`. Fails if `id` is not in `ids`.
+fn lagrange_coefficient(id: &Identifier, ids: &[Identifier]) -> Result<Scalar, Error> {
+    if !ids.contains(id) {
+        return Err(Error::InvalidIdentifier);
+    }
+    let mut num = scalar_one();
+    let mut den = scalar_one();
+    for x in ids.iter().filter(|x| *x != id) {
+        num *= x.0;
+        den *= x.0 + -id.0;
+    }
+    if den.is_zero() {
+        return Err(Error::InvalidIdentifier);
+    }
+    Ok(num * den.inv())
+}
+
+/// `expand_message_xmd` from RFC 9380 with SHA-256, producing the 48 bytes
+/// `hash_to_field` needs for a 256-bit group order.
+fn expand_message_xmd(msg: &[&[u8]], dst: &[u8]) -> [u8; 48] {
+    let dst_len = [dst.len() as u8];
+    let mut hasher = Sha256::default();
+    hasher.input(&[0u8; 64]);
+    for m in msg {
+        hasher.input(m);
+    }
+    hasher.input(&[0, 48, 0]);
+    hasher.input(dst);
+    hasher.input(&dst_len);
+    let b0 = hasher.result();
+
+    let mut ret = [0u8; 48];
+    let mut bi = [0u8; 32];
+    for i in 0..2 {
+        let mut hasher = Sha256::default();
+        for (b, b0) in bi.iter_mut().zip(b0.iter()) {
+            *b ^= *b0;
+        }
+        hasher.input(&bi);
+        hasher.input(&[i as u8 + 1]);
+        hasher.input(dst);
+        hasher.input(&dst_len);
+        bi.copy_from_slice(&hasher.result());
+        let len = if i == 0 { 32 } else { 16 };
+        ret[i * 32..i * 32 + len].copy_from_slice(&bi[..len]);
+    }
+    ret
+}
+
+/// Check the Schnorr equation `z.G = R + c.P`.
+fn schnorr_check(r: &PublicKey, z: &Scalar, c: &Scalar, pubkey: &PublicKey) -> bool {
+    let mut pj = Jacobian::default();
+    pj.set_ge(&pubkey.0);
+    let mut rj = Jacobian::default();
+    ECMULT_CONTEXT.ecmult(&mut rj, &pj, &c.neg(), z);
+    let lhs = to_affine(&rj);
+    !lhs.is_infinity() && lhs == r.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lagrange_coefficient, Ciphersuite, Identifier};
+    use hex;
+    use util::scalar_from_b32;
+    use Error;
+
+    #[test]
+    fn test_hash_to_scalar() {
+        // H1 of participant 1's binding factor input in the RFC 9591
+        // FROST(secp256k1, SHA-256) test vectors.
+        let input = hex::decode(concat!(
+            "02f37c34b66ced1fb51c34a90bdae006901f10625cc06c4f64663b0eae87d87b4f",
+            "ff9b5210ffbb3c07a73a7c8935be4a8c62cf015f6cf7ade6efac09a6513540fc",
+            "fac8df6fa81b3f4d9ced4be2474894308232dc0be75dbf81f5a103579a823631",
+            "0000000000000000000000000000000000000000000000000000000000000001"
+        )).unwrap();
+        let suite = Ciphersuite::Secp256k1Sha256;
+        let rho = suite.hash_to_scalar(b"rho", &[&input]);
+        assert_eq!(hex::encode(rho.b32()), "9bee5aef4012de4b94c9fc1a9a9572181079e293bf1d7545a5af0ef86f824a91");
+        assert_eq!(suite.hash_to_scalar(b"rho", &[&input[..40], &input[40..]]), rho);
+    }
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(Identifier::new(0), Err(Error::InvalidIdentifier));
+        assert_eq!(Identifier::parse(&[0u8; 32]), Err(Error::InvalidIdentifier));
+        assert_eq!(Identifier::parse(&[0xff; 32]), Err(Error::InvalidIdentifier));
+
+        let id = Identifier::new(258).unwrap();
+        let mut expected = [0u8; 32];
+        expected[30] = 1;
+        expected[31] = 2;
+        assert_eq!(id.serialize(), expected);
+        assert_eq!(Identifier::parse(&expected), Ok(id));
+        assert!(Identifier::new(2).unwrap() < id);
+
+        let alice = Identifier::derive(Ciphersuite::Secp256k1Sha256, b"alice").unwrap();
+        assert!(alice != Identifier::derive(Ciphersuite::Secp256k1Sha256, b"bob").unwrap());
+        assert!(alice != Identifier::derive(Ciphersuite::Secp256k1Sha256Tr, b"alice").unwrap());
+    }
+
+    #[test]
+    fn test_lagrange_coefficient() {
+        let ids: Vec<Identifier> = [1, 3].iter().map(|n| Identifier::new(*n).unwrap()).collect();
+        // For {1, 3}: l_1 = 3 / (3 - 1) and l_3 = 1 / (1 - 3), so that
+        // l_1 + l_3 = 1.
+        let l1 = lagrange_coefficient(&ids[0], &ids).unwrap();
+        let l3 = lagrange_coefficient(&ids[1], &ids).unwrap();
+        assert!((l1 + l3).is_one());
+        let two = Identifier::new(2).unwrap().serialize();
+        assert_eq!((l1 * scalar_from_b32(&two)).b32(), Identifier::new(3).unwrap().serialize());
+
+        let missing = Identifier::new(2).unwrap();
+        assert_eq!(lagrange_coefficient(&missing, &ids), Err(Error::InvalidIdentifier));
+    }
+}
@@ -0,0 +1,563 @@
+use std::collections::BTreeMap;
+
+use super::{lagrange_coefficient, schnorr_check, Ciphersuite, Identifier, KeyPackage, PublicKeyPackage};
+use rand::Rng;
+use schnorr;
+use secp256k1::group::Jacobian;
+use secp256k1::{Error, PublicKey, Scalar};
+use util::{mul_gen, point_mul, to_affine};
+
+/// The hiding and binding nonces `(d, e)` drawn by `commit` for a single
+/// signing round. They are spent by `Session::sign` and, for the same reason
+/// as `musig::SecNonce`, cannot be copied and are wiped when dropped.
+pub struct SigningNonces {
+    hiding: Scalar,
+    binding: Scalar,
+    commitments: SigningCommitments,
+}
+
+/// The commitments `(D, E) = (d.G, e.G)` to a signer's nonces, sent to the
+/// coordinator in the first round.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SigningCommitments {
+    pub hiding: PublicKey,
+    pub binding: PublicKey,
+}
+
+/// A signer's share `z_i` of the final signature.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SignatureShare(pub Scalar);
+
+/// A FROST signature `(R, z)`. With `Ciphersuite::Secp256k1Sha256Tr` it is
+/// also a BIP-340 signature, see `to_bip340`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Signature {
+    pub r: PublicKey,
+    pub z: Scalar,
+}
+
+impl SigningNonces {
+    /// Derive the nonces from 32 bytes of randomness each and the signing
+    /// share, as `H3(random || share)`, so that a weak random number
+    /// generator alone does not leak the share.
+    fn from_randomness(
+        suite: Ciphersuite,
+        hiding: &[u8; 32],
+        binding: &[u8; 32],
+        signing_share: &Scalar,
+    ) -> SigningNonces {
+        let share = signing_share.b32();
+        let hiding = suite.hash_to_scalar(b"nonce", &[hiding, &share]);
+        let binding = suite.hash_to_scalar(b"nonce", &[binding, &share]);
+        let commitments = SigningCommitments {
+            hiding: PublicKey(to_affine(&mul_gen(&hiding))),
+            binding: PublicKey(to_affine(&mul_gen(&binding))),
+        };
+        SigningNonces {
+            hiding,
+            binding,
+            commitments,
+        }
+    }
+
+    pub fn commitments(&self) -> SigningCommitments {
+        self.commitments
+    }
+}
+
+impl Drop for SigningNonces {
+    fn drop(&mut self) {
+        self.hiding.clear();
+        self.binding.clear();
+    }
+}
+
+impl SigningCommitments {
+    /// Parse `D || E`, the hiding commitment followed by the binding one, both
+    /// compressed.
+    pub fn parse(p: &[u8; 66]) -> Result<SigningCommitments, Error> {
+        let hiding = PublicKey::parse_compressed(array_ref!(p, 0, 33)).or(Err(Error::InvalidNonce))?;
+        let binding = PublicKey::parse_compressed(array_ref!(p, 33, 33)).or(Err(Error::InvalidNonce))?;
+        Ok(SigningCommitments { hiding, binding })
+    }
+
+    pub fn serialize(&self) -> [u8; 66] {
+        let mut ret = [0u8; 66];
+        ret[..33].copy_from_slice(&self.hiding.serialize_compressed());
+        ret[33..].copy_from_slice(&self.binding.serialize_compressed());
+        ret
+    }
+}
+
+impl SignatureShare {
+    /// Parse a 32-byte signature share. Fails if it is not below the group
+    /// order.
+    pub fn parse(p: &[u8; 32]) -> Result<SignatureShare, Error> {
+        let mut z = Scalar::default();
+        if z.set_b32(p) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(SignatureShare(z))
+    }
+
+    pub fn serialize(&self) -> [u8; 32] {
+        self.0.b32()
+    }
+}
+
+impl Signature {
+    /// Parse the 65-byte encoding `R || z` with a compressed `R`. Fails if `R`
+    /// is not a valid point or `z` is not below the group order.
+    pub fn parse(p: &[u8; 65]) -> Result<Signature, Error> {
+        let r = PublicKey::parse_compressed(array_ref!(p, 0, 33)).or(Err(Error::InvalidSignature))?;
+        let mut z = Scalar::default();
+        if z.set_b32(array_ref!(p, 33, 32)) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(Signature { r, z })
+    }
+
+    pub fn serialize(&self) -> [u8; 65] {
+        let mut ret = [0u8; 65];
+        ret[..33].copy_from_slice(&self.r.serialize_compressed());
+        self.z.fill_b32(array_mut_ref!(ret, 33, 32));
+        ret
+    }
+
+    /// The BIP-340 signature `(R.x, z)`. Only valid for signatures made with
+    /// `Ciphersuite::Secp256k1Sha256Tr`, for the x-only group key.
+    pub fn to_bip340(&self) -> schnorr::Signature {
+        let mut r = self.r.0.x;
+        r.normalize_var();
+        schnorr::Signature { r, s: self.z }
+    }
+
+    /// Verify the signature for a message and group key, checking
+    /// `z.G = R + c.Y`, where for `Ciphersuite::Secp256k1Sha256Tr` `R` and `Y`
+    /// are first negated if their y-coordinates are odd.
+    pub fn verify(&self, suite: Ciphersuite, msg: &[u8], verifying_key: &PublicKey) -> bool {
+        let (mut r, mut y) = (self.r, *verifying_key);
+        if suite.even_y() {
+            r = PublicKey::from(r.x_only().0);
+            y = PublicKey::from(y.x_only().0);
+        }
+        let c = suite.challenge(&r, &y, msg);
+        schnorr_check(&r, &self.z, &c, &y)
+    }
+}
+
+/// Generate nonces for one signing round from the random number generator
+/// and the signer's signing share. The commitments go to the coordinator and
+/// the nonces are kept for `Session::sign`.
+pub fn commit<R: Rng>(
+    suite: Ciphersuite,
+    rng: &mut R,
+    signing_share: &Scalar,
+) -> (SigningNonces, SigningCommitments) {
+    let mut hiding = [0u8; 32];
+    let mut binding = [0u8; 32];
+    rng.fill_bytes(&mut hiding);
+    rng.fill_bytes(&mut binding);
+    let nonces = SigningNonces::from_randomness(suite, &hiding, &binding, signing_share);
+    let commitments = nonces.commitments;
+    (nonces, commitments)
+}
+
+/// A signing package bound to a message: the commitments of the chosen
+/// signers with their binding factors `rho_i`, the group commitment `R` and
+/// the challenge `c`. Signers produce their shares from it, and the
+/// coordinator uses it to check and aggregate them.
+#[derive(Debug, Clone)]
+pub struct Session {
+    commitments: BTreeMap<Identifier, SigningCommitments>,
+    binding_factors: BTreeMap<Identifier, Scalar>,
+    verifying_key: PublicKey,
+    negate_key: bool,
+    r: PublicKey,
+    negate_nonces: bool,
+    c: Scalar,
+}
+
+impl Session {
+    /// Compute the binding factors `rho_i = H1(Y || H4(msg) || H5(commitments)
+    /// || id_i)`, the group commitment `R = sum(D_i + rho_i.E_i)` and the
+    /// challenge for the signers whose commitments are given.
+    ///
+    /// Fails if no commitments are given or the group commitment is infinity.
+    pub fn new(
+        suite: Ciphersuite,
+        verifying_key: &PublicKey,
+        commitments: &BTreeMap<Identifier, SigningCommitments>,
+        msg: &[u8],
+    ) -> Result<Session, Error> {
+        if commitments.is_empty() {
+            return Err(Error::InvalidThreshold);
+        }
+        let (xonly, parity) = verifying_key.x_only();
+        let negate_key = suite.even_y() && parity.is_odd();
+        let verifying_key = if suite.even_y() { PublicKey::from(xonly) } else { *verifying_key };
+
+        let mut encoded = Vec::with_capacity(commitments.len() * 98);
+        for (id, commitment) in commitments.iter() {
+            encoded.extend_from_slice(&id.serialize());
+            encoded.extend_from_slice(&commitment.serialize());
+        }
+        let mut prefix = verifying_key.serialize_compressed().to_vec();
+        prefix.extend_from_slice(&suite.hash(b"msg", msg));
+        prefix.extend_from_slice(&suite.hash(b"com", &encoded));
+
+        let mut binding_factors = BTreeMap::new();
+        let mut rj = Jacobian::default();
+        rj.set_infinity();
+        for (id, commitment) in commitments.iter() {
+            let rho = suite.hash_to_scalar(b"rho", &[&prefix, &id.serialize()]);
+            rj = rj.add_var(&point_mul(&commitment.binding.0, &rho), None);
+            rj = rj.add_ge_var(&commitment.hiding.0, None);
+            binding_factors.insert(*id, rho);
+        }
+        if rj.is_infinity() {
+            return Err(Error::InvalidNonce);
+        }
+        let r = PublicKey(to_affine(&rj));
+        let negate_nonces = suite.even_y() && r.x_only().1.is_odd();
+        let c = suite.challenge(&r, &verifying_key, msg);
+
+        Ok(Session {
+            commitments: commitments.clone(),
+            binding_factors,
+            verifying_key,
+            negate_key,
+            r,
+            negate_nonces,
+            c,
+        })
+    }
+
+    /// Compute the signature share `z_i = d + rho_i.e + lambda_i.s.c`. The
+    /// nonces are consumed.
+    ///
+    /// Fails if fewer than the threshold of signers take part, the nonces do
+    /// not match the signer's commitments, or the key package is for a
+    /// different group key.
+    pub fn sign(&self, nonces: SigningNonces, key_package: &KeyPackage) -> Result<SignatureShare, Error> {
+        if self.commitments.len() < key_package.min_signers as usize {
+            return Err(Error::InvalidThreshold);
+        }
+        let commitments = self.commitments.get(&key_package.identifier).ok_or(Error::InvalidIdentifier)?;
+        if *commitments != nonces.commitments {
+            return Err(Error::InvalidNonce);
+        }
+        if self.effective_key(&key_package.verifying_key) != self.verifying_key {
+            return Err(Error::InvalidPublicKey);
+        }
+
+        let ids: Vec<Identifier> = self.commitments.keys().cloned().collect();
+        let lambda = lagrange_coefficient(&key_package.identifier, &ids)?;
+        let rho = self.binding_factors[&key_package.identifier];
+
+        let (mut d, mut e) = (nonces.hiding, nonces.binding);
+        if self.negate_nonces {
+            d = -d;
+            e = -e;
+        }
+        let mut s = key_package.signing_share;
+        if self.negate_key {
+            s = -s;
+        }
+        let z = d + rho * e + lambda * s * self.c;
+        d.clear();
+        e.clear();
+        s.clear();
+        Ok(SignatureShare(z))
+    }
+
+    /// Verify a signer's signature share against its verifying share,
+    /// checking `z_i.G = D_i + rho_i.E_i + lambda_i.c.Y_i` up to the same
+    /// negations as in signing.
+    pub fn verify_share(&self, identifier: &Identifier, share: &SignatureShare, verifying_share: &PublicKey) -> bool {
+        let commitments = match self.commitments.get(identifier) {
+            Some(commitments) => commitments,
+            None => return false,
+        };
+        let ids: Vec<Identifier> = self.commitments.keys().cloned().collect();
+        let lambda = match lagrange_coefficient(identifier, &ids) {
+            Ok(lambda) => lambda,
+            Err(_) => return false,
+        };
+
+        let rho = self.binding_factors[identifier];
+        let mut rj = point_mul(&commitments.binding.0, &rho).add_ge_var(&commitments.hiding.0, None);
+        if self.negate_nonces {
+            rj = rj.neg();
+        }
+        if rj.is_infinity() {
+            return false;
+        }
+        let mut y = *verifying_share;
+        if self.negate_key {
+            y.negate();
+        }
+        schnorr_check(&PublicKey(to_affine(&rj)), &share.0, &(lambda * self.c), &y)
+    }
+
+    /// Combine the signature shares of all signers into a signature for the
+    /// group key, `(R, sum(z_i))`.
+    ///
+    /// Fails if the shares are not from exactly the signers of the session,
+    /// fewer than the threshold of signers take part, the public key package
+    /// is for a different group key, or the signature is invalid. In the last
+    /// case `verify_share` tells which signer misbehaved.
+    pub fn aggregate(
+        &self,
+        shares: &BTreeMap<Identifier, SignatureShare>,
+        public_key_package: &PublicKeyPackage,
+    ) -> Result<Signature, Error> {
+        if !shares.keys().eq(self.commitments.keys()) {
+            return Err(Error::InvalidIdentifier);
+        }
+        if self.commitments.len() < public_key_package.min_signers as usize {
+            return Err(Error::InvalidThreshold);
+        }
+        if self.effective_key(&public_key_package.verifying_key) != self.verifying_key {
+            return Err(Error::InvalidPublicKey);
+        }
+
+        let mut z = Scalar::default();
+        for share in shares.values() {
+            z += share.0;
+        }
+        let mut r = self.r;
+        if self.negate_nonces {
+            r.negate();
+        }
+        if !schnorr_check(&r, &z, &self.c, &self.verifying_key) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(Signature { r: self.r, z })
+    }
+
+    /// The group key as the session uses it, negated to an even y-coordinate
+    /// if the ciphersuite requires it.
+    fn effective_key(&self, verifying_key: &PublicKey) -> PublicKey {
+        let mut ret = *verifying_key;
+        if self.negate_key {
+            ret.negate();
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{commit, Session, Signature, SignatureShare, SigningCommitments, SigningNonces};
+    use frost::{split_secret, trusted_dealer_keygen, Ciphersuite, Identifier, KeyPackage};
+    use hex;
+    use rand::thread_rng;
+    use schnorr;
+    use util::{decode32, scalar_from_b32};
+    use {Error, SecretKey};
+
+    fn id(n: u16) -> Identifier {
+        Identifier::new(n).unwrap()
+    }
+
+    // Signer number, hiding and binding nonce randomness, hiding and binding
+    // nonces, binding factor and signature share.
+    type Signer = (u16, &'static str, &'static str, &'static str, &'static str, &'static str, &'static str);
+
+    // RFC 9591 FROST(secp256k1, SHA-256) test vectors, signing with
+    // participants 1 and 3 of the 2-of-3 key from `test_split_secret`.
+    static RFC_SIGNERS: [Signer; 2] = [
+        (
+            1,
+            "bda8e748e599187762cff956f03dc6ea13fc8e04491a0427b7e6e78600f41c52",
+            "2ca682429bf05df435b9927b8edb1d748278f3e42fa11ef358e49bbf4a1b780d",
+            "09764379667f9a9fa61928947bd925a7f162b21886b750d3b11c226d16b32f58",
+            "b2d3f8cb9da70984354c3fc3511b1f6ed21b7205941cb5553565d2ecade8c694",
+            "9bee5aef4012de4b94c9fc1a9a9572181079e293bf1d7545a5af0ef86f824a91",
+            "ca54b18d7449377cfa680760a5770b9e64e201f7ea36b068effeca5fce2155e5",
+        ),
+        (
+            3,
+            "70818dd5170672c4a4285fd593d4f222417f941f3118e1244955e7a1098a35d8",
+            "74ca2da071ed4a2a6cad5087d6758b48a558ab5861c61117fee05757e4b1309e",
+            "0d92e255e5b42ebc2863f8198d946fc10f388c4983073c18cbb77b88e3bf2e34",
+            "1c7243ce00a499b1e7ce3403e7b731d0c820cf108feb8c5ee7c29b4ef43be5e0",
+            "cfe0db2197c94cc355b6ab05610f27f4a874898009c8bf007f2a4e2ce2c8306d",
+            "da13d054e83052568706a6d161d80f112a6bc3f76aa903c022585ae7e091e65e",
+        ),
+    ];
+
+    // Self-generated vectors, not from RFC 9591, which does not define the
+    // FROST(secp256k1, SHA-256-TR) ciphersuite: the same inputs signed with
+    // it, producing a BIP-340 signature. The nonces, binding factors and
+    // shares were computed by this implementation and only guard against
+    // regressions; the signature itself is checked with `schnorr::verify`.
+    static TR_SIGNERS: [Signer; 2] = [
+        (
+            1,
+            "bda8e748e599187762cff956f03dc6ea13fc8e04491a0427b7e6e78600f41c52",
+            "2ca682429bf05df435b9927b8edb1d748278f3e42fa11ef358e49bbf4a1b780d",
+            "58cd30723da418156fe9b71870a118e0bbc3d0353ba7c760f9bbc8d60c3dab29",
+            "c22289cc43b82ed938d4b2288efb7381c405fb59f5d43bddc543d98838c60b19",
+            "55a3e44879db6daf00c81eb28e828869560c0901f347baff524f1c91a6669604",
+            "2ffc305d1694fd84108b84d98306a1af807c6ad9bc3a2d8e448a09643202a15b",
+        ),
+        (
+            3,
+            "70818dd5170672c4a4285fd593d4f222417f941f3118e1244955e7a1098a35d8",
+            "74ca2da071ed4a2a6cad5087d6758b48a558ab5861c61117fee05757e4b1309e",
+            "a4109db0a5db30fac8cd1f4e272ff02e08258928f067d82c63d97279b114514a",
+            "ce3837bd963f0d81002279f7bb9eefceac64435f638885c2beae6f1dd881fd9e",
+            "444c1cc7cfe48f4577cce65488f337a9cc8c33dea4cfa986eb590bd8e2b1fa2d",
+            "a8c392566ea29e852b4080a028bf5547166c87e703e4fb7136d4ebef65f99b3f",
+        ),
+    ];
+
+    fn check_vectors(suite: Ciphersuite, signers: &[Signer], expected: &str) -> Signature {
+        let secret = SecretKey::from_hex("0d004150d27c3bf2a42f312683d35fac7394b1e9e318249c1bfe7f0795a83114").unwrap();
+        let coefficient = scalar_from_b32(&decode32("fbf85eadae3058ea14f19148bb72b45e4399c0b16028acaf0395c9b03c823579"));
+        let (shares, public_key_package) = split_secret(&secret, &[coefficient], 3).unwrap();
+        let msg = hex::decode("74657374").unwrap();
+
+        let mut nonces = BTreeMap::new();
+        let mut commitments = BTreeMap::new();
+        for signer in signers {
+            let share = &shares[&id(signer.0)];
+            let n = SigningNonces::from_randomness(suite, &decode32(signer.1), &decode32(signer.2), &share.value);
+            assert_eq!(hex::encode(n.hiding.b32()), signer.3);
+            assert_eq!(hex::encode(n.binding.b32()), signer.4);
+            commitments.insert(id(signer.0), n.commitments());
+            nonces.insert(id(signer.0), n);
+        }
+
+        let session = Session::new(suite, &public_key_package.verifying_key, &commitments, &msg).unwrap();
+        let mut sig_shares = BTreeMap::new();
+        for signer in signers {
+            assert_eq!(hex::encode(session.binding_factors[&id(signer.0)].b32()), signer.5);
+            let key_package = KeyPackage::from_secret_share(&shares[&id(signer.0)]).unwrap();
+            let n = nonces.remove(&id(signer.0)).unwrap();
+            let share = session.sign(n, &key_package).unwrap();
+            assert_eq!(hex::encode(share.serialize()), signer.6);
+            assert!(session.verify_share(&id(signer.0), &share, &key_package.verifying_share));
+            sig_shares.insert(id(signer.0), share);
+        }
+
+        let sig = session.aggregate(&sig_shares, &public_key_package).unwrap();
+        assert!(sig.verify(suite, &msg, &public_key_package.verifying_key));
+        match suite {
+            Ciphersuite::Secp256k1Sha256 => assert_eq!(hex::encode(&sig.serialize()[..]), expected),
+            Ciphersuite::Secp256k1Sha256Tr => assert_eq!(hex::encode(&sig.to_bip340().serialize()[..]), expected),
+        }
+        sig
+    }
+
+    #[test]
+    fn test_rfc_vectors() {
+        let sig = check_vectors(
+            Ciphersuite::Secp256k1Sha256,
+            &RFC_SIGNERS,
+            "024c1ad4e031872661fa6ebd05dfc7fb30db08b38d79f0edbc82051ae931381bc6a46881e25c7989d3816eae32074f1ab0d49ee908a59713ed5284c6bade7cfb02",
+        );
+        assert_eq!(Signature::parse(&sig.serialize()), Ok(sig));
+    }
+
+    #[test]
+    fn test_taproot_generated_vectors() {
+        let sig = check_vectors(
+            Ciphersuite::Secp256k1Sha256Tr,
+            &TR_SIGNERS,
+            "0c776a9516a77808b70a31e74f1464814a6fcf897fb3a6bd84c7a9a9a7a5bcb8d8bfc2b385379c093bcc0579abc5f6f696e8f2c0c01f28ff7b5ef55397fc3c9a",
+        );
+        let pubkey = SecretKey::from_hex("0d004150d27c3bf2a42f312683d35fac7394b1e9e318249c1bfe7f0795a83114").unwrap();
+        let xonly = ::PublicKey::from_secret_key(&pubkey).x_only().0;
+        assert!(schnorr::verify(&sig.to_bip340(), b"test", &xonly));
+    }
+
+    #[test]
+    fn test_sign_bip340() {
+        // Random 3-of-5 keys, about half of which have an odd group key, with
+        // different sets of signers.
+        let mut rng = thread_rng();
+        let suite = Ciphersuite::Secp256k1Sha256Tr;
+        for round in 0..8 {
+            let (shares, public_key_package) = trusted_dealer_keygen(&mut rng, 3, 5).unwrap();
+            let signers: Vec<Identifier> = [1, 2, 3, 4, 5].iter().skip(round % 3).take(3).map(|n| id(*n)).collect();
+            let msg = [round as u8; 32];
+
+            let mut nonces = BTreeMap::new();
+            let mut commitments = BTreeMap::new();
+            for signer in signers.iter() {
+                let (n, c) = commit(suite, &mut rng, &shares[signer].value);
+                nonces.insert(*signer, n);
+                commitments.insert(*signer, c);
+            }
+            let session = Session::new(suite, &public_key_package.verifying_key, &commitments, &msg).unwrap();
+            let mut sig_shares = BTreeMap::new();
+            for signer in signers.iter() {
+                let key_package = KeyPackage::from_secret_share(&shares[signer]).unwrap();
+                let share = session.sign(nonces.remove(signer).unwrap(), &key_package).unwrap();
+                assert!(session.verify_share(signer, &share, &public_key_package.verifying_shares[signer]));
+                sig_shares.insert(*signer, share);
+            }
+            let sig = session.aggregate(&sig_shares, &public_key_package).unwrap();
+            let xonly = public_key_package.verifying_key.x_only().0;
+            assert!(schnorr::verify(&sig.to_bip340(), &msg, &xonly));
+            assert!(!schnorr::verify(&sig.to_bip340(), &[0xff; 32], &xonly));
+        }
+    }
+
+    #[test]
+    fn test_sign_invalid() {
+        let mut rng = thread_rng();
+        let suite = Ciphersuite::Secp256k1Sha256;
+        let (shares, public_key_package) = trusted_dealer_keygen(&mut rng, 2, 3).unwrap();
+        let key_packages: Vec<KeyPackage> = shares.values().map(|s| KeyPackage::from_secret_share(s).unwrap()).collect();
+        let msg = b"message";
+
+        let mut nonces = Vec::new();
+        let mut commitments = BTreeMap::new();
+        for key_package in key_packages.iter() {
+            let (n, c) = commit(suite, &mut rng, &key_package.signing_share);
+            nonces.push(n);
+            commitments.insert(key_package.identifier, c);
+        }
+        let only_one: BTreeMap<Identifier, SigningCommitments> =
+            commitments.iter().take(1).map(|(i, c)| (*i, *c)).collect();
+        assert_eq!(
+            Session::new(suite, &public_key_package.verifying_key, &BTreeMap::new(), msg).err(),
+            Some(Error::InvalidThreshold)
+        );
+
+        // Too few signers.
+        let session = Session::new(suite, &public_key_package.verifying_key, &only_one, msg).unwrap();
+        let (n, _) = commit(suite, &mut rng, &key_packages[0].signing_share);
+        assert_eq!(session.sign(n, &key_packages[0]).err(), Some(Error::InvalidThreshold));
+
+        // Nonces that do not match the commitments.
+        let session = Session::new(suite, &public_key_package.verifying_key, &commitments, msg).unwrap();
+        let (n, _) = commit(suite, &mut rng, &key_packages[0].signing_share);
+        assert_eq!(session.sign(n, &key_packages[0]).err(), Some(Error::InvalidNonce));
+
+        // A key package for another group key.
+        let mut other = key_packages[0].clone();
+        other.verifying_key = key_packages[0].verifying_share;
+        let n = nonces.remove(0);
+        assert_eq!(session.sign(n, &other).err(), Some(Error::InvalidPublicKey));
+
+        // A wrong share is caught by share verification and aggregation.
+        let mut sig_shares = BTreeMap::new();
+        for (key_package, n) in key_packages[1..].iter().zip(nonces) {
+            sig_shares.insert(key_package.identifier, session.sign(n, key_package).unwrap());
+        }
+        let first = key_packages[0].identifier;
+        let bad = SignatureShare(scalar_from_b32(&[1u8; 32]));
+        assert!(!session.verify_share(&first, &bad, &key_packages[0].verifying_share));
+        assert_eq!(session.aggregate(&sig_shares, &public_key_package).err(), Some(Error::InvalidIdentifier));
+        sig_shares.insert(first, bad);
+        assert_eq!(session.aggregate(&sig_shares, &public_key_package).err(), Some(Error::InvalidSignature));
+        assert_eq!(SignatureShare::parse(&[0xff; 32]), Err(Error::InvalidSignature));
+    }
+}
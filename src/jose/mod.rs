@@ -0,0 +1,245 @@
+//! JOSE support for secp256k1 keys (RFC 8812): JSON Web Keys with
+//! `kty: EC` and `crv: secp256k1`, and ES256K signatures in the JWS compact
+//! serialization.
+
+use base64;
+use json::Value;
+use secp256k1::{Error, Message, PublicKey, SecretKey, Signature};
+use sha2::{Digest, Sha256};
+
+const ALGORITHM: &str = "ES256K";
+
+/// Decode a base64url member holding a 32-byte big endian value.
+fn coordinate(jwk: &Value, name: &str) -> Result<[u8; 32], Error> {
+    let encoded = jwk.get(name).and_then(Value::as_str).ok_or(Error::InvalidJwk)?;
+    let bytes = base64::decode_url(encoded).or(Err(Error::InvalidJwk))?;
+    if bytes.len() != 32 {
+        return Err(Error::InvalidJwk);
+    }
+    Ok(*array_ref!(bytes, 0, 32))
+}
+
+/// Parse a JWK document and check that it describes a secp256k1 EC key.
+fn parse_jwk(jwk: &str) -> Result<Value, Error> {
+    let jwk = Value::parse(jwk).or(Err(Error::InvalidJwk))?;
+    if jwk.get("kty").and_then(Value::as_str) != Some("EC")
+        || jwk.get("crv").and_then(Value::as_str) != Some("secp256k1")
+    {
+        return Err(Error::InvalidJwk);
+    }
+    Ok(jwk)
+}
+
+fn public_key_from_jwk(jwk: &Value) -> Result<PublicKey, Error> {
+    let mut raw = [0u8; 65];
+    raw[0] = 0x04;
+    raw[1..33].copy_from_slice(&coordinate(jwk, "x")?);
+    raw[33..].copy_from_slice(&coordinate(jwk, "y")?);
+    PublicKey::parse(&raw)
+}
+
+impl PublicKey {
+    /// Serialize as a JSON Web Key with base64url `x` and `y` coordinates.
+    pub fn to_jwk(&self) -> String {
+        let raw = self.serialize();
+        format!(
+            r#"{{"kty":"EC","crv":"secp256k1","x":"{}","y":"{}"}}"#,
+            base64::encode_url(&raw[1..33]),
+            base64::encode_url(&raw[33..])
+        )
+    }
+
+    /// Parse a JSON Web Key. Private keys are accepted too, in which case
+    /// the `d` member is ignored.
+    pub fn from_jwk(jwk: &str) -> Result<PublicKey, Error> {
+        public_key_from_jwk(&parse_jwk(jwk)?)
+    }
+}
+
+impl SecretKey {
+    /// Serialize as a private JSON Web Key, including the public
+    /// coordinates.
+    pub fn to_jwk(&self) -> String {
+        let public = PublicKey::from_secret_key(self).to_jwk();
+        format!(
+            r#"{},"d":"{}"}}"#,
+            &public[..(public.len() - 1)],
+            base64::encode_url(&self.serialize())
+        )
+    }
+
+    /// Parse a private JSON Web Key. If the public coordinates are present
+    /// they must belong to the secret key.
+    pub fn from_jwk(jwk: &str) -> Result<SecretKey, Error> {
+        let jwk = parse_jwk(jwk)?;
+        let seckey = SecretKey::parse(&coordinate(&jwk, "d")?)?;
+        let has_public = jwk.get("x").is_some() || jwk.get("y").is_some();
+        if has_public && public_key_from_jwk(&jwk)? != PublicKey::from_secret_key(&seckey) {
+            return Err(Error::InvalidPublicKey);
+        }
+        Ok(seckey)
+    }
+}
+
+fn signing_input_hash(signing_input: &str) -> Message {
+    let hash = Sha256::digest(signing_input.as_bytes());
+    Message::parse(array_ref!(hash, 0, 32))
+}
+
+/// Sign a payload as an ES256K JWS in compact serialization, with the
+/// protected header `{"alg":"ES256K"}`. The signature is the raw 64-byte
+/// `r || s` form required by JOSE.
+pub fn sign(payload: &[u8], seckey: &SecretKey) -> Result<String, Error> {
+    let header = format!(r#"{{"alg":"{}"}}"#, ALGORITHM);
+    let signing_input = format!(
+        "{}.{}",
+        base64::encode_url(header.as_bytes()),
+        base64::encode_url(payload)
+    );
+    let (signature, _) = Message::sign(&signing_input_hash(&signing_input), seckey)?;
+    Ok(format!("{}.{}", signing_input, base64::encode_url(&signature.serialize())))
+}
+
+/// Verify an ES256K JWS in compact serialization and return its payload.
+/// The protected header must name the ES256K algorithm.
+pub fn verify(jws: &str, pubkey: &PublicKey) -> Result<Vec<u8>, Error> {
+    let parts: Vec<&str> = jws.split('.').collect();
+    if parts.len() != 3 {
+        return Err(Error::InvalidJws);
+    }
+
+    let header = base64::decode_url(parts[0]).or(Err(Error::InvalidJws))?;
+    let header = String::from_utf8(header).or(Err(Error::InvalidJws))?;
+    let header = Value::parse(&header).or(Err(Error::InvalidJws))?;
+    if header.get("alg").and_then(Value::as_str) != Some(ALGORITHM) {
+        return Err(Error::InvalidJws);
+    }
+    let payload = base64::decode_url(parts[1]).or(Err(Error::InvalidJws))?;
+
+    let raw = base64::decode_url(parts[2]).or(Err(Error::InvalidJws))?;
+    if raw.len() != 64 {
+        return Err(Error::InvalidSignature);
+    }
+    let signature = Signature::parse_standard(array_ref!(raw, 0, 64))?;
+    let signing_input = &jws[..(parts[0].len() + parts[1].len() + 1)];
+    if !Signature::verify(&signing_input_hash(signing_input), &signature, pubkey) {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64;
+    use super::{sign, verify};
+    use {Error, Message, PublicKey, SecretKey};
+
+    // josekit data/jwk/EC_secp256k1_private.jwk and EC_secp256k1_public.jwk
+    const PRIVATE_JWK: &str = r#"{
+        "kty":"EC",
+        "crv":"secp256k1",
+        "d":"quvz-ujJMCx4_7F8yqRQ-8XMJ_emd6OCs7__Shra3ks",
+        "x":"SYhti4CCtyMdwy0rZ6LgzOYVvKWCVLTnQxwFX7OAmBY",
+        "y":"k2ZfPG-eM4ec9eQqWY1Q2Igt91EbTWogiqQsaNmaYio"
+    }"#;
+    const PUBLIC_JWK: &str = r#"{
+        "kty":"EC",
+        "crv":"secp256k1",
+        "x":"SYhti4CCtyMdwy0rZ6LgzOYVvKWCVLTnQxwFX7OAmBY",
+        "y":"k2ZfPG-eM4ec9eQqWY1Q2Igt91EbTWogiqQsaNmaYio"
+    }"#;
+    // josekit data/jwt/ES256K.jwt, signed with the key above.
+    const JWT: &str = "eyJhbGciOiJFUzI1NksifQ.\
+                       eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ.\
+                       xcanu-N1dF3KKCKKT0eDqfViKxOYkuKKzo8uBg7JfsaBTsKR4J5NjFsCEX6jfwwbr7wfk92Qem-BLc45NH_vWA";
+
+    #[test]
+    fn test_jwk_round_trip() {
+        let seckey = SecretKey::from_jwk(PRIVATE_JWK).unwrap();
+        let pubkey = PublicKey::from_jwk(PUBLIC_JWK).unwrap();
+        assert_eq!(PublicKey::from_secret_key(&seckey), pubkey);
+        assert_eq!(PublicKey::from_jwk(PRIVATE_JWK).unwrap(), pubkey);
+
+        assert_eq!(
+            pubkey.to_jwk(),
+            r#"{"kty":"EC","crv":"secp256k1","x":"SYhti4CCtyMdwy0rZ6LgzOYVvKWCVLTnQxwFX7OAmBY","y":"k2ZfPG-eM4ec9eQqWY1Q2Igt91EbTWogiqQsaNmaYio"}"#
+        );
+        assert_eq!(
+            seckey.to_jwk(),
+            r#"{"kty":"EC","crv":"secp256k1","x":"SYhti4CCtyMdwy0rZ6LgzOYVvKWCVLTnQxwFX7OAmBY","y":"k2ZfPG-eM4ec9eQqWY1Q2Igt91EbTWogiqQsaNmaYio","d":"quvz-ujJMCx4_7F8yqRQ-8XMJ_emd6OCs7__Shra3ks"}"#
+        );
+        assert_eq!(SecretKey::from_jwk(&seckey.to_jwk()).unwrap(), seckey);
+        assert_eq!(PublicKey::from_jwk(&pubkey.to_jwk()).unwrap(), pubkey);
+
+        // The public coordinates are optional in a private key.
+        let d_only = r#"{"kty":"EC","crv":"secp256k1","d":"quvz-ujJMCx4_7F8yqRQ-8XMJ_emd6OCs7__Shra3ks"}"#;
+        assert_eq!(SecretKey::from_jwk(d_only).unwrap(), seckey);
+    }
+
+    #[test]
+    fn test_invalid_jwk() {
+        assert_eq!(SecretKey::from_jwk(PUBLIC_JWK), Err(Error::InvalidJwk));
+        assert_eq!(PublicKey::from_jwk("not json"), Err(Error::InvalidJwk));
+        assert_eq!(PublicKey::from_jwk(&"{\"a\":[".repeat(100_000)), Err(Error::InvalidJwk));
+        assert_eq!(PublicKey::from_jwk(&PUBLIC_JWK.replace("secp256k1", "P-256")), Err(Error::InvalidJwk));
+        assert_eq!(PublicKey::from_jwk(&PUBLIC_JWK.replace("\"EC\"", "\"OKP\"")), Err(Error::InvalidJwk));
+        // Padded and truncated coordinates.
+        assert_eq!(
+            PublicKey::from_jwk(&PUBLIC_JWK.replace("7OAmBY", "7OAmBY=")),
+            Err(Error::InvalidJwk)
+        );
+        assert_eq!(PublicKey::from_jwk(&PUBLIC_JWK.replace("7OAmBY", "7OAm")), Err(Error::InvalidJwk));
+        // A point that is not on the curve.
+        assert_eq!(
+            PublicKey::from_jwk(&PUBLIC_JWK.replace("k2ZfPG", "k2ZfPH")),
+            Err(Error::InvalidPublicKey)
+        );
+        // Public coordinates of another key.
+        let other = SecretKey::parse(&[0x01; 32]).unwrap();
+        let mismatched = format!(
+            r#"{},"d":"quvz-ujJMCx4_7F8yqRQ-8XMJ_emd6OCs7__Shra3ks"}}"#,
+            PublicKey::from_secret_key(&other).to_jwk().trim_end_matches('}')
+        );
+        assert_eq!(SecretKey::from_jwk(&mismatched), Err(Error::InvalidPublicKey));
+    }
+
+    #[test]
+    fn test_verify_external_jws() {
+        let pubkey = PublicKey::from_jwk(PUBLIC_JWK).unwrap();
+        let payload = verify(JWT, &pubkey).unwrap();
+        assert_eq!(
+            &payload[..],
+            &b"{\"iss\":\"joe\",\r\n \"exp\":1300819380,\r\n \"http://example.com/is_root\":true}"[..]
+        );
+
+        let other = PublicKey::from_secret_key(&SecretKey::parse(&[0x01; 32]).unwrap());
+        assert_eq!(verify(JWT, &other), Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let seckey = SecretKey::from_jwk(PRIVATE_JWK).unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        let jws = sign(b"{\"sub\":\"1234567890\"}", &seckey).unwrap();
+        assert!(jws.starts_with("eyJhbGciOiJFUzI1NksifQ."));
+        assert_eq!(verify(&jws, &pubkey).unwrap(), b"{\"sub\":\"1234567890\"}");
+
+        let parts: Vec<&str> = jws.split('.').collect();
+        assert_eq!(base64::decode_url(parts[2]).unwrap().len(), 64);
+
+        // Tampered payload, wrong algorithm, DER signature and malformed tokens.
+        let tampered = format!("{}.{}.{}", parts[0], base64::encode_url(b"{\"sub\":\"0\"}"), parts[2]);
+        assert_eq!(verify(&tampered, &pubkey), Err(Error::InvalidSignature));
+        let es256 = format!("{}.{}.{}", base64::encode_url(br#"{"alg":"ES256"}"#), parts[1], parts[2]);
+        assert_eq!(verify(&es256, &pubkey), Err(Error::InvalidJws));
+        let (signature, _) = Message::sign(&Message::parse(&[0x42; 32]), &seckey).unwrap();
+        let der = format!("{}.{}.{}", parts[0], parts[1], base64::encode_url(&signature.serialize_der()));
+        assert_eq!(verify(&der, &pubkey), Err(Error::InvalidSignature));
+        assert_eq!(verify(&format!("{}.{}", parts[0], parts[1]), &pubkey), Err(Error::InvalidJws));
+        assert_eq!(verify(&format!("{}.!.{}", parts[0], parts[2]), &pubkey), Err(Error::InvalidJws));
+        // A header nested deep enough to exhaust a recursive parser.
+        let nested = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        let deep = format!("{}.{}.{}", base64::encode_url(nested.as_bytes()), parts[1], parts[2]);
+        assert_eq!(verify(&deep, &pubkey), Err(Error::InvalidJws));
+    }
+}
@@ -0,0 +1,330 @@
+//! A small JSON reader, sufficient for EIP-712 typed data documents and
+//! JOSE keys and headers.
+//! Numbers are kept in their textual form so that 256-bit integers survive
+//! parsing unchanged.
+
+use secp256k1::Error;
+
+/// The deepest nesting of arrays and objects accepted by the parser.
+pub const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// A parsed JSON value. Object members keep their document order.
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Parse a complete JSON document.
+    pub fn parse(s: &str) -> Result<Value, Error> {
+        let mut parser = Parser {
+            input: s.as_bytes(),
+            pos: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.whitespace();
+        if parser.pos != parser.input.len() {
+            return Err(Error::InvalidTypedData);
+        }
+        Ok(value)
+    }
+
+    /// Look up a member of an object.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match *self {
+            Value::Object(ref members) => members.iter().find(|m| m.0 == key).map(|m| &m.1),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match *self {
+            Value::Array(ref values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match *self {
+            Value::Object(ref members) => Some(members),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn whitespace(&mut self) {
+        while self.pos < self.input.len() {
+            match self.input[self.pos] {
+                b' ' | b'\t' | b'\n' | b'\r' => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).cloned()
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), Error> {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(Error::InvalidTypedData)
+        }
+    }
+
+    fn literal(&mut self, word: &[u8], value: Value) -> Result<Value, Error> {
+        if self.input[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(Error::InvalidTypedData)
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        self.whitespace();
+        match self.peek() {
+            Some(b'{') => self.nested(Parser::object),
+            Some(b'[') => self.nested(Parser::array),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b't') => self.literal(b"true", Value::Bool(true)),
+            Some(b'f') => self.literal(b"false", Value::Bool(false)),
+            Some(b'n') => self.literal(b"null", Value::Null),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            _ => Err(Error::InvalidTypedData),
+        }
+    }
+
+    fn nested(&mut self, f: fn(&mut Self) -> Result<Value, Error>) -> Result<Value, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::InvalidTypedData);
+        }
+        self.depth += 1;
+        let ret = f(self);
+        self.depth -= 1;
+        ret
+    }
+
+    fn object(&mut self) -> Result<Value, Error> {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+        self.whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.whitespace();
+            self.expect(b':')?;
+            let value = self.value()?;
+            members.push((key, value));
+            self.whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(Error::InvalidTypedData),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        self.whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(values));
+                }
+                _ => return Err(Error::InvalidTypedData),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        if self.pos + 4 > self.input.len() {
+            return Err(Error::InvalidTypedData);
+        }
+        let mut v = 0u32;
+        for b in &self.input[self.pos..(self.pos + 4)] {
+            let d = (*b as char).to_digit(16).ok_or(Error::InvalidTypedData)?;
+            v = (v << 4) | d;
+        }
+        self.pos += 4;
+        Ok(v)
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            let b = self.peek().ok_or(Error::InvalidTypedData)?;
+            self.pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let e = self.peek().ok_or(Error::InvalidTypedData)?;
+                    self.pos += 1;
+                    let c = match e {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut code = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&code) {
+                                // A surrogate pair.
+                                self.expect(b'\\')?;
+                                self.expect(b'u')?;
+                                let low = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(Error::InvalidTypedData);
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            ::std::char::from_u32(code).ok_or(Error::InvalidTypedData)?
+                        }
+                        _ => return Err(Error::InvalidTypedData),
+                    };
+                    let mut buf = [0u8; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                0x00..=0x1f => return Err(Error::InvalidTypedData),
+                _ => bytes.push(b),
+            }
+        }
+        String::from_utf8(bytes).or(Err(Error::InvalidTypedData))
+    }
+
+    /// Skip a non-empty run of decimal digits.
+    fn digits(&mut self) -> Result<(), Error> {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(Error::InvalidTypedData);
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        // No leading zeros in the integer part.
+        if self.peek() == Some(b'0') {
+            self.pos += 1;
+        } else {
+            self.digits()?;
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.digits()?;
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.pos += 1;
+            }
+            self.digits()?;
+        }
+        let text = ::std::str::from_utf8(&self.input[start..self.pos]).or(Err(Error::InvalidTypedData))?;
+        Ok(Value::Number(text.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Value, MAX_DEPTH};
+
+    #[test]
+    fn test_parse() {
+        let value = Value::parse(
+            r#" { "a": [1, -2.5e3, "x\"\u00e9\ud83d\ude00", true, false, null], "b": {} } "#,
+        ).unwrap();
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                (
+                    "a".to_string(),
+                    Value::Array(vec![
+                        Value::Number("1".to_string()),
+                        Value::Number("-2.5e3".to_string()),
+                        Value::String("x\"\u{e9}\u{1f600}".to_string()),
+                        Value::Bool(true),
+                        Value::Bool(false),
+                        Value::Null,
+                    ]),
+                ),
+                ("b".to_string(), Value::Object(vec![])),
+            ])
+        );
+        assert_eq!(value.get("b"), Some(&Value::Object(vec![])));
+        assert_eq!(value.get("c"), None);
+
+        // Large integers are preserved verbatim.
+        assert_eq!(
+            Value::parse("115792089237316195423570985008687907853269984665640564039457584007913129639935"),
+            Ok(Value::Number("115792089237316195423570985008687907853269984665640564039457584007913129639935".to_string()))
+        );
+
+        for bad in &[
+            "", "{", "[1,]", "{\"a\" 1}", "tru", "\"abc", "1 2", "-", "\"\\x\"", "01", "-00", "1.", "1e", ".5",
+        ] {
+            assert!(Value::parse(bad).is_err(), "{}", bad);
+        }
+        for good in &["0", "-0", "0.5", "10", "1e+0", "-0.0E-1"] {
+            assert_eq!(Value::parse(good), Ok(Value::Number(good.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_depth() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(Value::parse(&nested(MAX_DEPTH)).is_ok());
+        assert!(Value::parse(&nested(MAX_DEPTH + 1)).is_err());
+        assert!(Value::parse(&"[{\"a\":".repeat(100_000)).is_err());
+        assert!(Value::parse(&nested(1_000_000)).is_err());
+    }
+}
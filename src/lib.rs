@@ -14,12 +14,26 @@ extern crate secp256k1_test;
 
 pub mod ecmult;
 pub mod schnorr;
+pub mod ethereum;
+pub mod eip712;
+mod json;
+pub mod base64;
+pub mod bitcoin;
+pub mod bip32;
+pub mod bip39;
+pub mod pem;
+pub mod jose;
+pub mod musig;
+pub mod frost;
+mod util;
 
 
 pub use secp256k1::SharedSecret;
 pub use secp256k1::Error;
-pub use secp256k1::{PublicKey, SecretKey};
+pub use secp256k1::{Parity, PublicKey, SecretKey, XOnlyPublicKey};
 pub use secp256k1::Message;
+pub use secp256k1::{NonceFunction, Rfc6979};
 pub use secp256k1::RecoveryId;
+pub use secp256k1::RecoverableSignature;
 pub use secp256k1::signature::Signature;
 
@@ -0,0 +1,217 @@
+//! MuSig2 multi-signatures (BIP-327): key aggregation with plain and x-only
+//! tweaks, two-nonce generation and aggregation, partial signing and
+//! verification, and aggregation of partial signatures into a BIP-340
+//! Schnorr signature valid for the aggregate key.
+//!
+//! A signing round for keys `pk_1..pk_u` goes as follows. Every signer
+//! builds the same `KeyAggContext` and applies the same tweaks, calls
+//! `nonce_gen` and sends its `PubNonce` to the others. The public nonces are
+//! summed with `aggregate_nonces`, each signer creates a `Session` for the
+//! message and produces a `PartialSignature` with its `SecNonce`, and any
+//! party combines the partial signatures with `Session::aggregate`.
+
+mod nonce;
+mod session;
+
+pub use self::nonce::{aggregate_nonces, nonce_gen, AggNonce, PubNonce, SecNonce};
+pub use self::session::{PartialSignature, Session};
+
+use schnorr::{tagged_hash, TaggedHasher};
+use secp256k1::group::Jacobian;
+use secp256k1::{Error, PublicKey, Scalar, XOnlyPublicKey};
+use util::{point_mul, scalar_from_b32, scalar_one, to_affine};
+
+/// Sort public keys by their compressed encoding (KeySort), so that signers
+/// can agree on the aggregate key without agreeing on an order first.
+pub fn sort_keys(pubkeys: &[PublicKey]) -> Vec<PublicKey> {
+    let mut ret = pubkeys.to_vec();
+    ret.sort_by_key(|pk| pk.serialize_compressed());
+    ret
+}
+
+/// The aggregate key of a list of public keys, together with the tweaks
+/// applied to it so far.
+///
+/// The aggregate key is `Q = a_1.P_1 + ... + a_u.P_u`, where every `a_i` is
+/// derived from the whole key list so that no signer can choose its key as
+/// a function of the others. The second distinct key gets `a_i = 1`.
+#[derive(Debug, Clone)]
+pub struct KeyAggContext {
+    pubkeys: Vec<[u8; 33]>,
+    coefficients: Vec<Scalar>,
+    q: PublicKey,
+    gacc: Scalar,
+    tacc: Scalar,
+}
+
+impl KeyAggContext {
+    /// Aggregate the public keys in the given order. Fails if the list is
+    /// empty or the aggregate key is infinity.
+    pub fn new(pubkeys: &[PublicKey]) -> Result<KeyAggContext, Error> {
+        let serialized: Vec<[u8; 33]> = pubkeys.iter().map(|pk| pk.serialize_compressed()).collect();
+        let first = *serialized.first().ok_or(Error::InvalidPublicKey)?;
+        let second = serialized.iter().find(|pk| **pk != first).cloned();
+
+        let list: Vec<&[u8]> = serialized.iter().map(|pk| &pk[..]).collect();
+        let l = tagged_hash(b"KeyAgg list", &list);
+        let hasher = TaggedHasher::new(b"KeyAgg coefficient");
+        let coefficients: Vec<Scalar> = serialized
+            .iter()
+            .map(|pk| {
+                if Some(*pk) == second {
+                    scalar_one()
+                } else {
+                    scalar_from_b32(&hasher.hash(&[&l, pk]))
+                }
+            })
+            .collect();
+
+        let mut qj = Jacobian::default();
+        qj.set_infinity();
+        for (pk, a) in pubkeys.iter().zip(coefficients.iter()) {
+            qj = qj.add_var(&point_mul(&pk.0, a), None);
+        }
+        let q = to_affine(&qj);
+        if q.is_infinity() {
+            return Err(Error::InvalidPublicKey);
+        }
+
+        Ok(KeyAggContext {
+            pubkeys: serialized,
+            coefficients,
+            q: PublicKey(q),
+            gacc: scalar_one(),
+            tacc: Scalar::default(),
+        })
+    }
+
+    /// The aggregate key, including its y-coordinate. This is the key to
+    /// derive further keys from, e.g. with a BIP-32 style plain tweak.
+    pub fn public_key(&self) -> PublicKey {
+        self.q
+    }
+
+    /// The x-only aggregate key, which BIP-340 signatures verify against.
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.q.x_only().0
+    }
+
+    /// Tweak the aggregate key by adding `t.G`. Fails if the tweak is not
+    /// below the group order or the result is infinity, in which case the
+    /// context is left unchanged.
+    pub fn plain_tweak_add(&mut self, tweak: &[u8; 32]) -> Result<(), Error> {
+        self.tweak_add(tweak, false)
+    }
+
+    /// Tweak the x-only aggregate key by adding `t.G`, negating the key first
+    /// if its y-coordinate is odd, as for a Taproot output key. Fails if the
+    /// tweak is not below the group order or the result is infinity, in
+    /// which case the context is left unchanged.
+    pub fn xonly_tweak_add(&mut self, tweak: &[u8; 32]) -> Result<(), Error> {
+        self.tweak_add(tweak, true)
+    }
+
+    fn tweak_add(&mut self, tweak: &[u8; 32], xonly: bool) -> Result<(), Error> {
+        let negate = xonly && self.q.x_only().1.is_odd();
+        let mut q = self.q;
+        if negate {
+            q.negate();
+        }
+        q.tweak_add_assign(tweak)?;
+
+        let mut t = Scalar::default();
+        t.set_b32(tweak);
+        if negate {
+            self.gacc = -self.gacc;
+            self.tacc = -self.tacc;
+        }
+        self.tacc += t;
+        self.q = q;
+        Ok(())
+    }
+
+    /// The coefficient `a_i` of a key in the list, if present.
+    fn coefficient(&self, pubkey: &PublicKey) -> Option<Scalar> {
+        let pk = pubkey.serialize_compressed();
+        self.pubkeys
+            .iter()
+            .position(|p| *p == pk)
+            .map(|i| self.coefficients[i])
+    }
+
+    /// `g.gacc`, where `g` is -1 if the aggregate key has an odd y-coordinate
+    /// and 1 otherwise. Multiplying a signer's secret key by this, and by its
+    /// coefficient, gives its share of the secret key for the x-only key.
+    fn parity_acc(&self) -> Scalar {
+        if self.q.x_only().1.is_odd() {
+            -self.gacc
+        } else {
+            self.gacc
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sort_keys, KeyAggContext};
+    use util::decode32;
+    use {Error, PublicKey};
+
+    // BIP-327 key aggregation test vectors.
+    const PUBKEYS: [&str; 7] = [
+        "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+        "03dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+        "023590a94e768f8e1815c2f24b4d80a8e3149316c3518ce7b7ad338368d038ca66",
+        "020000000000000000000000000000000000000000000000000000000000000005",
+        "02fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30",
+        "04f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+        "03935f972da013f80ae011890fa89b67a27b7be6ccb24d3274d18b2d4067f261a9",
+    ];
+
+    fn pubkeys(indices: &[usize]) -> Vec<PublicKey> {
+        indices.iter().map(|i| PublicKey::from_hex(PUBKEYS[*i]).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_key_agg() {
+        let vectors: [(&[usize], &str); 4] = [
+            (&[0, 1, 2], "90539eede565f5d054f32cc0c220126889ed1e5d193baf15aef344fe59d4610c"),
+            (&[2, 1, 0], "6204de8b083426dc6eaf9502d27024d53fc826bf7d2012148a0575435df54b2b"),
+            (&[0, 0, 0], "b436e3bad62b8cd409969a224731c193d051162d8c5ae8b109306127da3aa935"),
+            (&[0, 0, 1, 1], "69bc22bfa5d106306e48a20679de1d7389386124d07571d0d872686028c26a3e"),
+        ];
+        for &(indices, expected) in vectors.iter() {
+            let ctx = KeyAggContext::new(&pubkeys(indices)).unwrap();
+            assert_eq!(ctx.x_only_public_key().to_hex(), expected);
+        }
+    }
+
+    #[test]
+    fn test_key_agg_invalid() {
+        // Invalid public keys: not on the curve, x not below the field size,
+        // and an uncompressed prefix on a 33-byte key.
+        for i in &[3, 4, 5] {
+            assert_eq!(PublicKey::from_hex(PUBKEYS[*i]), Err(Error::InvalidPublicKey));
+        }
+        assert_eq!(KeyAggContext::new(&[]).err(), Some(Error::InvalidPublicKey));
+
+        // A tweak not below the group order.
+        let mut ctx = KeyAggContext::new(&pubkeys(&[0, 1])).unwrap();
+        let order = decode32("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141");
+        assert_eq!(ctx.xonly_tweak_add(&order), Err(Error::InvalidTweak));
+
+        // A tweak that takes the aggregate key of a single key to infinity.
+        let mut ctx = KeyAggContext::new(&pubkeys(&[6])).unwrap();
+        let before = ctx.public_key();
+        let tweak = decode32("252e4bd67410a76cdf933d30eaa1608214037f1b105a013eccd3c5c184a6110b");
+        assert_eq!(ctx.plain_tweak_add(&tweak), Err(Error::InvalidTweak));
+        assert_eq!(ctx.public_key(), before);
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let sorted = sort_keys(&pubkeys(&[0, 1, 2, 6]));
+        let hexes: Vec<String> = sorted.iter().map(|pk| pk.to_hex(true)).collect();
+        assert_eq!(hexes, vec![PUBKEYS[2], PUBKEYS[0], PUBKEYS[6], PUBKEYS[1]]);
+    }
+}
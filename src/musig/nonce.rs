@@ -0,0 +1,333 @@
+use rand::Rng;
+use schnorr::{tagged_hash, TaggedHasher};
+use secp256k1::group::{Affine, Jacobian, AFFINE_INFINITY};
+use secp256k1::{Error, PublicKey, Scalar, SecretKey, XOnlyPublicKey};
+use util::{mul_gen, scalar_from_b32, to_affine};
+
+/// A signer's secret nonce pair `(k_1, k_2)`, bound to its public key.
+///
+/// Signing twice with the same secret nonce reveals the secret key, so a
+/// `SecNonce` is neither `Clone` nor `Copy`, `Session::partial_sign` takes it
+/// by value, and it is cleared when dropped.
+pub struct SecNonce {
+    pub(super) k1: Scalar,
+    pub(super) k2: Scalar,
+    pub(super) pubkey: PublicKey,
+}
+
+/// The public nonce pair `(k_1.G, k_2.G)` a signer sends to the others.
+#[derive(Debug, Clone, Copy)]
+pub struct PubNonce {
+    pub(super) r1: Affine,
+    pub(super) r2: Affine,
+}
+
+/// The sum of all signers' public nonces. Either point may be infinity.
+#[derive(Debug, Clone, Copy)]
+pub struct AggNonce {
+    pub(super) r1: Affine,
+    pub(super) r2: Affine,
+}
+
+impl SecNonce {
+    /// Parse the 97-byte `k_1 || k_2 || pk` encoding. Fails if either scalar
+    /// is zero or not below the group order.
+    ///
+    /// Only use this to resume a session whose nonce was never used to sign.
+    pub fn parse(p: &[u8; 97]) -> Result<SecNonce, Error> {
+        let mut k1 = Scalar::default();
+        let mut k2 = Scalar::default();
+        if k1.set_b32(array_ref!(p, 0, 32)) || k1.is_zero() {
+            return Err(Error::InvalidNonce);
+        }
+        if k2.set_b32(array_ref!(p, 32, 32)) || k2.is_zero() {
+            return Err(Error::InvalidNonce);
+        }
+        let pubkey = PublicKey::parse_compressed(array_ref!(p, 64, 33))?;
+        Ok(SecNonce { k1, k2, pubkey })
+    }
+
+    pub fn serialize(&self) -> [u8; 97] {
+        let mut ret = [0u8; 97];
+        self.k1.fill_b32(array_mut_ref!(ret, 0, 32));
+        self.k2.fill_b32(array_mut_ref!(ret, 32, 32));
+        ret[64..].copy_from_slice(&self.pubkey.serialize_compressed());
+        ret
+    }
+}
+
+impl Drop for SecNonce {
+    fn drop(&mut self) {
+        self.k1.clear();
+        self.k2.clear();
+    }
+}
+
+impl PubNonce {
+    /// Parse the 66-byte encoding of two compressed points.
+    pub fn parse(p: &[u8; 66]) -> Result<PubNonce, Error> {
+        let r1 = PublicKey::parse_compressed(array_ref!(p, 0, 33)).or(Err(Error::InvalidNonce))?;
+        let r2 = PublicKey::parse_compressed(array_ref!(p, 33, 33)).or(Err(Error::InvalidNonce))?;
+        Ok(PubNonce { r1: r1.0, r2: r2.0 })
+    }
+
+    pub fn serialize(&self) -> [u8; 66] {
+        let mut ret = [0u8; 66];
+        ret[..33].copy_from_slice(&PublicKey(self.r1).serialize_compressed());
+        ret[33..].copy_from_slice(&PublicKey(self.r2).serialize_compressed());
+        ret
+    }
+}
+
+impl AggNonce {
+    /// Parse the 66-byte encoding of two compressed points, where 33 zero
+    /// bytes stand for infinity.
+    pub fn parse(p: &[u8; 66]) -> Result<AggNonce, Error> {
+        Ok(AggNonce {
+            r1: parse_point_ext(array_ref!(p, 0, 33))?,
+            r2: parse_point_ext(array_ref!(p, 33, 33))?,
+        })
+    }
+
+    pub fn serialize(&self) -> [u8; 66] {
+        let mut ret = [0u8; 66];
+        if !self.r1.is_infinity() {
+            ret[..33].copy_from_slice(&PublicKey(self.r1).serialize_compressed());
+        }
+        if !self.r2.is_infinity() {
+            ret[33..].copy_from_slice(&PublicKey(self.r2).serialize_compressed());
+        }
+        ret
+    }
+}
+
+fn parse_point_ext(p: &[u8; 33]) -> Result<Affine, Error> {
+    if p.iter().all(|b| *b == 0) {
+        return Ok(AFFINE_INFINITY);
+    }
+    Ok(PublicKey::parse_compressed(p).or(Err(Error::InvalidNonce))?.0)
+}
+
+/// Generate a fresh nonce pair for signing with the key `pubkey`.
+///
+/// The nonces are derived from 32 random bytes, which must never repeat.
+/// The optional secret key, x-only aggregate key, message and extra input
+/// are hashed in as well, so a faulty random number generator does not
+/// directly lead to nonce reuse across different sessions.
+pub fn nonce_gen<R: Rng>(
+    rng: &mut R,
+    pubkey: &PublicKey,
+    seckey: Option<&SecretKey>,
+    aggpk: Option<&XOnlyPublicKey>,
+    msg: Option<&[u8]>,
+    extra_in: Option<&[u8]>,
+) -> (SecNonce, PubNonce) {
+    loop {
+        let mut rand = [0u8; 32];
+        rng.fill_bytes(&mut rand);
+        if let Some(ret) = nonce_gen_internal(&rand, pubkey, seckey, aggpk, msg, extra_in) {
+            return ret;
+        }
+    }
+}
+
+/// Derive the nonce pair from the random bytes `rand`, or `None` in the
+/// negligible case that either nonce is zero.
+fn nonce_gen_internal(
+    rand: &[u8; 32],
+    pubkey: &PublicKey,
+    seckey: Option<&SecretKey>,
+    aggpk: Option<&XOnlyPublicKey>,
+    msg: Option<&[u8]>,
+    extra_in: Option<&[u8]>,
+) -> Option<(SecNonce, PubNonce)> {
+    let mut rand = *rand;
+    if let Some(seckey) = seckey {
+        let aux = tagged_hash(b"MuSig/aux", &[&rand]);
+        for (r, (d, a)) in rand.iter_mut().zip(seckey.serialize().iter().zip(aux.iter())) {
+            *r = d ^ a;
+        }
+    }
+
+    let pk = pubkey.serialize_compressed();
+    let aggpk = aggpk.map(|aggpk| aggpk.serialize());
+    let aggpk: &[u8] = match aggpk {
+        Some(ref aggpk) => aggpk,
+        None => &[],
+    };
+    let extra_in = extra_in.unwrap_or(&[]);
+
+    let mut hasher = TaggedHasher::new(b"MuSig/nonce");
+    hasher.input(&rand);
+    hasher.input(&[pk.len() as u8]);
+    hasher.input(&pk);
+    hasher.input(&[aggpk.len() as u8]);
+    hasher.input(aggpk);
+    match msg {
+        Some(msg) => {
+            hasher.input(&[1]);
+            hasher.input(&(msg.len() as u64).to_be_bytes());
+            hasher.input(msg);
+        }
+        None => hasher.input(&[0]),
+    }
+    hasher.input(&(extra_in.len() as u32).to_be_bytes());
+    hasher.input(extra_in);
+
+    let k1 = scalar_from_b32(&hasher.hash(&[&[0]]));
+    let k2 = scalar_from_b32(&hasher.hash(&[&[1]]));
+    if k1.is_zero() || k2.is_zero() {
+        return None;
+    }
+
+    let pubnonce = PubNonce {
+        r1: to_affine(&mul_gen(&k1)),
+        r2: to_affine(&mul_gen(&k2)),
+    };
+    let secnonce = SecNonce {
+        k1,
+        k2,
+        pubkey: *pubkey,
+    };
+    Some((secnonce, pubnonce))
+}
+
+/// Sum the public nonces of all signers.
+pub fn aggregate_nonces(pubnonces: &[PubNonce]) -> AggNonce {
+    let mut r1 = Jacobian::default();
+    let mut r2 = Jacobian::default();
+    r1.set_infinity();
+    r2.set_infinity();
+    for pubnonce in pubnonces {
+        r1 = r1.add_ge_var(&pubnonce.r1, None);
+        r2 = r2.add_ge_var(&pubnonce.r2, None);
+    }
+    AggNonce {
+        r1: to_affine(&r1),
+        r2: to_affine(&r2),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{aggregate_nonces, nonce_gen, nonce_gen_internal, AggNonce, PubNonce, SecNonce};
+    use hex;
+    use rand::thread_rng;
+    use util::decode32;
+    use {Error, PublicKey, SecretKey, XOnlyPublicKey};
+
+    fn pubnonce(h: &str) -> Result<PubNonce, Error> {
+        let data = hex::decode(h).unwrap();
+        PubNonce::parse(array_ref!(data, 0, 66))
+    }
+
+    #[test]
+    fn test_nonce_gen() {
+        // BIP-327 nonce generation test vectors.
+        let rand = [0x0f; 32];
+        let seckey = SecretKey::parse(&[0x02; 32]).unwrap();
+        let pubkey = PublicKey::from_hex("024d4b6cd1361032ca9bd2aeb9d900aa4d45d9ead80ac9423374c451a7254d0766").unwrap();
+        let aggpk = XOnlyPublicKey::parse(&[0x07; 32]).unwrap();
+        let (secnonce, pubnonce) = nonce_gen_internal(
+            &rand,
+            &pubkey,
+            Some(&seckey),
+            Some(&aggpk),
+            Some(&[0x01; 32][..]),
+            Some(&[0x08; 32][..]),
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(&secnonce.serialize()[..]),
+            "b114e502beaa4e301dd08a50264172c84e41650e6cb726b410c0694d59effb64\
+             95b5caf28d045b973d63e3c99a44b807bde375fd6cb39e46dc4a511708d0e9d2\
+             024d4b6cd1361032ca9bd2aeb9d900aa4d45d9ead80ac9423374c451a7254d0766"
+        );
+        assert_eq!(
+            hex::encode(&pubnonce.serialize()[..]),
+            "02f7be7089e8376eb355272368766b17e88e7db72047d05e56aa881ea52b3b35df\
+             02c29c8046fdd0ded4c7e55869137200fbdbfe2eb654267b6d7013602caed3115a"
+        );
+
+        let pubkey = PublicKey::from_hex("02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9").unwrap();
+        let (secnonce, pubnonce) = nonce_gen_internal(&rand, &pubkey, None, None, None, None).unwrap();
+        assert_eq!(
+            hex::encode(&secnonce.serialize()[..]),
+            "89bdd787d0284e5e4d5fc572e49e316bab7e21e3b1830de37dfe80156fa41a6d\
+             0b17ae8d024c53679699a6fd7944d9c4a366b514baf43088e0708b1023dd2897\
+             02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+        );
+        assert_eq!(
+            hex::encode(&pubnonce.serialize()[..]),
+            "02c96e7cb1e8aa5dac64d872947914198f607d90ecde5200de52978ad5ded63c00\
+             0299ec5117c2d29edee8a2092587c3909be694d5cff0667d6c02ea4059f7cd9786"
+        );
+
+        let roundtrip = SecNonce::parse(&secnonce.serialize()).unwrap();
+        assert_eq!(&roundtrip.serialize()[..], &secnonce.serialize()[..]);
+
+        let (a, _) = nonce_gen(&mut thread_rng(), &pubkey, None, None, None, None);
+        let (b, _) = nonce_gen(&mut thread_rng(), &pubkey, None, None, None, None);
+        assert_ne!(&a.serialize()[..], &b.serialize()[..]);
+    }
+
+    #[test]
+    fn test_nonce_agg() {
+        // BIP-327 nonce aggregation test vectors.
+        let pubnonces = [
+            "020151c80f435648df67a22b749cd798ce54e0321d034b92b709b567d60a42e666\
+             03ba47fbc1834437b3212e89a84d8425e7bf12e0245d98262268ebdcb385d50641",
+            "03ff406ffd8adb9cd29877e4985014f66a59f6cd01c0e88caa8e5f3166b1f676a6\
+             0248c264cdd57d3c24d79990b0f865674eb62a0f9018277a95011b41bfc193b833",
+            "020151c80f435648df67a22b749cd798ce54e0321d034b92b709b567d60a42e666\
+             0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "03ff406ffd8adb9cd29877e4985014f66a59f6cd01c0e88caa8e5f3166b1f676a6\
+             0379be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "04ff406ffd8adb9cd29877e4985014f66a59f6cd01c0e88caa8e5f3166b1f676a6\
+             0248c264cdd57d3c24d79990b0f865674eb62a0f9018277a95011b41bfc193b833",
+            "03ff406ffd8adb9cd29877e4985014f66a59f6cd01c0e88caa8e5f3166b1f676a6\
+             0248c264cdd57d3c24d79990b0f865674eb62a0f9018277a95011b41bfc193b831",
+            "03ff406ffd8adb9cd29877e4985014f66a59f6cd01c0e88caa8e5f3166b1f676a6\
+             02fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30",
+        ];
+
+        let valid = [
+            (
+                [0, 1],
+                "035fe1873b4f2967f52fea4a06ad5a8eccbe9d0fd73068012c894e2e87ccb5804b\
+                 024725377345bde0e9c33af3c43c0a29a9249f2f2956fa8cfeb55c8573d0262dc8",
+            ),
+            // The second points sum to infinity.
+            (
+                [2, 3],
+                "035fe1873b4f2967f52fea4a06ad5a8eccbe9d0fd73068012c894e2e87ccb5804b\
+                 000000000000000000000000000000000000000000000000000000000000000000",
+            ),
+        ];
+        for &(indices, expected) in valid.iter() {
+            let nonces: Vec<PubNonce> = indices.iter().map(|i| pubnonce(pubnonces[*i]).unwrap()).collect();
+            let aggnonce = aggregate_nonces(&nonces);
+            assert_eq!(hex::encode(&aggnonce.serialize()[..]), expected);
+            let roundtrip = AggNonce::parse(&aggnonce.serialize()).unwrap();
+            assert_eq!(&roundtrip.serialize()[..], &aggnonce.serialize()[..]);
+        }
+
+        // Invalid public nonces: a wrong prefix, a point not on the curve,
+        // and an x-coordinate not below the field size.
+        for i in &[4, 5, 6] {
+            assert_eq!(pubnonce(pubnonces[*i]).err(), Some(Error::InvalidNonce));
+        }
+    }
+
+    #[test]
+    fn test_invalid_secnonce() {
+        let mut data = [0u8; 97];
+        data[64..].copy_from_slice(
+            &hex::decode("03935f972da013f80ae011890fa89b67a27b7be6ccb24d3274d18b2d4067f261a9").unwrap(),
+        );
+        assert_eq!(SecNonce::parse(&data).err(), Some(Error::InvalidNonce));
+        data[..32].copy_from_slice(&decode32("508b81a611f100a6b2b6b29656590898af488bcf2e1f55cf22e5cfb84421fe61"));
+        data[32..64].copy_from_slice(&decode32("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"));
+        assert_eq!(SecNonce::parse(&data).err(), Some(Error::InvalidNonce));
+    }
+}
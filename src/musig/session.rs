@@ -0,0 +1,497 @@
+use super::nonce::{AggNonce, PubNonce, SecNonce};
+use super::KeyAggContext;
+use ecmult::ECMULT_CONTEXT;
+use schnorr::{tagged_hash, Signature};
+use secp256k1::group::{Affine, Jacobian, AFFINE_G};
+use secp256k1::{Error, PublicKey, Scalar, SecretKey};
+use util::{point_mul, scalar_from_b32, to_affine};
+
+/// A signer's share `s_i` of the final signature.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PartialSignature(pub Scalar);
+
+impl PartialSignature {
+    /// Parse a 32-byte partial signature. Fails if it is not below the group
+    /// order.
+    pub fn parse(p: &[u8; 32]) -> Result<PartialSignature, Error> {
+        let mut s = Scalar::default();
+        if s.set_b32(p) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(PartialSignature(s))
+    }
+
+    pub fn serialize(&self) -> [u8; 32] {
+        self.0.b32()
+    }
+}
+
+/// The values every signer derives for a message once the aggregate nonce is
+/// known: the nonce coefficient `b`, the final nonce `R` and the challenge
+/// `e`.
+#[derive(Debug, Clone)]
+pub struct Session {
+    key_agg_ctx: KeyAggContext,
+    b: Scalar,
+    r: Affine,
+    e: Scalar,
+}
+
+impl Session {
+    pub fn new(key_agg_ctx: &KeyAggContext, aggnonce: &AggNonce, msg: &[u8]) -> Session {
+        let qx = key_agg_ctx.x_only_public_key().serialize();
+        let b = scalar_from_b32(&tagged_hash(b"MuSig/noncecoef", &[&aggnonce.serialize(), &qx, msg]));
+
+        // R = R_1 + b.R_2, replaced by G if it is infinity so that signing
+        // can always go ahead.
+        let rj = point_mul(&aggnonce.r2, &b).add_ge_var(&aggnonce.r1, None);
+        let r = if rj.is_infinity() { AFFINE_G } else { to_affine(&rj) };
+
+        let e = scalar_from_b32(&tagged_hash(b"BIP0340/challenge", &[&r.x.b32(), &qx, msg]));
+        Session {
+            key_agg_ctx: key_agg_ctx.clone(),
+            b,
+            r,
+            e,
+        }
+    }
+
+    /// Compute the partial signature `s = k_1 + b.k_2 + e.a.d`, negating the
+    /// nonces and the key as BIP-340 requires. The secret nonce is consumed.
+    ///
+    /// Fails if the secret nonce was generated for a different key or the
+    /// key is not part of the aggregate key.
+    pub fn partial_sign(&self, secnonce: SecNonce, seckey: &SecretKey) -> Result<PartialSignature, Error> {
+        let pubkey = PublicKey::from_secret_key(seckey);
+        if pubkey.serialize_compressed() != secnonce.pubkey.serialize_compressed() {
+            return Err(Error::InvalidNonce);
+        }
+        let a = self.key_agg_ctx.coefficient(&pubkey).ok_or(Error::InvalidPublicKey)?;
+
+        let (mut k1, mut k2) = (secnonce.k1, secnonce.k2);
+        if self.r.y.is_odd() {
+            k1 = -k1;
+            k2 = -k2;
+        }
+        let mut d = self.key_agg_ctx.parity_acc() * seckey.0;
+        let s = k1 + self.b * k2 + self.e * a * d;
+        k1.clear();
+        k2.clear();
+        d.clear();
+        Ok(PartialSignature(s))
+    }
+
+    /// Verify a partial signature against the signer's public nonce and
+    /// public key, checking `s.G = R_1 + b.R_2 + e.a.g.P` up to the same
+    /// negations as in signing.
+    pub fn partial_sig_verify(&self, psig: &PartialSignature, pubnonce: &PubNonce, pubkey: &PublicKey) -> bool {
+        let a = match self.key_agg_ctx.coefficient(pubkey) {
+            Some(a) => a,
+            None => return false,
+        };
+
+        let mut re = point_mul(&pubnonce.r2, &self.b).add_ge_var(&pubnonce.r1, None);
+        if self.r.y.is_odd() {
+            re = re.neg();
+        }
+
+        // s.G - e.a.g.P
+        let mut pj = Jacobian::default();
+        pj.set_ge(&pubkey.0);
+        let mut sj = Jacobian::default();
+        let ead = self.e * a * self.key_agg_ctx.parity_acc();
+        ECMULT_CONTEXT.ecmult(&mut sj, &pj, &ead.neg(), &psig.0);
+
+        let (lhs, rhs) = (to_affine(&sj), to_affine(&re));
+        if lhs.is_infinity() || rhs.is_infinity() {
+            return lhs.is_infinity() && rhs.is_infinity();
+        }
+        lhs == rhs
+    }
+
+    /// Combine the partial signatures of all signers into a BIP-340
+    /// signature for the tweaked aggregate key.
+    pub fn aggregate(&self, psigs: &[PartialSignature]) -> Signature {
+        let mut tacc = self.key_agg_ctx.tacc;
+        if self.key_agg_ctx.q.x_only().1.is_odd() {
+            tacc = -tacc;
+        }
+        let mut s = self.e * tacc;
+        for psig in psigs {
+            s += psig.0;
+        }
+        Signature { r: self.r.x, s }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PartialSignature, Session};
+    use bitcoin::taproot;
+    use hex;
+    use musig::{aggregate_nonces, nonce_gen, sort_keys, AggNonce, KeyAggContext, PubNonce, SecNonce};
+    use rand::thread_rng;
+    use schnorr;
+    use {Error, PublicKey, SecretKey};
+
+    fn decode<T: AsMut<[u8]> + Default>(h: &str) -> T {
+        let mut ret = T::default();
+        ret.as_mut().copy_from_slice(&hex::decode(h).unwrap());
+        ret
+    }
+
+    fn secnonce(h: &str) -> Result<SecNonce, Error> {
+        let data = hex::decode(h).unwrap();
+        SecNonce::parse(array_ref!(data, 0, 97))
+    }
+
+    fn pubnonce(h: &str) -> Result<PubNonce, Error> {
+        let data = hex::decode(h).unwrap();
+        PubNonce::parse(array_ref!(data, 0, 66))
+    }
+
+    fn aggnonce(h: &str) -> Result<AggNonce, Error> {
+        let data = hex::decode(h).unwrap();
+        AggNonce::parse(array_ref!(data, 0, 66))
+    }
+
+    fn psig(h: &str) -> Result<PartialSignature, Error> {
+        PartialSignature::parse(&decode(h))
+    }
+
+    fn select<T: Copy>(items: &[T], indices: &[usize]) -> Vec<T> {
+        indices.iter().map(|i| items[*i]).collect()
+    }
+
+    // BIP-327 sign and verify test vectors.
+    const SIGN_SECKEY: &str = "7fb9e0e687ada1eebf7ecfe2f21e73ebdb51a7d450948dfe8d76d7f2d1007671";
+    const SIGN_SECNONCES: [&str; 2] = [
+        "508b81a611f100a6b2b6b29656590898af488bcf2e1f55cf22e5cfb84421fe61\
+         fa27fd49b1d50085b481285e1ca205d55c82cc1b31ff5cd54a489829355901f7\
+         03935f972da013f80ae011890fa89b67a27b7be6ccb24d3274d18b2d4067f261a9",
+        "0000000000000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000000\
+         03935f972da013f80ae011890fa89b67a27b7be6ccb24d3274d18b2d4067f261a9",
+    ];
+    const SIGN_PUBKEYS: [&str; 4] = [
+        "03935f972da013f80ae011890fa89b67a27b7be6ccb24d3274d18b2d4067f261a9",
+        "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+        "02dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba661",
+        "020000000000000000000000000000000000000000000000000000000000000007",
+    ];
+    const SIGN_PUBNONCES: [&str; 5] = [
+        "0337c87821afd50a8644d820a8f3e02e499c931865c2360fb43d0a0d20dafe07ea\
+         0287bf891d2a6deaebadc909352aa9405d1428c15f4b75f04dae642a95c2548480",
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+         0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        "032de2662628c90b03f5e720284eb52ff7d71f4284f627b68a853d78c78e1ffe93\
+         03e4c5524e83ffe1493b9077cf1ca6beb2090c93d930321071ad40b2f44e599046",
+        "0237c87821afd50a8644d820a8f3e02e499c931865c2360fb43d0a0d20dafe07ea\
+         0387bf891d2a6deaebadc909352aa9405d1428c15f4b75f04dae642a95c2548480",
+        "020000000000000000000000000000000000000000000000000000000000000009\
+         0287bf891d2a6deaebadc909352aa9405d1428c15f4b75f04dae642a95c2548480",
+    ];
+    const SIGN_AGGNONCES: [&str; 5] = [
+        "028465fcf0bbdbcf443aabcce533d42b4b5a10966ac09a49655e8c42daab8fcd61\
+         037496a3cc86926d452cafcfd55d25972ca1675d549310de296bff42f72eeea8c9",
+        "000000000000000000000000000000000000000000000000000000000000000000\
+         000000000000000000000000000000000000000000000000000000000000000000",
+        "048465fcf0bbdbcf443aabcce533d42b4b5a10966ac09a49655e8c42daab8fcd61\
+         037496a3cc86926d452cafcfd55d25972ca1675d549310de296bff42f72eeea8c9",
+        "028465fcf0bbdbcf443aabcce533d42b4b5a10966ac09a49655e8c42daab8fcd61\
+         020000000000000000000000000000000000000000000000000000000000000009",
+        "028465fcf0bbdbcf443aabcce533d42b4b5a10966ac09a49655e8c42daab8fcd61\
+         02fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30",
+    ];
+    const SIGN_MSG: &str = "f95466d086770e689964664219266fe5ed215c92ae20bab5c9d79addddf3c0cf";
+
+    fn sign_pubkeys(indices: &[usize]) -> Vec<PublicKey> {
+        indices.iter().map(|i| PublicKey::from_hex(SIGN_PUBKEYS[*i]).unwrap()).collect()
+    }
+
+    fn sign_pubnonces(indices: &[usize]) -> Vec<PubNonce> {
+        indices.iter().map(|i| pubnonce(SIGN_PUBNONCES[*i]).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let seckey = SecretKey::from_hex(SIGN_SECKEY).unwrap();
+        let msg = hex::decode(SIGN_MSG).unwrap();
+
+        // (key indices, nonce indices, aggregate nonce, signer position, expected)
+        type Case = (&'static [usize], &'static [usize], usize, usize, &'static str);
+        let valid: [Case; 4] = [
+            (&[0, 1, 2], &[0, 1, 2], 0, 0, "012abbcb52b3016ac03ad82395a1a415c48b93def78718e62a7a90052fe224fb"),
+            (&[1, 0, 2], &[1, 0, 2], 0, 1, "9ff2f7aaa856150cc8819254218d3adeeb0535269051897724f9db3789513a52"),
+            (&[1, 2, 0], &[1, 2, 0], 0, 2, "fa23c359f6fac4e7796bb93bc9f0532a95468c539ba20ff86d7c76ed92227900"),
+            // The aggregate nonce is infinity in both halves.
+            (&[0, 1], &[0, 3], 1, 0, "ae386064b26105404798f75de2eb9af5eda5387b064b83d049cb7c5e08879531"),
+        ];
+        for &(key_indices, nonce_indices, aggnonce_index, signer, expected) in valid.iter() {
+            let pubkeys = sign_pubkeys(key_indices);
+            let pubnonces = sign_pubnonces(nonce_indices);
+            let aggnonce = aggregate_nonces(&pubnonces);
+            assert_eq!(hex::encode(&aggnonce.serialize()[..]), SIGN_AGGNONCES[aggnonce_index]);
+
+            let ctx = KeyAggContext::new(&pubkeys).unwrap();
+            let session = Session::new(&ctx, &aggnonce, &msg);
+            let psig = session.partial_sign(secnonce(SIGN_SECNONCES[0]).unwrap(), &seckey).unwrap();
+            assert_eq!(hex::encode(psig.serialize()), expected);
+            assert!(session.partial_sig_verify(&psig, &pubnonces[signer], &pubkeys[signer]));
+        }
+    }
+
+    #[test]
+    fn test_sign_invalid() {
+        let seckey = SecretKey::from_hex(SIGN_SECKEY).unwrap();
+        let msg = hex::decode(SIGN_MSG).unwrap();
+        let aggnonce0 = aggnonce(SIGN_AGGNONCES[0]).unwrap();
+
+        // The signer's key is not among the aggregated keys.
+        let ctx = KeyAggContext::new(&sign_pubkeys(&[1, 2])).unwrap();
+        let session = Session::new(&ctx, &aggnonce0, &msg);
+        assert_eq!(
+            session.partial_sign(secnonce(SIGN_SECNONCES[0]).unwrap(), &seckey),
+            Err(Error::InvalidPublicKey)
+        );
+
+        // The secret nonce belongs to a different key.
+        let ctx = KeyAggContext::new(&sign_pubkeys(&[0, 1, 2])).unwrap();
+        let session = Session::new(&ctx, &aggnonce0, &msg);
+        let other = SecretKey::parse(&[0x01; 32]).unwrap();
+        assert_eq!(
+            session.partial_sign(secnonce(SIGN_SECNONCES[0]).unwrap(), &other),
+            Err(Error::InvalidNonce)
+        );
+
+        // An invalid public key, invalid aggregate nonces and a secret nonce
+        // of zeros are all rejected when parsing.
+        assert_eq!(PublicKey::from_hex(SIGN_PUBKEYS[3]), Err(Error::InvalidPublicKey));
+        for i in &[2, 3, 4] {
+            assert_eq!(aggnonce(SIGN_AGGNONCES[*i]).err(), Some(Error::InvalidNonce));
+        }
+        assert_eq!(secnonce(SIGN_SECNONCES[1]).err(), Some(Error::InvalidNonce));
+        assert_eq!(pubnonce(SIGN_PUBNONCES[4]).err(), Some(Error::InvalidNonce));
+    }
+
+    #[test]
+    fn test_verify_fail() {
+        let msg = hex::decode(SIGN_MSG).unwrap();
+        let pubkeys = sign_pubkeys(&[0, 1, 2]);
+        let pubnonces = sign_pubnonces(&[0, 1, 2]);
+        let ctx = KeyAggContext::new(&pubkeys).unwrap();
+        let session = Session::new(&ctx, &aggregate_nonces(&pubnonces), &msg);
+
+        // Wrong signature, and a valid signature for the wrong signer.
+        let wrong = psig("fed54434ad4cfe953fc527dc6a5e5be8f6234907b7c187559557ce87a0541c46").unwrap();
+        assert!(!session.partial_sig_verify(&wrong, &pubnonces[0], &pubkeys[0]));
+        let valid = psig("012abbcb52b3016ac03ad82395a1a415c48b93def78718e62a7a90052fe224fb").unwrap();
+        assert!(session.partial_sig_verify(&valid, &pubnonces[0], &pubkeys[0]));
+        assert!(!session.partial_sig_verify(&valid, &pubnonces[1], &pubkeys[1]));
+        assert!(!session.partial_sig_verify(&valid, &pubnonces[0], &sign_pubkeys(&[2])[0]));
+
+        // A signature not below the group order.
+        assert_eq!(
+            psig("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_tweaked_sign() {
+        // BIP-327 tweak test vectors.
+        let seckey = SecretKey::from_hex(SIGN_SECKEY).unwrap();
+        let msg = hex::decode(SIGN_MSG).unwrap();
+        let pubkeys: Vec<PublicKey> = [
+            "03935f972da013f80ae011890fa89b67a27b7be6ccb24d3274d18b2d4067f261a9",
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "02dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+        ]
+        .iter()
+        .map(|h| PublicKey::from_hex(h).unwrap())
+        .collect();
+        let pubnonces = sign_pubnonces(&[0, 1, 2]);
+        let tweaks: Vec<[u8; 32]> = [
+            "e8f791ff9225a2af0102afff4a9a723d9612a682a25ebe79802b263cdfcd83bb",
+            "ae2ea797cc0fe72ac5b97b97f3c6957d7e4199a167a58eb08bcaffda70ac0455",
+            "f52ecbc565b3d8bea2dfd5b75a4f457e54369809322e4120831626f290fa87e0",
+            "1969ad73cc177fa0b4fced6df1f7bf9907e665fde9ba196a74fed0a3cf5aef9d",
+        ]
+        .iter()
+        .map(|h| decode(h))
+        .collect();
+
+        let valid: [(&[bool], &str); 5] = [
+            (&[true], "e28a5c66e61e178c2ba19db77b6cf9f7e2f0f56c17918cd13135e60cc848fe91"),
+            (&[false], "38b0767798252f21bf5702c48028b095428320f73a4b14db1e25de58543d2d2d"),
+            (&[false, true], "408a0a21c4a0f5dacaf9646ad6eb6fecd7f7a11f03ed1f48dfff2185bc2c2408"),
+            (
+                &[false, false, true, true],
+                "45abd206e61e3df2ec9e264a6fec8292141a633c28586388235541f9ade75435",
+            ),
+            (
+                &[true, false, true, false],
+                "b255fdcac27b40c7ce7848e2d3b7bf5ea0ed756da81565ac804ccca3e1d5d239",
+            ),
+        ];
+        let order = [1, 2, 0];
+        for &(xonly, expected) in valid.iter() {
+            let mut ctx = KeyAggContext::new(&select(&pubkeys, &order)).unwrap();
+            for (tweak, xonly) in tweaks.iter().zip(xonly.iter()) {
+                if *xonly {
+                    ctx.xonly_tweak_add(tweak).unwrap();
+                } else {
+                    ctx.plain_tweak_add(tweak).unwrap();
+                }
+            }
+            let aggnonce = aggregate_nonces(&select(&pubnonces, &order));
+            assert_eq!(
+                hex::encode(&aggnonce.serialize()[..]),
+                "028465fcf0bbdbcf443aabcce533d42b4b5a10966ac09a49655e8c42daab8fcd61\
+                 037496a3cc86926d452cafcfd55d25972ca1675d549310de296bff42f72eeea8c9"
+            );
+            let session = Session::new(&ctx, &aggnonce, &msg);
+            let psig = session.partial_sign(secnonce(SIGN_SECNONCES[0]).unwrap(), &seckey).unwrap();
+            assert_eq!(hex::encode(psig.serialize()), expected);
+            assert!(session.partial_sig_verify(&psig, &pubnonces[0], &pubkeys[0]));
+        }
+
+        // A tweak not below the group order.
+        let mut ctx = KeyAggContext::new(&select(&pubkeys, &order)).unwrap();
+        let order_bytes = decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141");
+        assert_eq!(ctx.plain_tweak_add(&order_bytes), Err(Error::InvalidTweak));
+    }
+
+    #[test]
+    fn test_sig_agg() {
+        // BIP-327 signature aggregation test vectors.
+        let pubkeys: Vec<PublicKey> = [
+            "03935f972da013f80ae011890fa89b67a27b7be6ccb24d3274d18b2d4067f261a9",
+            "02d2dc6f5df7c56acf38c7fa0ae7a759ae30e19b37359dfde015872324c7ef6e05",
+            "03c7fb101d97ff930acd0c6760852ef64e69083de0b06ac6335724754bb4b0522c",
+            "02352433b21e7e05d3b452b81cae566e06d2e003ece16d1074aaba4289e0e3d581",
+        ]
+        .iter()
+        .map(|h| PublicKey::from_hex(h).unwrap())
+        .collect();
+        let tweaks: Vec<[u8; 32]> = [
+            "b511da492182a91b0ffb9a98020d55f260ae86d7ecbd0399c7383d59a5f2af7c",
+            "a815fe049ee3c5aab66310477fbc8bcccac2f3395f59f921c364acd78a2f48dc",
+            "75448a87274b056468b977be06eb1e9f657577b7320b0a3376ea51fd420d18a8",
+        ]
+        .iter()
+        .map(|h| decode(h))
+        .collect();
+        let psigs: Vec<PartialSignature> = [
+            "b15d2cd3c3d22b04dae438ce653f6b4ecf042f42cfded7c41b64aaf9b4af53fb",
+            "6193d6ac61b354e9105bbdc8937a3454a6d705b6d57322a5a472a02ce99fcb64",
+            "9a87d3b79ec67228cb97878b76049b15dbd05b8158d17b5b9114d3c226887505",
+            "66f82ea90923689b855d36c6b7e032fb9970301481b99e01cdb4d6ac7c347a15",
+            "4f5aee41510848a6447dcd1bbc78457ef69024944c87f40250d3ef2c25d33efe",
+            "ddef427bbb847cc027beff4edb01038148917832253ebc355fc33f4a8e2fcce4",
+            "97b890a26c981da8102d3bc294159d171d72810fdf7c6a691def02f0f7af3fdc",
+            "53fa9e08ba5243cbcb0d797c5ee83bc6728e539eb76c2d0bf0f971ee4e909971",
+        ]
+        .iter()
+        .map(|h| psig(h).unwrap())
+        .collect();
+        let msg = hex::decode("599c67ea410d005b9da90817cf03ed3b1c868e4da4edf00a5880b0082c237869").unwrap();
+
+        // (key indices, tweaks and whether they are x-only, aggregate nonce,
+        // partial signatures, expected)
+        type Case = (&'static [usize], &'static [(usize, bool)], &'static str, &'static [usize], &'static str);
+        let valid: [Case; 4] = [
+            (
+                &[0, 1],
+                &[],
+                "0341432722c5cd0268d829c702cf0d1cbce57033eed201fd335191385227c3210c\
+                 03d377f2d258b64aadc0e16f26462323d701d286046a2ea93365656afd9875982b",
+                &[0, 1],
+                "041da22223ce65c92c9a0d6c2cac828aaf1eee56304fec371ddf91ebb2b9ef09\
+                 12f1038025857fedeb3ff696f8b99fa4bb2c5812f6095a2e0004ec99ce18de1e",
+            ),
+            (
+                &[0, 2],
+                &[],
+                "0224afd36c902084058b51b5d36676bba4dc97c775873768e58822f87fe437d792\
+                 028cb15929099eee2f5dae404cd39357591ba32e9af4e162b8d3e7cb5efe31cb20",
+                &[2, 3],
+                "1069b67ec3d2f3c7c08291accb17a9c9b8f2819a52eb5df8726e17e7d6b52e9f\
+                 01800260a7e9dac450f4be522de4ce12ba91aeaf2b4279219ef74be1d286add9",
+            ),
+            (
+                &[0, 2],
+                &[(0, false)],
+                "0208c5c438c710f4f96a61e9ff3c37758814b8c3ae12bfea0ed2c87ff6954ff186\
+                 020b1816ea104b4fca2d304d733e0e19cead51303ff6420bfd222335caa402916d",
+                &[4, 5],
+                "5c558e1dcade86da0b2f02626a512e30a22cf5255caea7ee32c38e9a71a0e914\
+                 8ba6c0e6ec7683b64220f0298696f1b878cd47b107b81f7188812d593971e0cc",
+            ),
+            (
+                &[0, 3],
+                &[(0, true), (1, false), (2, true)],
+                "02b5ad07afcd99b6d92cb433fbd2a28fdeb98eae2eb09b6014ef0f8197cd584033\
+                 02e8616910f9293cf692c49f351db86b25e352901f0e237bafda11f1c1cef29ffd",
+                &[6, 7],
+                "839b08820b681dba8daf4cc7b104e8f2638f9388f8d7a555dc17b6e6971d7426\
+                 ce07bf6ab01f1db50e4e33719295f4094572b79868e440fb3defd3fac1db589e",
+            ),
+        ];
+        for &(key_indices, tweak_indices, aggnonce_hex, psig_indices, expected) in valid.iter() {
+            let mut ctx = KeyAggContext::new(&select(&pubkeys, key_indices)).unwrap();
+            for &(i, xonly) in tweak_indices {
+                if xonly {
+                    ctx.xonly_tweak_add(&tweaks[i]).unwrap();
+                } else {
+                    ctx.plain_tweak_add(&tweaks[i]).unwrap();
+                }
+            }
+            let session = Session::new(&ctx, &aggnonce(aggnonce_hex).unwrap(), &msg);
+            let signature = session.aggregate(&select(&psigs, psig_indices));
+            assert_eq!(hex::encode(&signature.serialize()[..]), expected);
+            assert!(schnorr::verify(&signature, &msg, &ctx.x_only_public_key()));
+        }
+
+        // A partial signature not below the group order.
+        assert_eq!(
+            psig("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_taproot_signing() {
+        // 2-of-2 and 3-of-3 key path spends of a Taproot output whose
+        // internal key is the aggregate key.
+        let msg = b"taproot sighash";
+        for n in 2..4 {
+            let seckeys: Vec<SecretKey> = (0..n).map(|_| SecretKey::random(&mut thread_rng())).collect();
+            let pubkeys: Vec<PublicKey> = seckeys.iter().map(PublicKey::from_secret_key).collect();
+            let mut ctx = KeyAggContext::new(&sort_keys(&pubkeys)).unwrap();
+            let internal_key = ctx.x_only_public_key();
+            ctx.xonly_tweak_add(&taproot::tap_tweak_hash(&internal_key, None)).unwrap();
+            let (output_key, _) = taproot::tweak_internal_key(&internal_key, None).unwrap();
+            assert_eq!(ctx.x_only_public_key(), output_key);
+
+            let mut secnonces = Vec::new();
+            let mut pubnonces = Vec::new();
+            for (seckey, pubkey) in seckeys.iter().zip(pubkeys.iter()) {
+                let (secnonce, pubnonce) =
+                    nonce_gen(&mut thread_rng(), pubkey, Some(seckey), Some(&output_key), Some(msg), None);
+                secnonces.push(secnonce);
+                pubnonces.push(pubnonce);
+            }
+            let session = Session::new(&ctx, &aggregate_nonces(&pubnonces), msg);
+
+            let mut psigs = Vec::new();
+            for (i, secnonce) in secnonces.into_iter().enumerate() {
+                let psig = session.partial_sign(secnonce, &seckeys[i]).unwrap();
+                assert!(session.partial_sig_verify(&psig, &pubnonces[i], &pubkeys[i]));
+                psigs.push(psig);
+            }
+            let signature = session.aggregate(&psigs);
+            assert!(schnorr::verify(&signature, msg, &output_key));
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Key containers used by OpenSSL and X.509: SEC1 `ECPrivateKey`, PKCS#8
+//! `PrivateKeyInfo` and `SubjectPublicKeyInfo`, in DER and PEM form.
+
+use base64;
+use secp256k1::der::{
+    self, Reader, TAG_BIT_STRING, TAG_CONTEXT_0, TAG_CONTEXT_1, TAG_INTEGER, TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE,
+};
+use secp256k1::{Error, PublicKey, SecretKey};
+
+/// The content of the `id-ecPublicKey` object identifier, 1.2.840.10045.2.1.
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+/// The content of the `secp256k1` object identifier, 1.3.132.0.10.
+const OID_SECP256K1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+const LABEL_SEC1: &str = "EC PRIVATE KEY";
+const LABEL_PKCS8: &str = "PRIVATE KEY";
+const LABEL_SPKI: &str = "PUBLIC KEY";
+
+/// Armor DER data as PEM with the given label, wrapping the base64 body at
+/// 64 characters.
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let body = base64::encode(der);
+    let mut ret = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(64) {
+        ret.push_str(::std::str::from_utf8(line).unwrap());
+        ret.push('\n');
+    }
+    ret.push_str(&format!("-----END {}-----\n", label));
+    ret
+}
+
+/// Extract the DER data of the first PEM block with the given label. Text
+/// before the block, such as the output of `openssl ec -text`, is skipped.
+pub fn decode(label: &str, pem: &str) -> Result<Vec<u8>, Error> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+
+    let mut lines = pem.lines().map(|line| line.trim()).skip_while(|line| *line != begin);
+    if lines.next().is_none() {
+        return Err(Error::InvalidPem);
+    }
+    let mut body = String::new();
+    for line in lines {
+        if line == end {
+            return base64::decode(&body).or(Err(Error::InvalidPem));
+        }
+        body.push_str(line);
+    }
+    Err(Error::InvalidPem)
+}
+
+/// The `AlgorithmIdentifier` of an EC key on secp256k1.
+fn algorithm_identifier() -> Vec<u8> {
+    let mut content = der::encode(TAG_OID, OID_EC_PUBLIC_KEY);
+    content.extend(der::encode(TAG_OID, OID_SECP256K1));
+    der::encode(TAG_SEQUENCE, &content)
+}
+
+fn parse_algorithm_identifier(reader: &mut Reader) -> Result<(), Error> {
+    let mut algorithm = Reader::new(reader.read(TAG_SEQUENCE)?);
+    algorithm.expect(TAG_OID, OID_EC_PUBLIC_KEY)?;
+    algorithm.expect(TAG_OID, OID_SECP256K1)?;
+    algorithm.finish()
+}
+
+fn parse_public_key(data: &[u8]) -> Result<PublicKey, Error> {
+    match data.len() {
+        33 => PublicKey::parse_compressed(array_ref!(data, 0, 33)),
+        65 => PublicKey::parse(array_ref!(data, 0, 65)),
+        _ => Err(Error::InvalidPublicKey),
+    }
+}
+
+/// Read a BIT STRING holding an encoded point. Points are always a whole
+/// number of bytes, so the unused bits count must be zero.
+fn parse_bit_string_key(reader: &mut Reader) -> Result<PublicKey, Error> {
+    let bits = reader.read(TAG_BIT_STRING)?;
+    if bits.first() != Some(&0) {
+        return Err(Error::InvalidDer);
+    }
+    parse_public_key(&bits[1..])
+}
+
+fn bit_string_key(pubkey: &PublicKey, compressed: bool) -> Vec<u8> {
+    let mut bits = vec![0u8];
+    if compressed {
+        bits.extend_from_slice(&pubkey.serialize_compressed());
+    } else {
+        bits.extend_from_slice(&pubkey.serialize()[..]);
+    }
+    der::encode(TAG_BIT_STRING, &bits)
+}
+
+impl SecretKey {
+    /// Parse a DER encoded SEC1 `ECPrivateKey`. The optional curve
+    /// parameters must name secp256k1 and the optional public key must
+    /// belong to the secret key.
+    pub fn from_sec1_der(data: &[u8]) -> Result<SecretKey, Error> {
+        let mut outer = Reader::new(data);
+        let mut reader = Reader::new(outer.read(TAG_SEQUENCE)?);
+        outer.finish()?;
+
+        reader.expect(TAG_INTEGER, &[1])?;
+        let bytes = reader.read(TAG_OCTET_STRING)?;
+        if bytes.len() > 32 {
+            return Err(Error::InvalidSecretKey);
+        }
+        let mut raw = [0u8; 32];
+        raw[(32 - bytes.len())..].copy_from_slice(bytes);
+        let seckey = SecretKey::parse(&raw)?;
+
+        if reader.peek() == Some(TAG_CONTEXT_0) {
+            let mut parameters = Reader::new(reader.read(TAG_CONTEXT_0)?);
+            parameters.expect(TAG_OID, OID_SECP256K1)?;
+            parameters.finish()?;
+        }
+        if reader.peek() == Some(TAG_CONTEXT_1) {
+            let mut public = Reader::new(reader.read(TAG_CONTEXT_1)?);
+            let pubkey = parse_bit_string_key(&mut public)?;
+            public.finish()?;
+            if pubkey != PublicKey::from_secret_key(&seckey) {
+                return Err(Error::InvalidPublicKey);
+            }
+        }
+        reader.finish()?;
+        Ok(seckey)
+    }
+
+    fn sec1_der(&self, parameters: bool) -> Vec<u8> {
+        let mut content = der::encode(TAG_INTEGER, &[1]);
+        content.extend(der::encode(TAG_OCTET_STRING, &self.serialize()));
+        if parameters {
+            content.extend(der::encode(TAG_CONTEXT_0, &der::encode(TAG_OID, OID_SECP256K1)));
+        }
+        let pubkey = PublicKey::from_secret_key(self);
+        content.extend(der::encode(TAG_CONTEXT_1, &bit_string_key(&pubkey, false)));
+        der::encode(TAG_SEQUENCE, &content)
+    }
+
+    /// Serialize as a DER encoded SEC1 `ECPrivateKey` with the curve
+    /// parameters and the uncompressed public key, as OpenSSL does.
+    pub fn to_sec1_der(&self) -> Vec<u8> {
+        self.sec1_der(true)
+    }
+
+    /// Parse a PEM encoded SEC1 key, labelled `EC PRIVATE KEY`.
+    pub fn from_sec1_pem(pem: &str) -> Result<SecretKey, Error> {
+        SecretKey::from_sec1_der(&decode(LABEL_SEC1, pem)?)
+    }
+
+    pub fn to_sec1_pem(&self) -> String {
+        encode(LABEL_SEC1, &self.to_sec1_der())
+    }
+
+    /// Parse a DER encoded PKCS#8 `PrivateKeyInfo` holding a secp256k1 EC
+    /// key. Trailing attributes are ignored.
+    pub fn from_pkcs8_der(data: &[u8]) -> Result<SecretKey, Error> {
+        let mut outer = Reader::new(data);
+        let mut reader = Reader::new(outer.read(TAG_SEQUENCE)?);
+        outer.finish()?;
+
+        let version = reader.read(TAG_INTEGER)?;
+        if version != [0] && version != [1] {
+            return Err(Error::InvalidDer);
+        }
+        parse_algorithm_identifier(&mut reader)?;
+        SecretKey::from_sec1_der(reader.read(TAG_OCTET_STRING)?)
+    }
+
+    /// Serialize as a DER encoded PKCS#8 `PrivateKeyInfo`. The embedded
+    /// SEC1 key omits the curve parameters, which the algorithm identifier
+    /// already carries.
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        let mut content = der::encode(TAG_INTEGER, &[0]);
+        content.extend(algorithm_identifier());
+        content.extend(der::encode(TAG_OCTET_STRING, &self.sec1_der(false)));
+        der::encode(TAG_SEQUENCE, &content)
+    }
+
+    /// Parse a PEM encoded PKCS#8 key, labelled `PRIVATE KEY`. Encrypted
+    /// keys are not supported.
+    pub fn from_pkcs8_pem(pem: &str) -> Result<SecretKey, Error> {
+        SecretKey::from_pkcs8_der(&decode(LABEL_PKCS8, pem)?)
+    }
+
+    pub fn to_pkcs8_pem(&self) -> String {
+        encode(LABEL_PKCS8, &self.to_pkcs8_der())
+    }
+}
+
+impl PublicKey {
+    /// Parse a DER encoded `SubjectPublicKeyInfo` with a compressed or
+    /// uncompressed secp256k1 point.
+    pub fn from_spki_der(data: &[u8]) -> Result<PublicKey, Error> {
+        let mut outer = Reader::new(data);
+        let mut reader = Reader::new(outer.read(TAG_SEQUENCE)?);
+        outer.finish()?;
+
+        parse_algorithm_identifier(&mut reader)?;
+        let pubkey = parse_bit_string_key(&mut reader)?;
+        reader.finish()?;
+        Ok(pubkey)
+    }
+
+    /// Serialize as a DER encoded `SubjectPublicKeyInfo`.
+    pub fn to_spki_der(&self, compressed: bool) -> Vec<u8> {
+        let mut content = algorithm_identifier();
+        content.extend(bit_string_key(self, compressed));
+        der::encode(TAG_SEQUENCE, &content)
+    }
+
+    /// Parse a PEM encoded `SubjectPublicKeyInfo`, labelled `PUBLIC KEY`.
+    pub fn from_spki_pem(pem: &str) -> Result<PublicKey, Error> {
+        PublicKey::from_spki_der(&decode(LABEL_SPKI, pem)?)
+    }
+
+    pub fn to_spki_pem(&self, compressed: bool) -> String {
+        encode(LABEL_SPKI, &self.to_spki_der(compressed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex;
+    use super::{decode, encode};
+    use {Error, PublicKey, SecretKey};
+
+    // Generated with `openssl ecparam -name secp256k1 -genkey`, then
+    // converted with `openssl pkcs8 -topk8 -nocrypt` and `openssl ec`.
+    const SEC1_PEM: &str = include_str!("../../tests/fixtures/sec1.pem");
+    const SEC1_DER: &[u8] = include_bytes!("../../tests/fixtures/sec1.der");
+    const SEC1_NO_PUBLIC_PEM: &str = include_str!("../../tests/fixtures/sec1_no_public.pem");
+    const SEC1_TEXT: &str = include_str!("../../tests/fixtures/sec1.txt");
+    const PKCS8_PEM: &str = include_str!("../../tests/fixtures/pkcs8.pem");
+    const SPKI_PEM: &str = include_str!("../../tests/fixtures/spki.pem");
+    const SPKI_DER: &[u8] = include_bytes!("../../tests/fixtures/spki.der");
+    const SPKI_COMPRESSED_PEM: &str = include_str!("../../tests/fixtures/spki_compressed.pem");
+
+    /// Read a colon separated hex field from `openssl ec -text` output.
+    fn text_field(name: &str) -> Vec<u8> {
+        let hex: String = SEC1_TEXT
+            .lines()
+            .skip_while(|line| *line != format!("{}:", name))
+            .skip(1)
+            .take_while(|line| line.starts_with(' '))
+            .flat_map(|line| line.trim().split(':'))
+            .collect();
+        hex::decode(hex).unwrap()
+    }
+
+    #[test]
+    fn test_openssl_fixtures() {
+        let raw = text_field("priv");
+        let seckey = SecretKey::parse(array_ref!(raw, 0, 32)).unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        assert_eq!(&pubkey.serialize()[..], &text_field("pub")[..]);
+
+        assert_eq!(SecretKey::from_sec1_pem(SEC1_PEM).unwrap(), seckey);
+        assert_eq!(SecretKey::from_sec1_der(SEC1_DER).unwrap(), seckey);
+        assert_eq!(SecretKey::from_sec1_pem(SEC1_NO_PUBLIC_PEM).unwrap(), seckey);
+        assert_eq!(SecretKey::from_pkcs8_pem(PKCS8_PEM).unwrap(), seckey);
+        assert_eq!(PublicKey::from_spki_pem(SPKI_PEM).unwrap(), pubkey);
+        assert_eq!(PublicKey::from_spki_der(SPKI_DER).unwrap(), pubkey);
+        assert_eq!(PublicKey::from_spki_pem(SPKI_COMPRESSED_PEM).unwrap(), pubkey);
+
+        assert_eq!(seckey.to_sec1_pem(), SEC1_PEM);
+        assert_eq!(seckey.to_sec1_der(), SEC1_DER);
+        assert_eq!(seckey.to_pkcs8_pem(), PKCS8_PEM);
+        assert_eq!(pubkey.to_spki_pem(false), SPKI_PEM);
+        assert_eq!(pubkey.to_spki_der(false), SPKI_DER);
+        assert_eq!(pubkey.to_spki_pem(true), SPKI_COMPRESSED_PEM);
+
+        // The text output embeds no PEM block unless asked to.
+        assert_eq!(SecretKey::from_sec1_pem(SEC1_TEXT), Err(Error::InvalidPem));
+        let with_text = format!("{}{}", SEC1_TEXT, SEC1_PEM);
+        assert_eq!(SecretKey::from_sec1_pem(&with_text).unwrap(), seckey);
+    }
+
+    #[test]
+    fn test_pem_armor() {
+        let data: Vec<u8> = (0..100u8).collect();
+        let pem = encode("TEST", &data);
+        assert!(pem.lines().all(|line| line.len() <= 64));
+        assert_eq!(decode("TEST", &pem).unwrap(), data);
+        assert_eq!(decode("TEST", &pem.replace('\n', "\r\n")).unwrap(), data);
+
+        assert_eq!(decode("OTHER", &pem), Err(Error::InvalidPem));
+        assert_eq!(decode("TEST", &pem.replace("END TEST", "END OTHER")), Err(Error::InvalidPem));
+        assert_eq!(decode("TEST", &pem.replace("AAE", "AA!")), Err(Error::InvalidPem));
+    }
+
+    #[test]
+    fn test_wrong_key_type() {
+        // A PKCS#8 key and an SPKI key for the wrong container.
+        assert_eq!(SecretKey::from_sec1_pem(PKCS8_PEM), Err(Error::InvalidPem));
+        assert_eq!(SecretKey::from_sec1_der(&decode("PRIVATE KEY", PKCS8_PEM).unwrap()), Err(Error::InvalidDer));
+        assert_eq!(SecretKey::from_pkcs8_der(SEC1_DER), Err(Error::InvalidDer));
+        assert_eq!(PublicKey::from_spki_der(SEC1_DER), Err(Error::InvalidDer));
+
+        // prime256v1 (1.2.840.10045.3.1.7) instead of secp256k1.
+        let mut der = SPKI_DER.to_vec();
+        let oid = [0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+        let pos = der.windows(oid.len()).position(|w| w == oid).unwrap();
+        der.splice(pos..(pos + oid.len()), vec![0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07]);
+        der[1] += 3;
+        der[3] += 3;
+        assert_eq!(PublicKey::from_spki_der(&der), Err(Error::InvalidDer));
+
+        // Trailing data.
+        let mut der = SPKI_DER.to_vec();
+        der.push(0);
+        assert_eq!(PublicKey::from_spki_der(&der), Err(Error::InvalidDer));
+    }
+
+    #[test]
+    fn test_mismatched_public_key() {
+        let seckey = SecretKey::from_sec1_der(SEC1_DER).unwrap();
+        let other = SecretKey::parse(&[0x01; 32]).unwrap();
+        let other_pubkey = PublicKey::from_secret_key(&other).serialize();
+        let pubkey = PublicKey::from_secret_key(&seckey).serialize();
+
+        let mut der = SEC1_DER.to_vec();
+        let pos = der.windows(65).position(|w| w == &pubkey[..]).unwrap();
+        der[pos..(pos + 65)].copy_from_slice(&other_pubkey);
+        assert_eq!(SecretKey::from_sec1_der(&der), Err(Error::InvalidPublicKey));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let seckey = SecretKey::parse(&[0x42; 32]).unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        assert_eq!(SecretKey::from_sec1_pem(&seckey.to_sec1_pem()).unwrap(), seckey);
+        assert_eq!(SecretKey::from_pkcs8_pem(&seckey.to_pkcs8_pem()).unwrap(), seckey);
+        assert_eq!(PublicKey::from_spki_pem(&pubkey.to_spki_pem(true)).unwrap(), pubkey);
+        assert_eq!(PublicKey::from_spki_pem(&pubkey.to_spki_pem(false)).unwrap(), pubkey);
+    }
+}
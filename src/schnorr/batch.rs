@@ -0,0 +1,162 @@
+use ecmult::ECMULT_CONTEXT;
+use secp256k1::group::{Affine, Jacobian};
+use secp256k1::{Scalar, XOnlyPublicKey};
+use super::bip340::{challenge, Signature};
+use super::tagged::{tagged_hash, TaggedHasher};
+
+/// Verify a batch of BIP-340 signatures, each given with its message and x-only public key. Returns true only if
+/// every signature is valid; use `find_invalid` to tell which ones are not.
+///
+/// Instead of checking `s_i.G = R_i + e_i.P_i` one by one, this checks the single random linear combination
+/// `sum(a_i.R_i) + sum(a_i.e_i.P_i) - sum(a_i.s_i).G = 0` with one multi-scalar multiplication. The weights `a_i`
+/// are derived from a hash of the whole batch, so that no invalid signature can be crafted to cancel out against
+/// another.
+pub fn verify_batch(items: &[(&Signature, &[u8], &XOnlyPublicKey)]) -> bool {
+    let mut seed = TaggedHasher::new(b"BIP0340/batch");
+    for &(signature, msg, pubkey) in items {
+        seed.input(&signature.serialize());
+        seed.input(&pubkey.serialize());
+        seed.input(&(msg.len() as u64).to_be_bytes());
+        seed.input(msg);
+    }
+    let seed = seed.result();
+
+    let mut points = Vec::with_capacity(2 * items.len());
+    let mut ng = Scalar::default();
+    for (i, &(signature, msg, pubkey)) in items.iter().enumerate() {
+        let mut rx = signature.r;
+        rx.normalize_var();
+        let mut r = Affine::default();
+        if !r.set_xo_var(&rx, false) {
+            return false;
+        }
+        let e = challenge(&rx.b32(), &pubkey.serialize(), msg);
+
+        // The first weight can be 1 without loss of security.
+        let mut a = Scalar::default();
+        if i == 0 {
+            a.set_int(1);
+        } else {
+            a.set_b32(&tagged_hash(b"BIP0340/batch", &[&seed, &(i as u64).to_be_bytes()]));
+        }
+        ng += a * signature.s;
+        points.push((a, r));
+        points.push((a * e, pubkey.0));
+    }
+
+    let mut rj = Jacobian::default();
+    ECMULT_CONTEXT.ecmult_multi(&mut rj, &points, &ng.neg());
+    rj.is_infinity()
+}
+
+/// Return the indices of the invalid signatures in a batch, in increasing order.
+///
+/// The batch is split in halves recursively and halves that pass `verify_batch` are not looked at again, so when
+/// only a few signatures are invalid this costs a few batch verifications rather than one verification per
+/// signature.
+pub fn find_invalid(items: &[(&Signature, &[u8], &XOnlyPublicKey)]) -> Vec<usize> {
+    let mut ret = Vec::new();
+    find_invalid_in(items, 0, &mut ret);
+    ret
+}
+
+fn find_invalid_in(items: &[(&Signature, &[u8], &XOnlyPublicKey)], offset: usize, ret: &mut Vec<usize>) {
+    if items.is_empty() || verify_batch(items) {
+        return;
+    }
+    if items.len() == 1 {
+        ret.push(offset);
+        return;
+    }
+    let mid = items.len() / 2;
+    find_invalid_in(&items[..mid], offset, ret);
+    find_invalid_in(&items[mid..], offset + mid, ret);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_invalid, verify_batch};
+    use hex;
+    use rand::{thread_rng, Rng};
+    use schnorr::{sign, verify, Signature};
+    use {PublicKey, SecretKey, XOnlyPublicKey};
+
+    type Item = (Signature, Vec<u8>, XOnlyPublicKey);
+
+    fn random_items(n: usize) -> Vec<Item> {
+        let mut rng = thread_rng();
+        (0..n)
+            .map(|i| {
+                let seckey = SecretKey::random(&mut rng);
+                let msg = vec![i as u8; i % 40];
+                let mut aux = [0u8; 32];
+                rng.fill_bytes(&mut aux);
+                let signature = sign(&msg, &seckey, &aux).unwrap();
+                (signature, msg, PublicKey::from_secret_key(&seckey).x_only().0)
+            })
+            .collect()
+    }
+
+    fn batch(items: &[Item]) -> Vec<(&Signature, &[u8], &XOnlyPublicKey)> {
+        items.iter().map(|item| (&item.0, &item.1[..], &item.2)).collect()
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        assert!(verify_batch(&[]));
+        assert!(find_invalid(&[]).is_empty());
+
+        let mut items = random_items(33);
+        assert!(verify_batch(&batch(&items)));
+        assert!(verify_batch(&batch(&items[..1])));
+        assert!(find_invalid(&batch(&items)).is_empty());
+
+        // A wrong message, a signature for another key, and a tampered s.
+        items[3].1.push(0);
+        items[17].2 = items[18].2;
+        let s = items[32].0.s;
+        items[32].0.s += s;
+        assert!(!verify_batch(&batch(&items)));
+        assert!(!verify_batch(&batch(&items[32..])));
+        assert_eq!(find_invalid(&batch(&items)), vec![3, 17, 32]);
+        for (i, item) in items.iter().enumerate() {
+            assert_eq!(verify(&item.0, &item.1, &item.2), ![3, 17, 32].contains(&i));
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_cancelling() {
+        // Two invalid signatures whose errors cancel out in an unweighted sum: s_1 + d and s_2 - d.
+        let mut items = random_items(2);
+        let d = items[0].0.s;
+        items[0].0.s += d;
+        items[1].0.s += d.neg();
+        assert!(!verify_batch(&batch(&items)));
+        assert_eq!(find_invalid(&batch(&items)), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_verify_batch_bip340_vectors() {
+        // BIP-340 test vector 1 is valid. Vectors 6 and 9 are invalid: R has an odd y-coordinate, and R.x is not
+        // on the curve.
+        let pubkey = XOnlyPublicKey::from_hex("DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659").unwrap();
+        let msg = hex::decode("243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89").unwrap();
+        let sigs: Vec<Signature> = [
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+            "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+            "0000000000000000000000000000000000000000000000000000000000000000123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051",
+        ]
+        .iter()
+        .map(|h| {
+            let mut p = [0u8; 64];
+            p.copy_from_slice(&hex::decode(h).unwrap());
+            Signature::parse(&p).unwrap()
+        })
+        .collect();
+
+        let items: Vec<(&Signature, &[u8], &XOnlyPublicKey)> = sigs.iter().map(|s| (s, &msg[..], &pubkey)).collect();
+        assert!(verify_batch(&items[..1]));
+        assert!(!verify_batch(&items));
+        assert_eq!(find_invalid(&items), vec![1, 2]);
+    }
+}
@@ -0,0 +1,276 @@
+use ecmult::{ECMULT_CONTEXT, ECMULT_GEN_CONTEXT};
+use secp256k1::field::Field;
+use secp256k1::group::{Affine, Jacobian};
+use secp256k1::{Error, PublicKey, Scalar, SecretKey, XOnlyPublicKey};
+use super::tagged::tagged_hash;
+
+/// A BIP-340 Schnorr signature, encoded as the 64 bytes `bytes(R.x) || bytes(s)`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Signature {
+    pub r: Field,
+    pub s: Scalar,
+}
+
+impl Signature {
+    /// Parse a 64-byte signature. Fails if `r` is not below the field size or `s` is not below the group order.
+    pub fn parse(p: &[u8; 64]) -> Result<Signature, Error> {
+        let mut r = Field::default();
+        if !r.set_b32(array_ref!(p, 0, 32)) {
+            return Err(Error::InvalidSignature);
+        }
+        let mut s = Scalar::default();
+        if s.set_b32(array_ref!(p, 32, 32)) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(Signature { r, s })
+    }
+
+    pub fn serialize(&self) -> [u8; 64] {
+        let mut ret = [0u8; 64];
+        let mut r = self.r;
+        r.normalize_var();
+        r.fill_b32(array_mut_ref!(ret, 0, 32));
+        self.s.fill_b32(array_mut_ref!(ret, 32, 32));
+        ret
+    }
+}
+
+/// Compute `k.G` and return it as a normalized affine point.
+fn mul_gen(k: &Scalar) -> Affine {
+    let mut pj = Jacobian::default();
+    ECMULT_GEN_CONTEXT.ecmult_gen(&mut pj, k);
+    let mut p = Affine::default();
+    p.set_gej(&pj);
+    p.x.normalize();
+    p.y.normalize();
+    p
+}
+
+/// e = int(hash_BIP0340/challenge(bytes(R) || bytes(P) || m)) mod n
+pub(super) fn challenge(rx: &[u8; 32], px: &[u8; 32], msg: &[u8]) -> Scalar {
+    let hash = tagged_hash(b"BIP0340/challenge", &[rx, px, msg]);
+    let mut e = Scalar::default();
+    e.set_b32(&hash);
+    e
+}
+
+/// Sign a message following BIP-340. The secret key's public point is negated if needed so that it has an even y
+/// coordinate, and `aux_rand` is mixed into the nonce derivation as described in the BIP.
+pub fn sign(msg: &[u8], seckey: &SecretKey, aux_rand: &[u8; 32]) -> Result<Signature, Error> {
+    let (p, parity) = PublicKey::from_secret_key(seckey).x_only();
+    let d = if parity.is_odd() { seckey.0.neg() } else { seckey.0 };
+    let px = p.serialize();
+
+    let aux = tagged_hash(b"BIP0340/aux", &[aux_rand]);
+    let mut t = d.b32();
+    for (t, a) in t.iter_mut().zip(aux.iter()) {
+        *t ^= a;
+    }
+    let rand = tagged_hash(b"BIP0340/nonce", &[&t, &px, msg]);
+
+    let mut k = Scalar::default();
+    k.set_b32(&rand);
+    if k.is_zero() {
+        return Err(Error::InvalidMessage);
+    }
+    let r = mul_gen(&k);
+    if r.y.is_odd() {
+        k = k.neg();
+    }
+    let rx = r.x.b32();
+
+    let e = challenge(&rx, &px, msg);
+    let s = k + e * d;
+    k.clear();
+
+    Ok(Signature { r: r.x, s })
+}
+
+/// Verify a BIP-340 signature against a message and an x-only public key.
+pub fn verify(signature: &Signature, msg: &[u8], pubkey: &XOnlyPublicKey) -> bool {
+    let mut rx = signature.r;
+    rx.normalize_var();
+    let e = challenge(&rx.b32(), &pubkey.serialize(), msg);
+
+    // R = s.G - e.P
+    let mut pj = Jacobian::default();
+    pj.set_ge(&pubkey.0);
+    let mut rj = Jacobian::default();
+    ECMULT_CONTEXT.ecmult(&mut rj, &pj, &e.neg(), &signature.s);
+
+    let mut r = Affine::default();
+    r.set_gej_var(&rj);
+    if r.is_infinity() {
+        return false;
+    }
+    r.x.normalize_var();
+    r.y.normalize_var();
+
+    !r.y.is_odd() && r.x == rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sign, verify, Signature};
+    use hex;
+    use {PublicKey, SecretKey, XOnlyPublicKey};
+
+    fn decode32(h: &str) -> [u8; 32] {
+        let mut ret = [0u8; 32];
+        ret.copy_from_slice(&hex::decode(h).unwrap());
+        ret
+    }
+
+    fn decode64(h: &str) -> [u8; 64] {
+        let mut ret = [0u8; 64];
+        ret.copy_from_slice(&hex::decode(h).unwrap());
+        ret
+    }
+
+    /// (index, secret key, public key, aux_rand, message, signature, verification result) from the BIP-340
+    /// test-vectors.csv.
+    const VECTORS: &[(u8, &str, &str, &str, &str, &str, bool)] = &[
+        (0, "0000000000000000000000000000000000000000000000000000000000000003",
+         "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+         "0000000000000000000000000000000000000000000000000000000000000000",
+         "0000000000000000000000000000000000000000000000000000000000000000",
+         "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+         true),
+        (1, "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "0000000000000000000000000000000000000000000000000000000000000001",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+         true),
+        (2, "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+         "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+         "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+         "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+         "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+         true),
+        (3, "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+         "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+         "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+         "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+         "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+         true),
+        (4, "",
+         "D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9",
+         "",
+         "4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703",
+         "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+         true),
+        (5, "",
+         "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+         false),
+        (6, "",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+         false),
+        (7, "",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD",
+         false),
+        (8, "",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6",
+         false),
+        (9, "",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "0000000000000000000000000000000000000000000000000000000000000000123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051",
+         false),
+        (10, "",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "00000000000000000000000000000000000000000000000000000000000000017615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197",
+         false),
+        (11, "",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+         false),
+        (12, "",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+         false),
+        (13, "",
+         "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+         false),
+        (14, "",
+         "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+         "",
+         "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+         "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+         false),
+        (15, "0340034003400340034003400340034003400340034003400340034003400340",
+         "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+         "0000000000000000000000000000000000000000000000000000000000000000",
+         "",
+         "71535DB165ECD9FBBC046E5FFAEA61186BB6AD436732FCCC25291A55895464CF6069CE26BF03466228F19A3A62DB8A649F2D560FAC652827D1AF0574E427AB63",
+         true),
+        (16, "0340034003400340034003400340034003400340034003400340034003400340",
+         "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+         "0000000000000000000000000000000000000000000000000000000000000000",
+         "11",
+         "08A20A0AFEF64124649232E0693C583AB1B9934AE63B4C3511F3AE1134C6A303EA3173BFEA6683BD101FA5AA5DBC1996FE7CACFC5A577D33EC14564CEC2BACBF",
+         true),
+        (17, "0340034003400340034003400340034003400340034003400340034003400340",
+         "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+         "0000000000000000000000000000000000000000000000000000000000000000",
+         "0102030405060708090A0B0C0D0E0F1011",
+         "5130F39A4059B43BC7CAC09A19ECE52B5D8699D1A71E3C52DA9AFDB6B50AC370C4A482B77BF960F8681540E25B6771ECE1E5A37FD80E5A51897C5566A97EA5A5",
+         true),
+        (18, "0340034003400340034003400340034003400340034003400340034003400340",
+         "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+         "0000000000000000000000000000000000000000000000000000000000000000",
+         "99999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999",
+         "403B12B0D8555A344175EA7EC746566303321E5DBFA8BE6F091635163ECA79A8585ED3E3170807E7C03B720FC54C7B23897FCBA0E9D0B4A06894CFD249F22367",
+         true),
+    ];
+
+    #[test]
+    fn bip340_sign_vectors() {
+        for &(index, seckey, pubkey, aux_rand, msg, sig, _) in VECTORS {
+            if seckey.is_empty() {
+                continue;
+            }
+            let seckey = SecretKey::from_hex(seckey).unwrap();
+            let msg = hex::decode(msg).unwrap();
+            let signature = sign(&msg, &seckey, &decode32(aux_rand)).unwrap();
+            assert_eq!(&signature.serialize()[..], &decode64(sig)[..], "wrong signature for index {}", index);
+            let pubkey = XOnlyPublicKey::from_hex(pubkey).unwrap();
+            assert_eq!(pubkey, PublicKey::from_secret_key(&seckey).into());
+            assert!(verify(&signature, &msg, &pubkey), "signature does not verify for index {}", index);
+        }
+    }
+
+    #[test]
+    fn bip340_verify_vectors() {
+        for &(index, _, pubkey, _, msg, sig, expected) in VECTORS {
+            let msg = hex::decode(msg).unwrap();
+            let valid = match (Signature::parse(&decode64(sig)), XOnlyPublicKey::from_hex(pubkey)) {
+                (Ok(signature), Ok(pubkey)) => verify(&signature, &msg, &pubkey),
+                _ => false,
+            };
+            assert_eq!(valid, expected, "incorrect validation for index {}", index);
+        }
+    }
+}
@@ -2,6 +2,7 @@ use secp256k1::{PublicKey, SecretKey};
 use secp256k1::Message;
 use secp256k1::Error;
 use sha2::{ Digest, Sha256 };
+use super::tagged::TaggedHasher;
 
 /// Objects implementing Combinable can be serialized as bytes for use in producing hash challenges
 /// e.g. H( R || T || m)
@@ -22,6 +23,12 @@ impl Combinable for [u8; 32] {
     }
 }
 
+impl Combinable for Vec<u8> {
+    fn as_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+}
+
 /// A Challenge of the form H(P || R || ... || m).
 /// Challenges are often used in constructing signatures. Since we use SHA256 to derive H(...), the value is a scalar
 /// and can be used as a secret key. Thus if we let
@@ -47,6 +54,19 @@ impl Challenge {
         Challenge(h)
     }
 
+    /// A domain separated challenge hash_tag(len(a) || a || len(b) || b || ...), using a BIP-340 tagged hash and
+    /// prefixing each item with its length as 8 big-endian bytes, so that items of different lengths cannot be
+    /// shifted into one another and challenges from different protocols never collide.
+    pub fn tagged(tag: &[u8], items: &[&dyn Combinable]) -> Challenge {
+        let mut hasher = TaggedHasher::new(tag);
+        for &item in items {
+            let bytes = item.as_bytes();
+            hasher.input(&(bytes.len() as u64).to_be_bytes());
+            hasher.input(&bytes);
+        }
+        Challenge(hasher.result())
+    }
+
     pub fn as_scalar(&self) -> Result<SecretKey, Error> {
         SecretKey::parse(&self.0)
     }
@@ -64,3 +84,25 @@ impl Combinable for Message {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::Challenge;
+
+    #[test]
+    fn test_tagged_challenge_framing() {
+        let a = b"ab".to_vec();
+        let b = b"c".to_vec();
+        let ab = b"abc".to_vec();
+        let empty = Vec::new();
+
+        // Plain challenges only see the concatenation.
+        assert_eq!(Challenge::new(&[&a, &b]).0, Challenge::new(&[&ab]).0);
+        assert_eq!(Challenge::new(&[&ab, &empty]).0, Challenge::new(&[&ab]).0);
+
+        assert_ne!(Challenge::tagged(b"test", &[&a, &b]).0, Challenge::tagged(b"test", &[&ab]).0);
+        assert_ne!(Challenge::tagged(b"test", &[&ab, &empty]).0, Challenge::tagged(b"test", &[&ab]).0);
+        assert_ne!(Challenge::tagged(b"test", &[&ab]).0, Challenge::tagged(b"other", &[&ab]).0);
+        assert_eq!(Challenge::tagged(b"test", &[&a, &b]).0, Challenge::tagged(b"test", &[&a, &b]).0);
+    }
+}
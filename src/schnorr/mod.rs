@@ -1,5 +1,11 @@
 mod schnorr;
 mod challenge;
+mod bip340;
+mod tagged;
+mod batch;
 
 pub use self::schnorr::Schnorr;
 pub use self::challenge::{Challenge, Combinable};
+pub use self::bip340::{sign, verify, Signature};
+pub use self::tagged::{tagged_hash, TaggedHasher};
+pub use self::batch::{find_invalid, verify_batch};
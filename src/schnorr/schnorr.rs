@@ -21,6 +21,8 @@ impl Schnorr {
     /// P_A' = H(H(P_A||P_B) || P_A) * P_A ,
     /// P_B' = H(H(P_A||P_B) || P_B) * P_B ,
     /// joint_key = P_A' + P_B'
+    ///
+    /// This is not the BIP-327 aggregate key; use `musig::KeyAggContext` for MuSig2 signing.
     pub fn partial_joint_key_from(Pa : &PublicKey, Pb: &PublicKey) -> PublicKey {
         let (p_a, p_b) = Schnorr::partial_joint_hashes_from(Pa, Pb);
         let partial_a = p_a * *Pa;
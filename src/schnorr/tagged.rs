@@ -0,0 +1,74 @@
+use sha2::{Digest, Sha256};
+
+/// A SHA-256 hasher for BIP-340 style tagged hashes,
+/// `SHA256(SHA256(tag) || SHA256(tag) || data...)`.
+///
+/// The 64-byte tag prefix fills exactly one SHA-256 block, so the hasher created by `new` holds the midstate after
+/// that block. Keep it around and clone it for each hash to avoid hashing the prefix again.
+#[derive(Clone)]
+pub struct TaggedHasher {
+    hasher: Sha256,
+}
+
+impl TaggedHasher {
+    pub fn new(tag: &[u8]) -> TaggedHasher {
+        let tag_hash = Sha256::digest(tag);
+        let mut hasher = Sha256::default();
+        hasher.input(&tag_hash);
+        hasher.input(&tag_hash);
+        TaggedHasher { hasher }
+    }
+
+    pub fn input(&mut self, data: &[u8]) {
+        self.hasher.input(data);
+    }
+
+    pub fn result(self) -> [u8; 32] {
+        let mut ret = [0u8; 32];
+        ret.copy_from_slice(&self.hasher.result());
+        ret
+    }
+
+    /// Hash the concatenation of `data` starting from the cached midstate.
+    pub fn hash(&self, data: &[&[u8]]) -> [u8; 32] {
+        let mut hasher = self.clone();
+        for d in data {
+            hasher.input(d);
+        }
+        hasher.result()
+    }
+}
+
+/// Compute the BIP-340 tagged hash SHA256(SHA256(tag) || SHA256(tag) || data...).
+pub fn tagged_hash(tag: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    TaggedHasher::new(tag).hash(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tagged_hash, TaggedHasher};
+    use hex;
+
+    #[test]
+    fn test_tagged_hash() {
+        // BIP-341 wallet test vectors, TapTweak of a key path only output.
+        let p = hex::decode("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap();
+        let expected = "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70";
+        assert_eq!(hex::encode(tagged_hash(b"TapTweak", &[&p])), expected);
+
+        let mut hasher = TaggedHasher::new(b"TapTweak");
+        hasher.input(&p[..10]);
+        hasher.input(&p[10..]);
+        assert_eq!(hex::encode(hasher.result()), expected);
+    }
+
+    #[test]
+    fn test_midstate_reuse() {
+        let hasher = TaggedHasher::new(b"BIP0340/challenge");
+        let a = hasher.hash(&[b"first"]);
+        let b = hasher.hash(&[b"second"]);
+        assert_eq!(a, tagged_hash(b"BIP0340/challenge", &[b"first"]));
+        assert_eq!(b, tagged_hash(b"BIP0340/challenge", &[b"second"]));
+        assert_ne!(a, tagged_hash(b"BIP0340/nonce", &[b"first"]));
+    }
+}
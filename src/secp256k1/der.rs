@@ -0,0 +1,386 @@
+//! ASN.1 DER helpers for ECDSA signatures, following the strict and
+//! lax parsers of libsecp256k1, and a reader and writer for the key
+//! containers of the `pem` module.
+
+use secp256k1::scalar::Scalar;
+use secp256k1::error::Error;
+
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_CONTEXT_0: u8 = 0xa0;
+pub const TAG_CONTEXT_1: u8 = 0xa1;
+
+/// Maximum length of a DER encoded signature: 6 bytes of framing and
+/// two 33 byte integers.
+pub const MAX_SIGNATURE_DER_LEN: usize = 6 + 33 + 33;
+
+/// Read a DER length, rejecting indefinite, reserved and non-minimal
+/// encodings as well as lengths running past the end of the input.
+fn read_len(input: &[u8], pos: &mut usize) -> Result<usize, Error> {
+    if *pos >= input.len() {
+        return Err(Error::InvalidSignature);
+    }
+    let b1 = input[*pos];
+    *pos += 1;
+
+    if b1 == 0xff {
+        // X.690-0207 8.1.3.5.c the value 0xFF shall not be used.
+        return Err(Error::InvalidSignature);
+    }
+    if b1 & 0x80 == 0 {
+        // X.690-0207 8.1.3.4 short form length octets.
+        let len = b1 as usize;
+        if len > input.len() - *pos {
+            return Err(Error::InvalidSignature);
+        }
+        return Ok(len);
+    }
+    if b1 == 0x80 {
+        // Indefinite length is not allowed in DER.
+        return Err(Error::InvalidSignature);
+    }
+
+    // X.690-0207 8.1.3.5 long form length octets.
+    let lenleft = (b1 & 0x7f) as usize;
+    if lenleft > input.len() - *pos {
+        return Err(Error::InvalidSignature);
+    }
+    if input[*pos] == 0 {
+        // Not the shortest possible length encoding.
+        return Err(Error::InvalidSignature);
+    }
+    if lenleft > ::std::mem::size_of::<usize>() {
+        return Err(Error::InvalidSignature);
+    }
+    let mut len = 0usize;
+    for b in &input[*pos..(*pos + lenleft)] {
+        len = (len << 8) | (*b as usize);
+    }
+    *pos += lenleft;
+    if len > input.len() - *pos {
+        return Err(Error::InvalidSignature);
+    }
+    if len < 128 {
+        // Not the shortest possible length encoding.
+        return Err(Error::InvalidSignature);
+    }
+    Ok(len)
+}
+
+/// Parse a strict DER INTEGER into a scalar. Negative values and values
+/// not below the group order are rejected.
+fn parse_integer(input: &[u8], pos: &mut usize) -> Result<Scalar, Error> {
+    if *pos >= input.len() || input[*pos] != TAG_INTEGER {
+        // Not a primitive integer (X.690-0207 8.3.1).
+        return Err(Error::InvalidSignature);
+    }
+    *pos += 1;
+
+    let mut len = read_len(input, pos)?;
+    if len == 0 || len > input.len() - *pos {
+        return Err(Error::InvalidSignature);
+    }
+    let bytes = &input[*pos..(*pos + len)];
+    if len > 1 && bytes[0] == 0x00 && bytes[1] & 0x80 == 0x00 {
+        // Excessive 0x00 padding.
+        return Err(Error::InvalidSignature);
+    }
+    if len > 1 && bytes[0] == 0xff && bytes[1] & 0x80 == 0x80 {
+        // Excessive 0xFF padding.
+        return Err(Error::InvalidSignature);
+    }
+    if bytes[0] & 0x80 == 0x80 {
+        // Negative.
+        return Err(Error::InvalidSignature);
+    }
+    *pos += len;
+
+    let mut bytes = bytes;
+    if bytes[0] == 0 {
+        bytes = &bytes[1..];
+        len -= 1;
+    }
+    if len > 32 {
+        return Err(Error::InvalidSignature);
+    }
+
+    let mut b32 = [0u8; 32];
+    b32[(32 - len)..].copy_from_slice(bytes);
+    let mut ret = Scalar::default();
+    if ret.set_b32(&b32) {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(ret)
+}
+
+/// Strictly parse a DER encoded `(r, s)` pair, as required by BIP-66.
+pub fn parse_signature(input: &[u8]) -> Result<(Scalar, Scalar), Error> {
+    let mut pos = 0;
+    if input.is_empty() || input[0] != TAG_SEQUENCE {
+        // The encoding doesn't start with a constructed sequence
+        // (X.690-0207 8.9.1).
+        return Err(Error::InvalidSignature);
+    }
+    pos += 1;
+
+    let len = read_len(input, &mut pos)?;
+    if len != input.len() - pos {
+        // Tuple exceeds bounds or garbage after tuple.
+        return Err(Error::InvalidSignature);
+    }
+
+    let r = parse_integer(input, &mut pos)?;
+    let s = parse_integer(input, &mut pos)?;
+
+    if pos != input.len() {
+        // Trailing garbage inside tuple.
+        return Err(Error::InvalidSignature);
+    }
+
+    Ok((r, s))
+}
+
+/// Read a lax length: long form lengths may carry any number of leading
+/// zero bytes, and the sequence length is skipped entirely by the caller.
+fn read_lax_len(input: &[u8], pos: &mut usize) -> Result<usize, Error> {
+    if *pos == input.len() {
+        return Err(Error::InvalidSignature);
+    }
+    let mut lenbyte = input[*pos] as usize;
+    *pos += 1;
+    if lenbyte & 0x80 == 0 {
+        return Ok(lenbyte);
+    }
+
+    lenbyte -= 0x80;
+    if lenbyte > input.len() - *pos {
+        return Err(Error::InvalidSignature);
+    }
+    while lenbyte > 0 && input[*pos] == 0 {
+        *pos += 1;
+        lenbyte -= 1;
+    }
+    if lenbyte >= ::std::mem::size_of::<usize>() {
+        return Err(Error::InvalidSignature);
+    }
+    let mut len = 0usize;
+    while lenbyte > 0 {
+        len = (len << 8) + input[*pos] as usize;
+        *pos += 1;
+        lenbyte -= 1;
+    }
+    Ok(len)
+}
+
+/// Parse a lax DER INTEGER, returning the position and length of its
+/// value bytes.
+fn read_lax_integer(input: &[u8], pos: &mut usize) -> Result<(usize, usize), Error> {
+    if *pos == input.len() || input[*pos] != 0x02 {
+        return Err(Error::InvalidSignature);
+    }
+    *pos += 1;
+
+    let len = read_lax_len(input, pos)?;
+    if len > input.len() - *pos {
+        return Err(Error::InvalidSignature);
+    }
+    Ok((*pos, len))
+}
+
+/// Copy a lax integer into a 32 byte buffer, ignoring leading zeroes.
+/// Returns false if the value does not fit.
+fn copy_lax_integer(value: &[u8], out: &mut [u8; 32]) -> bool {
+    let mut value = value;
+    while !value.is_empty() && value[0] == 0 {
+        value = &value[1..];
+    }
+    if value.len() > 32 {
+        return false;
+    }
+    out[(32 - value.len())..].copy_from_slice(value);
+    true
+}
+
+/// Parse a DER-like `(r, s)` pair the way libsecp256k1's
+/// `ecdsa_signature_parse_der_lax` does. Values that overflow result in
+/// an all-zero pair, which never verifies.
+pub fn parse_signature_lax(input: &[u8]) -> Result<(Scalar, Scalar), Error> {
+    let mut pos = 0;
+
+    // Sequence tag byte.
+    if pos == input.len() || input[pos] != 0x30 {
+        return Err(Error::InvalidSignature);
+    }
+    pos += 1;
+
+    // Sequence length bytes.
+    if pos == input.len() {
+        return Err(Error::InvalidSignature);
+    }
+    let mut lenbyte = input[pos] as usize;
+    pos += 1;
+    if lenbyte & 0x80 != 0 {
+        lenbyte -= 0x80;
+        if lenbyte > input.len() - pos {
+            return Err(Error::InvalidSignature);
+        }
+        pos += lenbyte;
+    }
+
+    let (rpos, rlen) = read_lax_integer(input, &mut pos)?;
+    pos += rlen;
+    let (spos, slen) = read_lax_integer(input, &mut pos)?;
+
+    let mut r = Scalar::default();
+    let mut s = Scalar::default();
+    let mut rb = [0u8; 32];
+    let mut sb = [0u8; 32];
+    if copy_lax_integer(&input[rpos..(rpos + rlen)], &mut rb) &&
+        copy_lax_integer(&input[spos..(spos + slen)], &mut sb)
+    {
+        let overflow_r = r.set_b32(&rb);
+        let overflow_s = s.set_b32(&sb);
+        if overflow_r || overflow_s {
+            r.clear();
+            s.clear();
+        }
+    }
+
+    Ok((r, s))
+}
+
+/// Encode a single scalar as a DER INTEGER, trimming leading zero bytes
+/// while keeping the sign bit clear.
+fn serialize_integer(a: &Scalar, out: &mut Vec<u8>) {
+    let mut b = [0u8; 33];
+    a.fill_b32(array_mut_ref!(b, 1, 32));
+    let mut start = 0;
+    while start < 32 && b[start] == 0 && b[start + 1] < 0x80 {
+        start += 1;
+    }
+    out.push(0x02);
+    out.push((33 - start) as u8);
+    out.extend_from_slice(&b[start..]);
+}
+
+/// Serialize an `(r, s)` pair as a DER sequence.
+pub fn serialize_signature(r: &Scalar, s: &Scalar) -> Vec<u8> {
+    let mut body = Vec::with_capacity(MAX_SIGNATURE_DER_LEN - 2);
+    serialize_integer(r, &mut body);
+    serialize_integer(s, &mut body);
+
+    let mut ret = Vec::with_capacity(2 + body.len());
+    ret.push(0x30);
+    ret.push(body.len() as u8);
+    ret.extend_from_slice(&body);
+    ret
+}
+
+/// Encode a tag, its minimal length and the content.
+pub fn encode(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut ret = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        ret.push(len as u8);
+    } else {
+        let bytes = (len as u64).to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        ret.push(0x80 | (bytes.len() - skip) as u8);
+        ret.extend_from_slice(&bytes[skip..]);
+    }
+    ret.extend_from_slice(content);
+    ret
+}
+
+/// Sequential reader over the elements of a DER encoded value. Lengths are
+/// read with the same strict rules as signatures, but errors are reported
+/// as `Error::InvalidDer`.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader { data, pos: 0 }
+    }
+
+    /// The tag of the next element, if any.
+    pub fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).cloned()
+    }
+
+    /// Read an element with the given tag and return its content.
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8], Error> {
+        if self.peek() != Some(tag) {
+            return Err(Error::InvalidDer);
+        }
+        self.pos += 1;
+        let len = read_len(self.data, &mut self.pos).or(Err(Error::InvalidDer))?;
+        let content = &self.data[self.pos..(self.pos + len)];
+        self.pos += len;
+        Ok(content)
+    }
+
+    /// Read an element with the given tag and check its content.
+    pub fn expect(&mut self, tag: u8, content: &[u8]) -> Result<(), Error> {
+        if self.read(tag)? != content {
+            return Err(Error::InvalidDer);
+        }
+        Ok(())
+    }
+
+    /// Check that the whole input has been consumed.
+    pub fn finish(self) -> Result<(), Error> {
+        if self.pos != self.data.len() {
+            return Err(Error::InvalidDer);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, Reader, TAG_OCTET_STRING, TAG_SEQUENCE};
+    use Error;
+
+    #[test]
+    fn test_lengths() {
+        for len in &[0usize, 1, 127, 128, 255, 256, 1000] {
+            let content = vec![0x55; *len];
+            let encoded = encode(TAG_OCTET_STRING, &content);
+            let mut reader = Reader::new(&encoded);
+            assert_eq!(reader.read(TAG_OCTET_STRING).unwrap(), &content[..]);
+            reader.finish().unwrap();
+        }
+        assert_eq!(&encode(TAG_OCTET_STRING, &[0u8; 128])[..3], &[0x04, 0x81, 0x80]);
+        assert_eq!(&encode(TAG_OCTET_STRING, &[0u8; 256])[..4], &[0x04, 0x82, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let invalid: [&[u8]; 6] = [
+            // Wrong tag.
+            &[0x04, 0x00],
+            // Truncated content.
+            &[0x30, 0x02, 0x00],
+            // Indefinite length.
+            &[0x30, 0x80, 0x00, 0x00],
+            // Non-minimal long form lengths.
+            &[0x30, 0x81, 0x01, 0x00],
+            &[0x30, 0x82, 0x00, 0x01, 0x00],
+            // Missing length.
+            &[0x30],
+        ];
+        for data in invalid.iter() {
+            assert_eq!(Reader::new(data).read(TAG_SEQUENCE), Err(Error::InvalidDer), "{:?}", data);
+        }
+
+        let mut reader = Reader::new(&[0x30, 0x00, 0x00]);
+        reader.read(TAG_SEQUENCE).unwrap();
+        assert_eq!(reader.finish(), Err(Error::InvalidDer));
+    }
+}
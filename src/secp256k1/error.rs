@@ -6,4 +6,24 @@ pub enum Error {
     InvalidRecoveryId,
     InvalidMessage,
     InvalidHex,
+    InvalidTweak,
+    InvalidChecksum,
+    InvalidTypedData,
+    InvalidBase58,
+    InvalidBase64,
+    InvalidAddress,
+    InvalidDerivationPath,
+    InvalidExtendedKey,
+    InvalidEntropyLength,
+    InvalidMnemonicLength,
+    InvalidMnemonicWord(usize),
+    InvalidDer,
+    InvalidPem,
+    InvalidJwk,
+    InvalidJws,
+    InvalidNonce,
+    InvalidIdentifier,
+    InvalidThreshold,
+    InvalidShare,
+    InvalidProof,
 }
@@ -1,59 +1,6 @@
-use std::ops::{ Add, AddAssign, Mul,  MulAssign };
 use std::cmp::Ordering;
-
-macro_rules! debug_assert_bits {
-    ($x:expr, $n:expr) => {
-        debug_assert!($x >> $n == 0);
-    };
-}
-
-macro_rules! field_const_raw {
-    (
-        $d9:expr,
-        $d8:expr,
-        $d7:expr,
-        $d6:expr,
-        $d5:expr,
-        $d4:expr,
-        $d3:expr,
-        $d2:expr,
-        $d1:expr,
-        $d0:expr
-    ) => {
-        Field {
-            n: [$d0, $d1, $d2, $d3, $d4, $d5, $d6, $d7, $d8, $d9],
-            magnitude: 1,
-            normalized: false,
-        }
-    };
-}
-
-macro_rules! field_const {
-    ($d7:expr, $d6:expr, $d5:expr, $d4:expr, $d3:expr, $d2:expr, $d1:expr, $d0:expr) => {
-        Field {
-            n: [
-                $d0 & 0x3ffffff,
-                ($d0 >> 26) | (($d1 & 0xfffff) << 6),
-                ($d1 >> 20) | (($d2 & 0x3fff) << 12),
-                ($d2 >> 14) | (($d3 & 0xff) << 18),
-                ($d3 >> 8) | (($d4 & 0x3) << 24),
-                ($d4 >> 2) & 0x3ffffff,
-                ($d4 >> 28) | (($d5 & 0x3fffff) << 4),
-                ($d5 >> 22) | (($d6 & 0xffff) << 10),
-                ($d6 >> 16) | (($d7 & 0x3ff) << 16),
-                ($d7 >> 10),
-            ],
-            magnitude: 1,
-            normalized: true,
-        }
-    };
-}
-
-macro_rules! field_storage_const {
-    ($d7:expr, $d6:expr, $d5:expr, $d4:expr, $d3:expr, $d2:expr, $d1:expr, $d0:expr) => {
-        $crate::secp256k1::field::FieldStorage([$d0, $d1, $d2, $d3, $d4, $d5, $d6, $d7])
-    };
-}
+use std::ops::AddAssign;
+use super::FieldStorage;
 
 #[derive(Debug, Clone, Copy)]
 /// Field element for secp256k1.
@@ -64,8 +11,33 @@ pub struct Field {
 }
 
 impl Field {
-    pub fn new(d7: u32, d6: u32, d5: u32, d4: u32, d3: u32, d2: u32, d1: u32, d0: u32) -> Self {
-        field_const!(d7, d6, d5, d4, d3, d2, d1, d0)
+    pub const fn new(d7: u32, d6: u32, d5: u32, d4: u32, d3: u32, d2: u32, d1: u32, d0: u32) -> Self {
+        Field {
+            n: [
+                d0 & 0x3ffffff,
+                (d0 >> 26) | ((d1 & 0xfffff) << 6),
+                (d1 >> 20) | ((d2 & 0x3fff) << 12),
+                (d2 >> 14) | ((d3 & 0xff) << 18),
+                (d3 >> 8) | ((d4 & 0x3) << 24),
+                (d4 >> 2) & 0x3ffffff,
+                (d4 >> 28) | ((d5 & 0x3fffff) << 4),
+                (d5 >> 22) | ((d6 & 0xffff) << 10),
+                (d6 >> 16) | ((d7 & 0x3ff) << 16),
+                (d7 >> 10),
+            ],
+            magnitude: 1,
+            normalized: true,
+        }
+    }
+
+    /// Build a field element of magnitude 1 from ten 26-bit limbs,
+    /// least significant first.
+    pub const fn new_raw(n: [u32; 10]) -> Self {
+        Field {
+            n,
+            magnitude: 1,
+            normalized: false,
+        }
     }
 
     pub fn from_int(a: u32) -> Field {
@@ -1453,191 +1425,6 @@ impl Field {
         debug_assert!(a.verify());
     }
 
-    pub fn sqr(&self) -> Field {
-        let mut ret = Field::default();
-        ret.sqr_in_place(self);
-        ret
-    }
-
-    /// If a has a square root, it is computed in r and 1 is
-    /// returned. If a does not have a square root, the root of its
-    /// negation is computed and 0 is returned. The input's magnitude
-    /// can be at most 8. The output magnitude is 1 (but not
-    /// guaranteed to be normalized). The result in r will always be a
-    /// square itself.
-    pub fn sqrt(&self) -> (Field, bool) {
-        let mut x2 = self.sqr();
-        x2 *= self;
-
-        let mut x3 = x2.sqr();
-        x3 *= self;
-
-        let mut x6 = x3.clone();
-        for _ in 0..3 {
-            x6 = x6.sqr();
-        }
-        x6 *= &x3;
-
-        let mut x9 = x6.clone();
-        for _ in 0..3 {
-            x9 = x9.sqr();
-        }
-        x9 *= &x3;
-
-        let mut x11 = x9.clone();
-        for _ in 0..2 {
-            x11 = x11.sqr();
-        }
-        x11 *= &x2;
-
-        let mut x22 = x11.clone();
-        for _ in 0..11 {
-            x22 = x22.sqr();
-        }
-        x22 *= &x11;
-
-        let mut x44 = x22.clone();
-        for _ in 0..22 {
-            x44 = x44.sqr();
-        }
-        x44 *= &x22;
-
-        let mut x88 = x44.clone();
-        for _ in 0..44 {
-            x88 = x88.sqr();
-        }
-        x88 *= &x44;
-
-        let mut x176 = x88.clone();
-        for _ in 0..88 {
-            x176 = x176.sqr();
-        }
-        x176 *= &x88;
-
-        let mut x220 = x176.clone();
-        for _ in 0..44 {
-            x220 = x220.sqr();
-        }
-        x220 *= &x44;
-
-        let mut x223 = x220.clone();
-        for _ in 0..3 {
-            x223 = x223.sqr();
-        }
-        x223 *= &x3;
-
-        let mut t1 = x223;
-        for _ in 0..23 {
-            t1 = t1.sqr();
-        }
-        t1 *= &x22;
-        for _ in 0..6 {
-            t1 = t1.sqr();
-        }
-        t1 *= &x2;
-        t1 = t1.sqr();
-        let r = t1.sqr();
-
-        t1 = r.sqr();
-        (r, &t1 == self)
-    }
-
-    /// Sets a field element to be the (modular) inverse of
-    /// another. Requires the input's magnitude to be at most 8. The
-    /// output magnitude is 1 (but not guaranteed to be normalized).
-    pub fn inv(&self) -> Field {
-        let mut x2 = self.sqr();
-        x2 *= self;
-
-        let mut x3 = x2.sqr();
-        x3 *= self;
-
-        let mut x6 = x3.clone();
-        for _ in 0..3 {
-            x6 = x6.sqr();
-        }
-        x6 *= &x3;
-
-        let mut x9 = x6.clone();
-        for _ in 0..3 {
-            x9 = x9.sqr();
-        }
-        x9 *= &x3;
-
-        let mut x11 = x9.clone();
-        for _ in 0..2 {
-            x11 = x11.sqr();
-        }
-        x11 *= &x2;
-
-        let mut x22 = x11.clone();
-        for _ in 0..11 {
-            x22 = x22.sqr();
-        }
-        x22 *= &x11;
-
-        let mut x44 = x22.clone();
-        for _ in 0..22 {
-            x44 = x44.sqr();
-        }
-        x44 *= &x22;
-
-        let mut x88 = x44.clone();
-        for _ in 0..44 {
-            x88 = x88.sqr();
-        }
-        x88 *= &x44;
-
-        let mut x176 = x88.clone();
-        for _ in 0..88 {
-            x176 = x176.sqr();
-        }
-        x176 *= &x88;
-
-        let mut x220 = x176.clone();
-        for _ in 0..44 {
-            x220 = x220.sqr();
-        }
-        x220 *= &x44;
-
-        let mut x223 = x220.clone();
-        for _ in 0..3 {
-            x223 = x223.sqr();
-        }
-        x223 *= &x3;
-
-        let mut t1 = x223.clone();
-        for _ in 0..23 {
-            t1 = t1.sqr();
-        }
-        t1 *= &x22;
-        for _ in 0..5 {
-            t1 = t1.sqr();
-        }
-        t1 *= self;
-        for _ in 0..3 {
-            t1 = t1.sqr();
-        }
-        t1 *= &x2;
-        for _ in 0..2 {
-            t1 = t1.sqr();
-        }
-        let r = self * &t1;
-        r
-    }
-
-    /// Potentially faster version of secp256k1_fe_inv, without
-    /// constant-time guarantee.
-    pub fn inv_var(&self) -> Field {
-        self.inv()
-    }
-
-    /// Checks whether a field element is a quadratic residue.
-    pub fn is_quad_var(&self) -> bool {
-        let (_, ret) = self.sqrt();
-        ret
-    }
-
     /// If flag is true, set *r equal to *a; otherwise leave
     /// it. Constant-time.
     pub fn cmov(&mut self, other: &Field, flag: bool) {
@@ -1684,24 +1471,6 @@ impl Default for Field {
     }
 }
 
-impl Add<Field> for Field {
-    type Output = Field;
-    fn add(self, other: Field) -> Field {
-        let mut ret = self.clone();
-        ret.add_assign(&other);
-        ret
-    }
-}
-
-impl<'a, 'b> Add<&'a Field> for &'b Field {
-    type Output = Field;
-    fn add(self, other: &'a Field) -> Field {
-        let mut ret = self.clone();
-        ret.add_assign(other);
-        ret
-    }
-}
-
 impl<'a> AddAssign<&'a Field> for Field {
     fn add_assign(&mut self, other: &'a Field) {
         self.n[0] += other.n[0];
@@ -1721,101 +1490,6 @@ impl<'a> AddAssign<&'a Field> for Field {
     }
 }
 
-impl AddAssign<Field> for Field {
-    fn add_assign(&mut self, other: Field) {
-        self.add_assign(&other)
-    }
-}
-
-impl Mul<Field> for Field {
-    type Output = Field;
-    fn mul(self, other: Field) -> Field {
-        let mut ret = Field::default();
-        ret.mul_in_place(&self, &other);
-        ret
-    }
-}
-
-impl<'a, 'b> Mul<&'a Field> for &'b Field {
-    type Output = Field;
-    fn mul(self, other: &'a Field) -> Field {
-        let mut ret = Field::default();
-        ret.mul_in_place(self, other);
-        ret
-    }
-}
-
-impl<'a> MulAssign<&'a Field> for Field {
-    fn mul_assign(&mut self, other: &'a Field) {
-        let mut ret = Field::default();
-        ret.mul_in_place(self, other);
-        *self = ret;
-    }
-}
-
-impl MulAssign<Field> for Field {
-    fn mul_assign(&mut self, other: Field) {
-        self.mul_assign(&other)
-    }
-}
-
-impl PartialEq for Field {
-    fn eq(&self, other: &Field) -> bool {
-        let mut na = self.neg(1);
-        na += other;
-        return na.normalizes_to_zero();
-    }
-}
-
-impl Eq for Field {}
-
-impl Ord for Field {
-    fn cmp(&self, other: &Field) -> Ordering {
-        self.cmp_var(other)
-    }
-}
-
-impl PartialOrd for Field {
-    fn partial_cmp(&self, other: &Field) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
-/// Compact field element storage.
-pub struct FieldStorage(pub [u32; 8]);
-
-impl Default for FieldStorage {
-    fn default() -> FieldStorage {
-        FieldStorage([0; 8])
-    }
-}
-
-impl FieldStorage {
-    pub fn new(d7: u32, d6: u32, d5: u32, d4: u32, d3: u32, d2: u32, d1: u32, d0: u32) -> Self {
-        field_storage_const!(d7, d6, d5, d4, d3, d2, d1, d0)
-    }
-
-    pub fn cmov(&mut self, other: &FieldStorage, flag: bool) {
-        self.0[0] =
-            if flag { other.0[0] } else { self.0[0] };
-        self.0[1] =
-            if flag { other.0[1] } else { self.0[1] };
-        self.0[2] =
-            if flag { other.0[2] } else { self.0[2] };
-        self.0[3] =
-            if flag { other.0[3] } else { self.0[3] };
-        self.0[4] =
-            if flag { other.0[4] } else { self.0[4] };
-        self.0[5] =
-            if flag { other.0[5] } else { self.0[5] };
-        self.0[6] =
-            if flag { other.0[6] } else { self.0[6] };
-        self.0[7] =
-            if flag { other.0[7] } else { self.0[7] };
-    }
-}
-
 impl From<FieldStorage> for Field {
     fn from(a: FieldStorage) -> Field {
         let mut r = Field::default();
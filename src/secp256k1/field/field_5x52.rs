@@ -0,0 +1,904 @@
+use std::cmp::Ordering;
+use std::ops::AddAssign;
+use super::FieldStorage;
+
+#[derive(Debug, Clone, Copy)]
+/// Field element for secp256k1.
+pub struct Field {
+    pub(crate) n: [u64; 5],
+    pub(crate) magnitude: u32,
+    pub(crate) normalized: bool,
+}
+
+impl Field {
+    pub const fn new(d7: u32, d6: u32, d5: u32, d4: u32, d3: u32, d2: u32, d1: u32, d0: u32) -> Self {
+        Field {
+            n: [
+                (d0 as u64) | (((d1 as u64) & 0xfffff) << 32),
+                ((d1 as u64) >> 20) | ((d2 as u64) << 12) | (((d3 as u64) & 0xff) << 44),
+                ((d3 as u64) >> 8) | (((d4 as u64) & 0xfffffff) << 24),
+                ((d4 as u64) >> 28) | ((d5 as u64) << 4) | (((d6 as u64) & 0xffff) << 36),
+                ((d6 as u64) >> 16) | ((d7 as u64) << 16),
+            ],
+            magnitude: 1,
+            normalized: true,
+        }
+    }
+
+    /// Build a field element of magnitude 1 from ten 26-bit limbs,
+    /// least significant first.
+    pub const fn new_raw(n: [u32; 10]) -> Self {
+        let mut t0 = (n[0] as u64) + ((n[1] as u64) << 26);
+        let mut t1 = (n[2] as u64) + ((n[3] as u64) << 26);
+        let mut t2 = (n[4] as u64) + ((n[5] as u64) << 26);
+        let mut t3 = (n[6] as u64) + ((n[7] as u64) << 26);
+        let mut t4 = (n[8] as u64) + ((n[9] as u64) << 26);
+
+        t1 += t0 >> 52;
+        t0 &= 0xFFFFFFFFFFFFF;
+        t2 += t1 >> 52;
+        t1 &= 0xFFFFFFFFFFFFF;
+        t3 += t2 >> 52;
+        t2 &= 0xFFFFFFFFFFFFF;
+        t4 += t3 >> 52;
+        t3 &= 0xFFFFFFFFFFFFF;
+
+        let x = t4 >> 48;
+        t4 &= 0x0FFFFFFFFFFFF;
+
+        t0 += x * 0x1000003D1;
+        t1 += t0 >> 52;
+        t0 &= 0xFFFFFFFFFFFFF;
+        t2 += t1 >> 52;
+        t1 &= 0xFFFFFFFFFFFFF;
+        t3 += t2 >> 52;
+        t2 &= 0xFFFFFFFFFFFFF;
+        t4 += t3 >> 52;
+        t3 &= 0xFFFFFFFFFFFFF;
+
+        Field {
+            n: [t0, t1, t2, t3, t4],
+            magnitude: 1,
+            normalized: false,
+        }
+    }
+
+    pub fn from_int(a: u32) -> Field {
+        let mut f = Field::default();
+        f.set_int(a);
+        f
+    }
+
+    fn verify(&self) -> bool {
+        let m = if self.normalized { 1 } else { 2 } * self.magnitude as u64;
+        let mut r = true;
+        r = r && (self.n[0] <= 0xFFFFFFFFFFFFF * m);
+        r = r && (self.n[1] <= 0xFFFFFFFFFFFFF * m);
+        r = r && (self.n[2] <= 0xFFFFFFFFFFFFF * m);
+        r = r && (self.n[3] <= 0xFFFFFFFFFFFFF * m);
+        r = r && (self.n[4] <= 0x0FFFFFFFFFFFF * m);
+        r = r && (self.magnitude <= 32);
+        if self.normalized {
+            r = r && self.magnitude <= 1;
+            if r && (self.n[4] == 0x0FFFFFFFFFFFF)
+                && (self.n[3] & self.n[2] & self.n[1]) == 0xFFFFFFFFFFFFF
+            {
+                r = r && (self.n[0] < 0xFFFFEFFFFFC2F);
+            }
+        }
+        r
+    }
+
+    /// Normalize a field element.
+    pub fn normalize(&mut self) {
+        let mut t0 = self.n[0];
+        let mut t1 = self.n[1];
+        let mut t2 = self.n[2];
+        let mut t3 = self.n[3];
+        let mut t4 = self.n[4];
+
+        let mut m: u64;
+        let mut x = t4 >> 48;
+        t4 &= 0x0FFFFFFFFFFFF;
+
+        t0 += x * 0x1000003D1;
+        t1 += t0 >> 52;
+        t0 &= 0xFFFFFFFFFFFFF;
+        t2 += t1 >> 52;
+        t1 &= 0xFFFFFFFFFFFFF;
+        m = t1;
+        t3 += t2 >> 52;
+        t2 &= 0xFFFFFFFFFFFFF;
+        m &= t2;
+        t4 += t3 >> 52;
+        t3 &= 0xFFFFFFFFFFFFF;
+        m &= t3;
+
+        debug_assert!(t4 >> 49 == 0);
+
+        x = (t4 >> 48)
+            | (if t4 == 0x0FFFFFFFFFFFF { 1 } else { 0 }
+                & if m == 0xFFFFFFFFFFFFF { 1 } else { 0 }
+                & if t0 >= 0xFFFFEFFFFFC2F { 1 } else { 0 });
+
+        t0 += x * 0x1000003D1;
+        t1 += t0 >> 52;
+        t0 &= 0xFFFFFFFFFFFFF;
+        t2 += t1 >> 52;
+        t1 &= 0xFFFFFFFFFFFFF;
+        t3 += t2 >> 52;
+        t2 &= 0xFFFFFFFFFFFFF;
+        t4 += t3 >> 52;
+        t3 &= 0xFFFFFFFFFFFFF;
+
+        debug_assert!(t4 >> 48 == x);
+
+        t4 &= 0x0FFFFFFFFFFFF;
+
+        self.n = [t0, t1, t2, t3, t4];
+        self.magnitude = 1;
+        self.normalized = true;
+        debug_assert!(self.verify());
+    }
+
+    /// Weakly normalize a field element: reduce it magnitude to 1,
+    /// but don't fully normalize.
+    pub fn normalize_weak(&mut self) {
+        let mut t0 = self.n[0];
+        let mut t1 = self.n[1];
+        let mut t2 = self.n[2];
+        let mut t3 = self.n[3];
+        let mut t4 = self.n[4];
+
+        let x = t4 >> 48;
+        t4 &= 0x0FFFFFFFFFFFF;
+
+        t0 += x * 0x1000003D1;
+        t1 += t0 >> 52;
+        t0 &= 0xFFFFFFFFFFFFF;
+        t2 += t1 >> 52;
+        t1 &= 0xFFFFFFFFFFFFF;
+        t3 += t2 >> 52;
+        t2 &= 0xFFFFFFFFFFFFF;
+        t4 += t3 >> 52;
+        t3 &= 0xFFFFFFFFFFFFF;
+
+        debug_assert!(t4 >> 49 == 0);
+
+        self.n = [t0, t1, t2, t3, t4];
+        self.magnitude = 1;
+        debug_assert!(self.verify());
+    }
+
+    /// Normalize a field element, without constant-time guarantee.
+    pub fn normalize_var(&mut self) {
+        let mut t0 = self.n[0];
+        let mut t1 = self.n[1];
+        let mut t2 = self.n[2];
+        let mut t3 = self.n[3];
+        let mut t4 = self.n[4];
+
+        let mut m: u64;
+        let mut x = t4 >> 48;
+        t4 &= 0x0FFFFFFFFFFFF;
+
+        t0 += x * 0x1000003D1;
+        t1 += t0 >> 52;
+        t0 &= 0xFFFFFFFFFFFFF;
+        t2 += t1 >> 52;
+        t1 &= 0xFFFFFFFFFFFFF;
+        m = t1;
+        t3 += t2 >> 52;
+        t2 &= 0xFFFFFFFFFFFFF;
+        m &= t2;
+        t4 += t3 >> 52;
+        t3 &= 0xFFFFFFFFFFFFF;
+        m &= t3;
+
+        debug_assert!(t4 >> 49 == 0);
+
+        x = (t4 >> 48)
+            | (if t4 == 0x0FFFFFFFFFFFF { 1 } else { 0 }
+                & if m == 0xFFFFFFFFFFFFF { 1 } else { 0 }
+                & if t0 >= 0xFFFFEFFFFFC2F { 1 } else { 0 });
+
+        if x > 0 {
+            t0 += 0x1000003D1;
+            t1 += t0 >> 52;
+            t0 &= 0xFFFFFFFFFFFFF;
+            t2 += t1 >> 52;
+            t1 &= 0xFFFFFFFFFFFFF;
+            t3 += t2 >> 52;
+            t2 &= 0xFFFFFFFFFFFFF;
+            t4 += t3 >> 52;
+            t3 &= 0xFFFFFFFFFFFFF;
+
+            debug_assert!(t4 >> 48 == x);
+
+            t4 &= 0x0FFFFFFFFFFFF;
+        }
+
+        self.n = [t0, t1, t2, t3, t4];
+        self.magnitude = 1;
+        self.normalized = true;
+        debug_assert!(self.verify());
+    }
+
+    /// Verify whether a field element represents zero i.e. would
+    /// normalize to a zero value. The field implementation may
+    /// optionally normalize the input, but this should not be relied
+    /// upon.
+    pub fn normalizes_to_zero(&self) -> bool {
+        let mut t0 = self.n[0];
+        let mut t1 = self.n[1];
+        let mut t2 = self.n[2];
+        let mut t3 = self.n[3];
+        let mut t4 = self.n[4];
+
+        let mut z0: u64;
+        let mut z1: u64;
+
+        let x = t4 >> 48;
+        t4 &= 0x0FFFFFFFFFFFF;
+
+        t0 += x * 0x1000003D1;
+        t1 += t0 >> 52;
+        t0 &= 0xFFFFFFFFFFFFF;
+        z0 = t0;
+        z1 = t0 ^ 0x1000003D0;
+        t2 += t1 >> 52;
+        t1 &= 0xFFFFFFFFFFFFF;
+        z0 |= t1;
+        z1 &= t1;
+        t3 += t2 >> 52;
+        t2 &= 0xFFFFFFFFFFFFF;
+        z0 |= t2;
+        z1 &= t2;
+        t4 += t3 >> 52;
+        t3 &= 0xFFFFFFFFFFFFF;
+        z0 |= t3;
+        z1 &= t3;
+        z0 |= t4;
+        z1 &= t4 ^ 0xF000000000000;
+
+        debug_assert!(t4 >> 49 == 0);
+
+        z0 == 0 || z1 == 0xFFFFFFFFFFFFF
+    }
+
+    /// Verify whether a field element represents zero i.e. would
+    /// normalize to a zero value. The field implementation may
+    /// optionally normalize the input, but this should not be relied
+    /// upon.
+    pub fn normalizes_to_zero_var(&self) -> bool {
+        let mut t0: u64;
+        let mut t1: u64;
+        let mut t2: u64;
+        let mut t3: u64;
+        let mut t4: u64;
+        let mut z0: u64;
+        let mut z1: u64;
+
+        t0 = self.n[0];
+        t4 = self.n[4];
+
+        let x = t4 >> 48;
+        t0 += x * 0x1000003D1;
+
+        z0 = t0 & 0xFFFFFFFFFFFFF;
+        z1 = z0 ^ 0x1000003D0;
+
+        if z0 != 0 && z1 != 0xFFFFFFFFFFFFF {
+            return false;
+        }
+
+        t1 = self.n[1];
+        t2 = self.n[2];
+        t3 = self.n[3];
+
+        t4 &= 0x0FFFFFFFFFFFF;
+
+        t1 += t0 >> 52;
+        t2 += t1 >> 52;
+        t1 &= 0xFFFFFFFFFFFFF;
+        z0 |= t1;
+        z1 &= t1;
+        t3 += t2 >> 52;
+        t2 &= 0xFFFFFFFFFFFFF;
+        z0 |= t2;
+        z1 &= t2;
+        t4 += t3 >> 52;
+        t3 &= 0xFFFFFFFFFFFFF;
+        z0 |= t3;
+        z1 &= t3;
+        z0 |= t4;
+        z1 &= t4 ^ 0xF000000000000;
+
+        debug_assert!(t4 >> 49 == 0);
+
+        z0 == 0 || z1 == 0xFFFFFFFFFFFFF
+    }
+
+    /// Set a field element equal to a small integer. Resulting field
+    /// element is normalized.
+    pub fn set_int(&mut self, a: u32) {
+        self.n = [a as u64, 0, 0, 0, 0];
+        self.magnitude = 1;
+        self.normalized = true;
+        debug_assert!(self.verify());
+    }
+
+    /// Verify whether a field element is zero. Requires the input to
+    /// be normalized.
+    pub fn is_zero(&self) -> bool {
+        debug_assert!(self.normalized);
+        debug_assert!(self.verify());
+        (self.n[0] | self.n[1] | self.n[2] | self.n[3] | self.n[4]) == 0
+    }
+
+    /// Check the "oddness" of a field element. Requires the input to
+    /// be normalized.
+    pub fn is_odd(&self) -> bool {
+        debug_assert!(self.normalized);
+        debug_assert!(self.verify());
+        self.n[0] & 1 != 0
+    }
+
+    /// Sets a field element equal to zero, initializing all fields.
+    pub fn clear(&mut self) {
+        self.magnitude = 0;
+        self.normalized = true;
+        self.n = [0, 0, 0, 0, 0];
+    }
+
+    /// Set a field element equal to 32-byte big endian value. If
+    /// successful, the resulting field element is normalized.
+    pub fn set_b32(&mut self, a: &[u8; 32]) -> bool {
+        self.n[0] = (a[31] as u64)
+            | ((a[30] as u64) << 8)
+            | ((a[29] as u64) << 16)
+            | ((a[28] as u64) << 24)
+            | ((a[27] as u64) << 32)
+            | ((a[26] as u64) << 40)
+            | (((a[25] & 0xf) as u64) << 48);
+        self.n[1] = (((a[25] >> 4) & 0xf) as u64)
+            | ((a[24] as u64) << 4)
+            | ((a[23] as u64) << 12)
+            | ((a[22] as u64) << 20)
+            | ((a[21] as u64) << 28)
+            | ((a[20] as u64) << 36)
+            | ((a[19] as u64) << 44);
+        self.n[2] = (a[18] as u64)
+            | ((a[17] as u64) << 8)
+            | ((a[16] as u64) << 16)
+            | ((a[15] as u64) << 24)
+            | ((a[14] as u64) << 32)
+            | ((a[13] as u64) << 40)
+            | (((a[12] & 0xf) as u64) << 48);
+        self.n[3] = (((a[12] >> 4) & 0xf) as u64)
+            | ((a[11] as u64) << 4)
+            | ((a[10] as u64) << 12)
+            | ((a[9] as u64) << 20)
+            | ((a[8] as u64) << 28)
+            | ((a[7] as u64) << 36)
+            | ((a[6] as u64) << 44);
+        self.n[4] = (a[5] as u64)
+            | ((a[4] as u64) << 8)
+            | ((a[3] as u64) << 16)
+            | ((a[2] as u64) << 24)
+            | ((a[1] as u64) << 32)
+            | ((a[0] as u64) << 40);
+
+        if self.n[4] == 0x0FFFFFFFFFFFF
+            && (self.n[3] & self.n[2] & self.n[1]) == 0xFFFFFFFFFFFFF
+            && self.n[0] >= 0xFFFFEFFFFFC2F
+        {
+            return false;
+        }
+
+        self.magnitude = 1;
+        self.normalized = true;
+        debug_assert!(self.verify());
+
+        true
+    }
+
+    pub fn fill_b32(&self, r: &mut [u8; 32]) {
+        debug_assert!(self.normalized);
+        debug_assert!(self.verify());
+
+        r[0] = ((self.n[4] >> 40) & 0xff) as u8;
+        r[1] = ((self.n[4] >> 32) & 0xff) as u8;
+        r[2] = ((self.n[4] >> 24) & 0xff) as u8;
+        r[3] = ((self.n[4] >> 16) & 0xff) as u8;
+        r[4] = ((self.n[4] >> 8) & 0xff) as u8;
+        r[5] = (self.n[4] & 0xff) as u8;
+        r[6] = ((self.n[3] >> 44) & 0xff) as u8;
+        r[7] = ((self.n[3] >> 36) & 0xff) as u8;
+        r[8] = ((self.n[3] >> 28) & 0xff) as u8;
+        r[9] = ((self.n[3] >> 20) & 0xff) as u8;
+        r[10] = ((self.n[3] >> 12) & 0xff) as u8;
+        r[11] = ((self.n[3] >> 4) & 0xff) as u8;
+        r[12] = (((self.n[2] >> 48) & 0xf) | ((self.n[3] & 0xf) << 4)) as u8;
+        r[13] = ((self.n[2] >> 40) & 0xff) as u8;
+        r[14] = ((self.n[2] >> 32) & 0xff) as u8;
+        r[15] = ((self.n[2] >> 24) & 0xff) as u8;
+        r[16] = ((self.n[2] >> 16) & 0xff) as u8;
+        r[17] = ((self.n[2] >> 8) & 0xff) as u8;
+        r[18] = (self.n[2] & 0xff) as u8;
+        r[19] = ((self.n[1] >> 44) & 0xff) as u8;
+        r[20] = ((self.n[1] >> 36) & 0xff) as u8;
+        r[21] = ((self.n[1] >> 28) & 0xff) as u8;
+        r[22] = ((self.n[1] >> 20) & 0xff) as u8;
+        r[23] = ((self.n[1] >> 12) & 0xff) as u8;
+        r[24] = ((self.n[1] >> 4) & 0xff) as u8;
+        r[25] = (((self.n[0] >> 48) & 0xf) | ((self.n[1] & 0xf) << 4)) as u8;
+        r[26] = ((self.n[0] >> 40) & 0xff) as u8;
+        r[27] = ((self.n[0] >> 32) & 0xff) as u8;
+        r[28] = ((self.n[0] >> 24) & 0xff) as u8;
+        r[29] = ((self.n[0] >> 16) & 0xff) as u8;
+        r[30] = ((self.n[0] >> 8) & 0xff) as u8;
+        r[31] = (self.n[0] & 0xff) as u8;
+    }
+
+    /// Convert a field element to a 32-byte big endian
+    /// value. Requires the input to be normalized.
+    pub fn b32(&self) -> [u8; 32] {
+        let mut r = [0u8; 32];
+        self.fill_b32(&mut r);
+        r
+    }
+
+    /// Set a field element equal to the additive inverse of
+    /// another. Takes a maximum magnitude of the input as an
+    /// argument. The magnitude of the output is one higher.
+    pub fn neg_in_place(&mut self, other: &Field, m: u32) {
+        debug_assert!(other.magnitude <= m);
+        debug_assert!(other.verify());
+
+        let m = m as u64;
+        self.n[0] = 0xFFFFEFFFFFC2F * 2 * (m + 1) - other.n[0];
+        self.n[1] = 0xFFFFFFFFFFFFF * 2 * (m + 1) - other.n[1];
+        self.n[2] = 0xFFFFFFFFFFFFF * 2 * (m + 1) - other.n[2];
+        self.n[3] = 0xFFFFFFFFFFFFF * 2 * (m + 1) - other.n[3];
+        self.n[4] = 0x0FFFFFFFFFFFF * 2 * (m + 1) - other.n[4];
+
+        self.magnitude = m as u32 + 1;
+        self.normalized = false;
+        debug_assert!(self.verify());
+    }
+
+    pub fn neg(&self, m: u32) -> Field {
+        let mut ret = Field::default();
+        ret.neg_in_place(self, m);
+        ret
+    }
+
+    /// Multiplies the passed field element with a small integer
+    /// constant. Multiplies the magnitude by that small integer.
+    pub fn mul_int(&mut self, a: u32) {
+        self.n[0] *= a as u64;
+        self.n[1] *= a as u64;
+        self.n[2] *= a as u64;
+        self.n[3] *= a as u64;
+        self.n[4] *= a as u64;
+
+        self.magnitude *= a;
+        self.normalized = false;
+        debug_assert!(self.verify());
+    }
+
+    /// Compare two field elements. Requires both inputs to be
+    /// normalized.
+    pub fn cmp_var(&self, other: &Field) -> Ordering {
+        // Variable time compare implementation.
+        debug_assert!(self.normalized);
+        debug_assert!(other.normalized);
+        debug_assert!(self.verify());
+        debug_assert!(other.verify());
+
+        for i in (0..5).rev() {
+            if self.n[i] > other.n[i] {
+                return Ordering::Greater;
+            }
+            if self.n[i] < other.n[i] {
+                return Ordering::Less;
+            }
+        }
+        Ordering::Equal
+    }
+
+    pub fn eq_var(&self, other: &Field) -> bool {
+        let mut na = self.neg(1);
+        na += other;
+        na.normalizes_to_zero_var()
+    }
+
+    fn mul_inner(&mut self, a: &Field, b: &Field) {
+        const M: u64 = 0xFFFFFFFFFFFFF;
+        const R: u64 = 0x1000003D10;
+
+        let (mut c, mut d): (u128, u128);
+        let (t3, mut t4, tx, mut u0): (u64, u64, u64, u64);
+        let (a0, a1, a2, a3, a4) = (a.n[0], a.n[1], a.n[2], a.n[3], a.n[4]);
+        let (b0, b1, b2, b3, b4) = (b.n[0], b.n[1], b.n[2], b.n[3], b.n[4]);
+
+        debug_assert_bits!(a0, 56);
+        debug_assert_bits!(a1, 56);
+        debug_assert_bits!(a2, 56);
+        debug_assert_bits!(a3, 56);
+        debug_assert_bits!(a4, 52);
+
+        debug_assert_bits!(b0, 56);
+        debug_assert_bits!(b1, 56);
+        debug_assert_bits!(b2, 56);
+        debug_assert_bits!(b3, 56);
+        debug_assert_bits!(b4, 52);
+
+        // [... a b c] is a shorthand for ... + a<<104 + b<<52 + c<<0 mod n.
+        // px is a shorthand for sum(a[i]*b[x-i], i=0..x).
+        // Note that [x 0 0 0 0 0] = [x*R].
+
+        d = (a0 as u128) * (b3 as u128)
+            + (a1 as u128) * (b2 as u128)
+            + (a2 as u128) * (b1 as u128)
+            + (a3 as u128) * (b0 as u128);
+        debug_assert_bits!(d, 114);
+        // [d 0 0 0] = [p3 0 0 0]
+        c = (a4 as u128) * (b4 as u128);
+        debug_assert_bits!(c, 112);
+        // [c 0 0 0 0 d 0 0 0] = [p8 0 0 0 0 p3 0 0 0]
+        d += (R as u128) * ((c as u64) as u128);
+        c >>= 64;
+        debug_assert_bits!(d, 115);
+        debug_assert_bits!(c, 48);
+        // [(c<<12) 0 0 0 0 0 d 0 0 0] = [p8 0 0 0 0 p3 0 0 0]
+        t3 = (d as u64) & M;
+        d >>= 52;
+        debug_assert_bits!(t3, 52);
+        debug_assert_bits!(d, 63);
+        // [(c<<12) 0 0 0 0 d t3 0 0 0] = [p8 0 0 0 0 p3 0 0 0]
+
+        d += (a0 as u128) * (b4 as u128)
+            + (a1 as u128) * (b3 as u128)
+            + (a2 as u128) * (b2 as u128)
+            + (a3 as u128) * (b1 as u128)
+            + (a4 as u128) * (b0 as u128);
+        debug_assert_bits!(d, 115);
+        // [(c<<12) 0 0 0 0 d t3 0 0 0] = [p8 0 0 0 p4 p3 0 0 0]
+        d += ((R << 12) as u128) * ((c as u64) as u128);
+        debug_assert_bits!(d, 116);
+        // [d t3 0 0 0] = [p8 0 0 0 p4 p3 0 0 0]
+        t4 = (d as u64) & M;
+        d >>= 52;
+        debug_assert_bits!(t4, 52);
+        debug_assert_bits!(d, 64);
+        // [d t4 t3 0 0 0] = [p8 0 0 0 p4 p3 0 0 0]
+        tx = t4 >> 48;
+        t4 &= M >> 4;
+        debug_assert_bits!(tx, 4);
+        debug_assert_bits!(t4, 48);
+        // [d t4+(tx<<48) t3 0 0 0] = [p8 0 0 0 p4 p3 0 0 0]
+
+        c = (a0 as u128) * (b0 as u128);
+        debug_assert_bits!(c, 112);
+        // [d t4+(tx<<48) t3 0 0 c] = [p8 0 0 0 p4 p3 0 0 p0]
+        d += (a1 as u128) * (b4 as u128)
+            + (a2 as u128) * (b3 as u128)
+            + (a3 as u128) * (b2 as u128)
+            + (a4 as u128) * (b1 as u128);
+        debug_assert_bits!(d, 115);
+        // [d t4+(tx<<48) t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        u0 = (d as u64) & M;
+        d >>= 52;
+        debug_assert_bits!(u0, 52);
+        debug_assert_bits!(d, 62);
+        // [d u0 t4+(tx<<48) t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        // [d 0 t4+(tx<<48)+(u0<<52) t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        u0 = (u0 << 4) | tx;
+        debug_assert_bits!(u0, 56);
+        // [d 0 t4+(u0<<48) t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        c += (u0 as u128) * ((R >> 4) as u128);
+        debug_assert_bits!(c, 113);
+        // [d 0 t4 t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        self.n[0] = (c as u64) & M;
+        c >>= 52;
+        debug_assert_bits!(self.n[0], 52);
+        debug_assert_bits!(c, 61);
+        // [d 0 t4 t3 0 c r0] = [p8 0 0 p5 p4 p3 0 0 p0]
+
+        c += (a0 as u128) * (b1 as u128) + (a1 as u128) * (b0 as u128);
+        debug_assert_bits!(c, 114);
+        // [d 0 t4 t3 0 c r0] = [p8 0 0 p5 p4 p3 0 p1 p0]
+        d += (a2 as u128) * (b4 as u128)
+            + (a3 as u128) * (b3 as u128)
+            + (a4 as u128) * (b2 as u128);
+        debug_assert_bits!(d, 114);
+        // [d 0 t4 t3 0 c r0] = [p8 0 p6 p5 p4 p3 0 p1 p0]
+        c += (((d as u64) & M) as u128) * (R as u128);
+        d >>= 52;
+        debug_assert_bits!(c, 115);
+        debug_assert_bits!(d, 62);
+        // [d 0 0 t4 t3 0 c r0] = [p8 0 p6 p5 p4 p3 0 p1 p0]
+        self.n[1] = (c as u64) & M;
+        c >>= 52;
+        debug_assert_bits!(self.n[1], 52);
+        debug_assert_bits!(c, 63);
+        // [d 0 0 t4 t3 c r1 r0] = [p8 0 p6 p5 p4 p3 0 p1 p0]
+
+        c += (a0 as u128) * (b2 as u128)
+            + (a1 as u128) * (b1 as u128)
+            + (a2 as u128) * (b0 as u128);
+        debug_assert_bits!(c, 114);
+        // [d 0 0 t4 t3 c r1 r0] = [p8 0 p6 p5 p4 p3 p2 p1 p0]
+        d += (a3 as u128) * (b4 as u128) + (a4 as u128) * (b3 as u128);
+        debug_assert_bits!(d, 114);
+        // [d 0 0 t4 t3 c t1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+        c += (R as u128) * ((d as u64) as u128);
+        d >>= 64;
+        debug_assert_bits!(c, 115);
+        debug_assert_bits!(d, 50);
+        // [(d<<12) 0 0 0 t4 t3 c r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+
+        self.n[2] = (c as u64) & M;
+        c >>= 52;
+        debug_assert_bits!(self.n[2], 52);
+        debug_assert_bits!(c, 63);
+        // [(d<<12) 0 0 0 t4 t3+c r2 r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+        c += ((R << 12) as u128) * ((d as u64) as u128) + (t3 as u128);
+        debug_assert_bits!(c, 100);
+        // [t4 c r2 r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+        self.n[3] = (c as u64) & M;
+        c >>= 52;
+        debug_assert_bits!(self.n[3], 52);
+        debug_assert_bits!(c, 48);
+        // [t4+c r3 r2 r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+        self.n[4] = (c as u64) + t4;
+        debug_assert_bits!(self.n[4], 49);
+        // [r4 r3 r2 r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+    }
+
+    fn sqr_inner(&mut self, a: &Field) {
+        const M: u64 = 0xFFFFFFFFFFFFF;
+        const R: u64 = 0x1000003D10;
+
+        let (mut c, mut d): (u128, u128);
+        let (t3, mut t4, tx, mut u0): (u64, u64, u64, u64);
+        let (mut a0, a1, a2, a3, mut a4) = (a.n[0], a.n[1], a.n[2], a.n[3], a.n[4]);
+
+        debug_assert_bits!(a0, 56);
+        debug_assert_bits!(a1, 56);
+        debug_assert_bits!(a2, 56);
+        debug_assert_bits!(a3, 56);
+        debug_assert_bits!(a4, 52);
+
+        // [... a b c] is a shorthand for ... + a<<104 + b<<52 + c<<0 mod n.
+        // px is a shorthand for sum(a[i]*a[x-i], i=0..x).
+        // Note that [x 0 0 0 0 0] = [x*R].
+
+        d = ((a0 * 2) as u128) * (a3 as u128) + ((a1 * 2) as u128) * (a2 as u128);
+        debug_assert_bits!(d, 114);
+        // [d 0 0 0] = [p3 0 0 0]
+        c = (a4 as u128) * (a4 as u128);
+        debug_assert_bits!(c, 112);
+        // [c 0 0 0 0 d 0 0 0] = [p8 0 0 0 0 p3 0 0 0]
+        d += (R as u128) * ((c as u64) as u128);
+        c >>= 64;
+        debug_assert_bits!(d, 115);
+        debug_assert_bits!(c, 48);
+        // [(c<<12) 0 0 0 0 0 d 0 0 0] = [p8 0 0 0 0 p3 0 0 0]
+        t3 = (d as u64) & M;
+        d >>= 52;
+        debug_assert_bits!(t3, 52);
+        debug_assert_bits!(d, 63);
+        // [(c<<12) 0 0 0 0 d t3 0 0 0] = [p8 0 0 0 0 p3 0 0 0]
+
+        a4 *= 2;
+        d += (a0 as u128) * (a4 as u128)
+            + ((a1 * 2) as u128) * (a3 as u128)
+            + (a2 as u128) * (a2 as u128);
+        debug_assert_bits!(d, 115);
+        // [(c<<12) 0 0 0 0 d t3 0 0 0] = [p8 0 0 0 p4 p3 0 0 0]
+        d += ((R << 12) as u128) * ((c as u64) as u128);
+        debug_assert_bits!(d, 116);
+        // [d t3 0 0 0] = [p8 0 0 0 p4 p3 0 0 0]
+        t4 = (d as u64) & M;
+        d >>= 52;
+        debug_assert_bits!(t4, 52);
+        debug_assert_bits!(d, 64);
+        // [d t4 t3 0 0 0] = [p8 0 0 0 p4 p3 0 0 0]
+        tx = t4 >> 48;
+        t4 &= M >> 4;
+        debug_assert_bits!(tx, 4);
+        debug_assert_bits!(t4, 48);
+        // [d t4+(tx<<48) t3 0 0 0] = [p8 0 0 0 p4 p3 0 0 0]
+
+        c = (a0 as u128) * (a0 as u128);
+        debug_assert_bits!(c, 112);
+        // [d t4+(tx<<48) t3 0 0 c] = [p8 0 0 0 p4 p3 0 0 p0]
+        d += (a1 as u128) * (a4 as u128) + ((a2 * 2) as u128) * (a3 as u128);
+        debug_assert_bits!(d, 114);
+        // [d t4+(tx<<48) t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        u0 = (d as u64) & M;
+        d >>= 52;
+        debug_assert_bits!(u0, 52);
+        debug_assert_bits!(d, 62);
+        // [d u0 t4+(tx<<48) t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        // [d 0 t4+(tx<<48)+(u0<<52) t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        u0 = (u0 << 4) | tx;
+        debug_assert_bits!(u0, 56);
+        // [d 0 t4+(u0<<48) t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        c += (u0 as u128) * ((R >> 4) as u128);
+        debug_assert_bits!(c, 113);
+        // [d 0 t4 t3 0 0 c] = [p8 0 0 p5 p4 p3 0 0 p0]
+        self.n[0] = (c as u64) & M;
+        c >>= 52;
+        debug_assert_bits!(self.n[0], 52);
+        debug_assert_bits!(c, 61);
+        // [d 0 t4 t3 0 c r0] = [p8 0 0 p5 p4 p3 0 0 p0]
+
+        a0 *= 2;
+        c += (a0 as u128) * (a1 as u128);
+        debug_assert_bits!(c, 114);
+        // [d 0 t4 t3 0 c r0] = [p8 0 0 p5 p4 p3 0 p1 p0]
+        d += (a2 as u128) * (a4 as u128) + (a3 as u128) * (a3 as u128);
+        debug_assert_bits!(d, 114);
+        // [d 0 t4 t3 0 c r0] = [p8 0 p6 p5 p4 p3 0 p1 p0]
+        c += (((d as u64) & M) as u128) * (R as u128);
+        d >>= 52;
+        debug_assert_bits!(c, 115);
+        debug_assert_bits!(d, 62);
+        // [d 0 0 t4 t3 0 c r0] = [p8 0 p6 p5 p4 p3 0 p1 p0]
+        self.n[1] = (c as u64) & M;
+        c >>= 52;
+        debug_assert_bits!(self.n[1], 52);
+        debug_assert_bits!(c, 63);
+        // [d 0 0 t4 t3 c r1 r0] = [p8 0 p6 p5 p4 p3 0 p1 p0]
+
+        c += (a0 as u128) * (a2 as u128) + (a1 as u128) * (a1 as u128);
+        debug_assert_bits!(c, 114);
+        // [d 0 0 t4 t3 c r1 r0] = [p8 0 p6 p5 p4 p3 p2 p1 p0]
+        d += (a3 as u128) * (a4 as u128);
+        debug_assert_bits!(d, 114);
+        // [d 0 0 t4 t3 c r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+        c += (R as u128) * ((d as u64) as u128);
+        d >>= 64;
+        debug_assert_bits!(c, 115);
+        debug_assert_bits!(d, 50);
+        // [(d<<12) 0 0 0 t4 t3 c r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+        self.n[2] = (c as u64) & M;
+        c >>= 52;
+        debug_assert_bits!(self.n[2], 52);
+        debug_assert_bits!(c, 63);
+        // [(d<<12) 0 0 0 t4 t3+c r2 r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+
+        c += ((R << 12) as u128) * ((d as u64) as u128) + (t3 as u128);
+        debug_assert_bits!(c, 100);
+        // [t4 c r2 r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+        self.n[3] = (c as u64) & M;
+        c >>= 52;
+        debug_assert_bits!(self.n[3], 52);
+        debug_assert_bits!(c, 48);
+        // [t4+c r3 r2 r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+        self.n[4] = (c as u64) + t4;
+        debug_assert_bits!(self.n[4], 49);
+        // [r4 r3 r2 r1 r0] = [p8 p7 p6 p5 p4 p3 p2 p1 p0]
+    }
+
+    /// Sets a field element to be the product of two others. Requires
+    /// the inputs' magnitudes to be at most 8. The output magnitude
+    /// is 1 (but not guaranteed to be normalized).
+    pub fn mul_in_place(&mut self, a: &Field, b: &Field) {
+        debug_assert!(a.magnitude <= 8);
+        debug_assert!(b.magnitude <= 8);
+        debug_assert!(a.verify());
+        debug_assert!(b.verify());
+        self.mul_inner(a, b);
+        self.magnitude = 1;
+        self.normalized = false;
+        debug_assert!(self.verify());
+    }
+
+    /// Sets a field element to be the square of another. Requires the
+    /// input's magnitude to be at most 8. The output magnitude is 1
+    /// (but not guaranteed to be normalized).
+    pub fn sqr_in_place(&mut self, a: &Field) {
+        debug_assert!(a.magnitude <= 8);
+        debug_assert!(a.verify());
+        self.sqr_inner(a);
+        self.magnitude = 1;
+        self.normalized = false;
+        debug_assert!(self.verify());
+    }
+
+    /// If flag is true, set *r equal to *a; otherwise leave
+    /// it. Constant-time.
+    pub fn cmov(&mut self, other: &Field, flag: bool) {
+        let mask0 = (flag as u64).wrapping_sub(1);
+        let mask1 = !mask0;
+        self.n[0] = (self.n[0] & mask0) | (other.n[0] & mask1);
+        self.n[1] = (self.n[1] & mask0) | (other.n[1] & mask1);
+        self.n[2] = (self.n[2] & mask0) | (other.n[2] & mask1);
+        self.n[3] = (self.n[3] & mask0) | (other.n[3] & mask1);
+        self.n[4] = (self.n[4] & mask0) | (other.n[4] & mask1);
+        self.magnitude = if flag {
+            other.magnitude
+        } else {
+            self.magnitude
+        };
+        self.normalized = if flag {
+            other.normalized
+        } else {
+            self.normalized
+        };
+    }
+}
+
+impl Default for Field {
+    fn default() -> Field {
+        Self {
+            n: [0u64; 5],
+            magnitude: 0,
+            normalized: true,
+        }
+    }
+}
+
+impl<'a> AddAssign<&'a Field> for Field {
+    fn add_assign(&mut self, other: &'a Field) {
+        self.n[0] += other.n[0];
+        self.n[1] += other.n[1];
+        self.n[2] += other.n[2];
+        self.n[3] += other.n[3];
+        self.n[4] += other.n[4];
+
+        self.magnitude += other.magnitude;
+        self.normalized = false;
+        debug_assert!(self.verify());
+    }
+}
+
+impl From<FieldStorage> for Field {
+    fn from(a: FieldStorage) -> Field {
+        let mut r = Field::default();
+
+        let a0 = (a.0[0] as u64) | (a.0[1] as u64) << 32;
+        let a1 = (a.0[2] as u64) | (a.0[3] as u64) << 32;
+        let a2 = (a.0[4] as u64) | (a.0[5] as u64) << 32;
+        let a3 = (a.0[6] as u64) | (a.0[7] as u64) << 32;
+
+        r.n[0] = a0 & 0xFFFFFFFFFFFFF;
+        r.n[1] = a0 >> 52 | ((a1 << 12) & 0xFFFFFFFFFFFFF);
+        r.n[2] = a1 >> 40 | ((a2 << 24) & 0xFFFFFFFFFFFFF);
+        r.n[3] = a2 >> 28 | ((a3 << 36) & 0xFFFFFFFFFFFFF);
+        r.n[4] = a3 >> 16;
+
+        r.magnitude = 1;
+        r.normalized = true;
+
+        r
+    }
+}
+
+impl Into<FieldStorage> for Field {
+    fn into(self) -> FieldStorage {
+        debug_assert!(self.normalized);
+        let mut r = FieldStorage::default();
+
+        let r0 = self.n[0] | self.n[1] << 52;
+        let r1 = self.n[1] >> 12 | self.n[2] << 40;
+        let r2 = self.n[2] >> 24 | self.n[3] << 28;
+        let r3 = self.n[3] >> 36 | self.n[4] << 16;
+
+        r.0[0] = r0 as u32;
+        r.0[1] = (r0 >> 32) as u32;
+        r.0[2] = r1 as u32;
+        r.0[3] = (r1 >> 32) as u32;
+        r.0[4] = r2 as u32;
+        r.0[5] = (r2 >> 32) as u32;
+        r.0[6] = r3 as u32;
+        r.0[7] = (r3 >> 32) as u32;
+
+        r
+    }
+}
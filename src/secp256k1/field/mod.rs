@@ -0,0 +1,523 @@
+use std::ops::{ Add, AddAssign, Mul,  MulAssign };
+use std::cmp::Ordering;
+
+macro_rules! debug_assert_bits {
+    ($x:expr, $n:expr) => {
+        debug_assert!($x >> $n == 0);
+    };
+}
+
+macro_rules! field_const_raw {
+    (
+        $d9:expr,
+        $d8:expr,
+        $d7:expr,
+        $d6:expr,
+        $d5:expr,
+        $d4:expr,
+        $d3:expr,
+        $d2:expr,
+        $d1:expr,
+        $d0:expr
+    ) => {
+        $crate::secp256k1::field::Field::new_raw([$d0, $d1, $d2, $d3, $d4, $d5, $d6, $d7, $d8, $d9])
+    };
+}
+
+macro_rules! field_const {
+    ($d7:expr, $d6:expr, $d5:expr, $d4:expr, $d3:expr, $d2:expr, $d1:expr, $d0:expr) => {
+        $crate::secp256k1::field::Field::new($d7, $d6, $d5, $d4, $d3, $d2, $d1, $d0)
+    };
+}
+
+macro_rules! field_storage_const {
+    ($d7:expr, $d6:expr, $d5:expr, $d4:expr, $d3:expr, $d2:expr, $d1:expr, $d0:expr) => {
+        $crate::secp256k1::field::FieldStorage([$d0, $d1, $d2, $d3, $d4, $d5, $d6, $d7])
+    };
+}
+
+// The 5x52 representation needs 64x64->128 bit products and is used on
+// 64-bit targets unless the `force-32bit` feature is set. Tests build both
+// to cross-check them.
+#[cfg(any(test, not(all(target_pointer_width = "64", not(feature = "force-32bit")))))]
+#[cfg_attr(all(target_pointer_width = "64", not(feature = "force-32bit")), allow(dead_code))]
+mod field_10x26;
+#[cfg(any(test, all(target_pointer_width = "64", not(feature = "force-32bit"))))]
+#[cfg_attr(not(all(target_pointer_width = "64", not(feature = "force-32bit"))), allow(dead_code))]
+mod field_5x52;
+
+#[cfg(not(all(target_pointer_width = "64", not(feature = "force-32bit"))))]
+pub use self::field_10x26::Field;
+#[cfg(all(target_pointer_width = "64", not(feature = "force-32bit")))]
+pub use self::field_5x52::Field;
+
+impl Field {
+    pub fn sqr(&self) -> Field {
+        let mut ret = Field::default();
+        ret.sqr_in_place(self);
+        ret
+    }
+
+    /// If a has a square root, it is computed in r and 1 is
+    /// returned. If a does not have a square root, the root of its
+    /// negation is computed and 0 is returned. The input's magnitude
+    /// can be at most 8. The output magnitude is 1 (but not
+    /// guaranteed to be normalized). The result in r will always be a
+    /// square itself.
+    pub fn sqrt(&self) -> (Field, bool) {
+        let mut x2 = self.sqr();
+        x2 *= self;
+
+        let mut x3 = x2.sqr();
+        x3 *= self;
+
+        let mut x6 = x3.clone();
+        for _ in 0..3 {
+            x6 = x6.sqr();
+        }
+        x6 *= &x3;
+
+        let mut x9 = x6.clone();
+        for _ in 0..3 {
+            x9 = x9.sqr();
+        }
+        x9 *= &x3;
+
+        let mut x11 = x9.clone();
+        for _ in 0..2 {
+            x11 = x11.sqr();
+        }
+        x11 *= &x2;
+
+        let mut x22 = x11.clone();
+        for _ in 0..11 {
+            x22 = x22.sqr();
+        }
+        x22 *= &x11;
+
+        let mut x44 = x22.clone();
+        for _ in 0..22 {
+            x44 = x44.sqr();
+        }
+        x44 *= &x22;
+
+        let mut x88 = x44.clone();
+        for _ in 0..44 {
+            x88 = x88.sqr();
+        }
+        x88 *= &x44;
+
+        let mut x176 = x88.clone();
+        for _ in 0..88 {
+            x176 = x176.sqr();
+        }
+        x176 *= &x88;
+
+        let mut x220 = x176.clone();
+        for _ in 0..44 {
+            x220 = x220.sqr();
+        }
+        x220 *= &x44;
+
+        let mut x223 = x220.clone();
+        for _ in 0..3 {
+            x223 = x223.sqr();
+        }
+        x223 *= &x3;
+
+        let mut t1 = x223;
+        for _ in 0..23 {
+            t1 = t1.sqr();
+        }
+        t1 *= &x22;
+        for _ in 0..6 {
+            t1 = t1.sqr();
+        }
+        t1 *= &x2;
+        t1 = t1.sqr();
+        let r = t1.sqr();
+
+        t1 = r.sqr();
+        (r, &t1 == self)
+    }
+
+    /// Sets a field element to be the (modular) inverse of
+    /// another. Requires the input's magnitude to be at most 8. The
+    /// output magnitude is 1 (but not guaranteed to be normalized).
+    pub fn inv(&self) -> Field {
+        let mut x2 = self.sqr();
+        x2 *= self;
+
+        let mut x3 = x2.sqr();
+        x3 *= self;
+
+        let mut x6 = x3.clone();
+        for _ in 0..3 {
+            x6 = x6.sqr();
+        }
+        x6 *= &x3;
+
+        let mut x9 = x6.clone();
+        for _ in 0..3 {
+            x9 = x9.sqr();
+        }
+        x9 *= &x3;
+
+        let mut x11 = x9.clone();
+        for _ in 0..2 {
+            x11 = x11.sqr();
+        }
+        x11 *= &x2;
+
+        let mut x22 = x11.clone();
+        for _ in 0..11 {
+            x22 = x22.sqr();
+        }
+        x22 *= &x11;
+
+        let mut x44 = x22.clone();
+        for _ in 0..22 {
+            x44 = x44.sqr();
+        }
+        x44 *= &x22;
+
+        let mut x88 = x44.clone();
+        for _ in 0..44 {
+            x88 = x88.sqr();
+        }
+        x88 *= &x44;
+
+        let mut x176 = x88.clone();
+        for _ in 0..88 {
+            x176 = x176.sqr();
+        }
+        x176 *= &x88;
+
+        let mut x220 = x176.clone();
+        for _ in 0..44 {
+            x220 = x220.sqr();
+        }
+        x220 *= &x44;
+
+        let mut x223 = x220.clone();
+        for _ in 0..3 {
+            x223 = x223.sqr();
+        }
+        x223 *= &x3;
+
+        let mut t1 = x223.clone();
+        for _ in 0..23 {
+            t1 = t1.sqr();
+        }
+        t1 *= &x22;
+        for _ in 0..5 {
+            t1 = t1.sqr();
+        }
+        t1 *= self;
+        for _ in 0..3 {
+            t1 = t1.sqr();
+        }
+        t1 *= &x2;
+        for _ in 0..2 {
+            t1 = t1.sqr();
+        }
+        let r = self * &t1;
+        r
+    }
+
+    /// Potentially faster version of secp256k1_fe_inv, without
+    /// constant-time guarantee.
+    pub fn inv_var(&self) -> Field {
+        self.inv()
+    }
+
+    /// Checks whether a field element is a quadratic residue.
+    pub fn is_quad_var(&self) -> bool {
+        let (_, ret) = self.sqrt();
+        ret
+    }
+
+}
+
+impl Add<Field> for Field {
+    type Output = Field;
+    fn add(self, other: Field) -> Field {
+        let mut ret = self.clone();
+        ret.add_assign(&other);
+        ret
+    }
+}
+
+impl<'a, 'b> Add<&'a Field> for &'b Field {
+    type Output = Field;
+    fn add(self, other: &'a Field) -> Field {
+        let mut ret = self.clone();
+        ret.add_assign(other);
+        ret
+    }
+}
+
+impl AddAssign<Field> for Field {
+    fn add_assign(&mut self, other: Field) {
+        self.add_assign(&other)
+    }
+}
+
+impl Mul<Field> for Field {
+    type Output = Field;
+    fn mul(self, other: Field) -> Field {
+        let mut ret = Field::default();
+        ret.mul_in_place(&self, &other);
+        ret
+    }
+}
+
+impl<'a, 'b> Mul<&'a Field> for &'b Field {
+    type Output = Field;
+    fn mul(self, other: &'a Field) -> Field {
+        let mut ret = Field::default();
+        ret.mul_in_place(self, other);
+        ret
+    }
+}
+
+impl<'a> MulAssign<&'a Field> for Field {
+    fn mul_assign(&mut self, other: &'a Field) {
+        let mut ret = Field::default();
+        ret.mul_in_place(self, other);
+        *self = ret;
+    }
+}
+
+impl MulAssign<Field> for Field {
+    fn mul_assign(&mut self, other: Field) {
+        self.mul_assign(&other)
+    }
+}
+
+impl PartialEq for Field {
+    fn eq(&self, other: &Field) -> bool {
+        let mut na = self.neg(1);
+        na += other;
+        return na.normalizes_to_zero();
+    }
+}
+
+impl Eq for Field {}
+
+impl Ord for Field {
+    fn cmp(&self, other: &Field) -> Ordering {
+        self.cmp_var(other)
+    }
+}
+
+impl PartialOrd for Field {
+    fn partial_cmp(&self, other: &Field) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// Compact field element storage.
+pub struct FieldStorage(pub [u32; 8]);
+
+impl Default for FieldStorage {
+    fn default() -> FieldStorage {
+        FieldStorage([0; 8])
+    }
+}
+
+impl FieldStorage {
+    pub fn new(d7: u32, d6: u32, d5: u32, d4: u32, d3: u32, d2: u32, d1: u32, d0: u32) -> Self {
+        field_storage_const!(d7, d6, d5, d4, d3, d2, d1, d0)
+    }
+
+    pub fn cmov(&mut self, other: &FieldStorage, flag: bool) {
+        self.0[0] =
+            if flag { other.0[0] } else { self.0[0] };
+        self.0[1] =
+            if flag { other.0[1] } else { self.0[1] };
+        self.0[2] =
+            if flag { other.0[2] } else { self.0[2] };
+        self.0[3] =
+            if flag { other.0[3] } else { self.0[3] };
+        self.0[4] =
+            if flag { other.0[4] } else { self.0[4] };
+        self.0[5] =
+            if flag { other.0[5] } else { self.0[5] };
+        self.0[6] =
+            if flag { other.0[6] } else { self.0[6] };
+        self.0[7] =
+            if flag { other.0[7] } else { self.0[7] };
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::field_10x26::Field as Field10x26;
+    use super::field_5x52::Field as Field5x52;
+    use super::FieldStorage;
+    use rand::{thread_rng, Rng};
+
+    fn random_b32() -> [u8; 32] {
+        let mut b32 = [0u8; 32];
+        thread_rng().fill_bytes(&mut b32);
+        b32
+    }
+
+    fn pair(b32: &[u8; 32]) -> Option<(Field10x26, Field5x52)> {
+        let mut a = Field10x26::default();
+        let mut b = Field5x52::default();
+        let ok = a.set_b32(b32);
+        assert_eq!(ok, b.set_b32(b32));
+        if ok {
+            Some((a, b))
+        } else {
+            None
+        }
+    }
+
+    fn assert_same(a: &Field10x26, b: &Field5x52) {
+        let (mut a, mut b) = (*a, *b);
+        a.normalize();
+        b.normalize();
+        assert_eq!(a.b32(), b.b32());
+    }
+
+    /// 0, p - 1, p, p + 1 and 2^256 - 1, for the overflow paths.
+    fn edge_b32() -> Vec<[u8; 32]> {
+        let mut p = [0xffu8; 32];
+        p[27] = 0xfe;
+        p[30] = 0xfc;
+        p[31] = 0x2f;
+        let mut pm1 = p;
+        pm1[31] = 0x2e;
+        let mut pp1 = p;
+        pp1[31] = 0x30;
+        vec![[0u8; 32], pm1, p, pp1, [0xffu8; 32]]
+    }
+
+    #[test]
+    fn test_const() {
+        let a = field_const!(
+            0x79BE667E, 0xF9DCBBAC, 0x55A06295, 0xCE870B07, 0x029BFCDB, 0x2DCE28D9, 0x59F2815B, 0x16F81798
+        );
+        let b = Field10x26::new(
+            0x79BE667E, 0xF9DCBBAC, 0x55A06295, 0xCE870B07, 0x029BFCDB, 0x2DCE28D9, 0x59F2815B, 0x16F81798,
+        );
+        assert_eq!(a.b32(), b.b32());
+
+        let raw = [
+            45417712, 267186148, 38529388, 142222828, 262670128, 207667908, 43357028, 586608, 115291924,
+            13447464,
+        ];
+        assert_same(&Field10x26::new_raw(raw), &Field5x52::new_raw(raw));
+        let raw = [0x3ffffff; 10];
+        assert_same(&Field10x26::new_raw(raw), &Field5x52::new_raw(raw));
+    }
+
+    #[test]
+    fn test_set_b32() {
+        let edges = edge_b32();
+        for b32 in edges[..2].iter() {
+            let (a, b) = pair(b32).unwrap();
+            assert_eq!(a.b32(), *b32);
+            assert_eq!(b.b32(), *b32);
+        }
+        for b32 in edges[2..].iter() {
+            assert!(pair(b32).is_none());
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        for _ in 0..256 {
+            let (x1, x2) = pair(&random_b32()).unwrap();
+            let (y1, y2) = pair(&random_b32()).unwrap();
+
+            let mut r1 = Field10x26::default();
+            let mut r2 = Field5x52::default();
+            r1.mul_in_place(&x1, &y1);
+            r2.mul_in_place(&x2, &y2);
+            assert_same(&r1, &r2);
+
+            r1.sqr_in_place(&x1);
+            r2.sqr_in_place(&x2);
+            assert_same(&r1, &r2);
+
+            let (mut s1, mut s2) = (x1, x2);
+            s1 += &y1;
+            s2 += &y2;
+            assert_same(&s1, &s2);
+            s1.mul_int(3);
+            s2.mul_int(3);
+            assert_same(&s1, &s2);
+            r1.mul_in_place(&s1, &y1);
+            r2.mul_in_place(&s2, &y2);
+            assert_same(&r1, &r2);
+
+            let (n1, n2) = (x1.neg(1), x2.neg(1));
+            assert_same(&n1, &n2);
+            assert_eq!(x1.eq_var(&y1), x2.eq_var(&y2));
+            let (mut z1, mut z2) = (n1, n2);
+            z1 += &x1;
+            z2 += &x2;
+            assert!(z1.normalizes_to_zero() && z2.normalizes_to_zero());
+            assert!(z1.normalizes_to_zero_var() && z2.normalizes_to_zero_var());
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let edges = edge_b32();
+        let mut values = vec![edges[0], edges[1]];
+        values.extend((0..8).map(|_| random_b32()));
+        for b32 in values.iter() {
+            let (x1, x2) = pair(b32).unwrap();
+            for m in 1..9 {
+                let (mut a1, mut a2) = (x1, x2);
+                a1.mul_int(m);
+                a2.mul_int(m);
+                a1 += &Field10x26::from_int(1);
+                a2 += &Field5x52::from_int(1);
+                let (mut w1, mut w2) = (a1, a2);
+                w1.normalize_weak();
+                w2.normalize_weak();
+                assert_same(&w1, &w2);
+                let (mut v1, mut v2) = (a1, a2);
+                v1.normalize_var();
+                v2.normalize_var();
+                assert_eq!(v1.b32(), v2.b32());
+                a1.normalize();
+                a2.normalize();
+                assert_eq!(a1.b32(), v2.b32());
+                assert_eq!(a1.is_odd(), a2.is_odd());
+                assert_eq!(a1.is_zero(), a2.is_zero());
+            }
+        }
+    }
+
+    #[test]
+    fn test_storage_cmov() {
+        for _ in 0..64 {
+            let b32 = random_b32();
+            let (x1, x2) = pair(&b32).unwrap();
+            let s1: FieldStorage = x1.into();
+            let s2: FieldStorage = x2.into();
+            assert_eq!(s1, s2);
+            assert_eq!(Field10x26::from(s1).b32(), b32);
+            assert_eq!(Field5x52::from(s2).b32(), b32);
+
+            let (y1, y2) = pair(&random_b32()).unwrap();
+            let (mut r1, mut r2) = (x1, x2);
+            r1.cmov(&y1, false);
+            r2.cmov(&y2, false);
+            assert_eq!(r1.b32(), r2.b32());
+            r1.cmov(&y1, true);
+            r2.cmov(&y2, true);
+            assert_eq!(r1.b32(), r2.b32());
+            assert_eq!(r2.b32(), y2.b32());
+        }
+    }
+}
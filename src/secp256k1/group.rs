@@ -113,6 +113,12 @@ pub static AFFINE_G: Affine = affine_const!(
 
 pub const CURVE_B: u32 = 7;
 
+/// The cube root of unity `beta` such that `lambda.(x, y) = (beta.x, y)`.
+const BETA: Field = field_const!(
+    0x7AE96A2B, 0x657C0710, 0x6E64479E, 0xAC3434E9, 0x9CF04975, 0x12F58995, 0xC1396C28,
+    0x719501EE
+);
+
 impl Affine {
     /// Set a group element equal to the point with given X and Y
     /// coordinates.
@@ -185,6 +191,14 @@ impl Affine {
         ret
     }
 
+    /// Multiply a group element by `lambda` with the endomorphism of
+    /// secp256k1, that is, multiply its x coordinate by `beta`.
+    pub fn mul_lambda(&self) -> Affine {
+        let mut ret = *self;
+        ret.x *= &BETA;
+        ret
+    }
+
     /// Set a group element equal to another which is given in
     /// jacobian coordinates.
     pub fn set_gej(&mut self, a: &Jacobian) {
@@ -18,6 +18,11 @@ pub struct PublicKey(pub(crate) Affine);
 /// Secret key (256-bit) on a secp256k1 curve.
 pub struct SecretKey(pub(crate) Scalar);
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// X-only public key on a secp256k1 curve, as used by BIP-340 and Taproot. The point always has an even
+/// y-coordinate.
+pub struct XOnlyPublicKey(pub(crate) Affine);
+
 impl PublicKey {
     /// Create a public key from a private key by performing P = k.G
     pub fn from_secret_key(seckey: &SecretKey) -> PublicKey {
@@ -150,6 +155,75 @@ impl PublicKey {
 
         ret
     }
+
+    /// Drop the y-coordinate of the public key, returning the x-only key together with the parity of the dropped
+    /// y-coordinate.
+    pub fn x_only(&self) -> (XOnlyPublicKey, Parity) {
+        let mut elem = self.0;
+        elem.x.normalize_var();
+        elem.y.normalize_var();
+        let parity = Parity::from_odd(elem.y.is_odd());
+        if parity == Parity::Odd {
+            elem.y = elem.y.neg(1);
+            elem.y.normalize_var();
+        }
+        (XOnlyPublicKey(elem), parity)
+    }
+
+    /// Tweak the public key by adding t.G, where t is the 32-byte tweak. Fails if the tweak is not below the group
+    /// order or the result is infinity, in which case the key is left unchanged.
+    pub fn tweak_add_assign(&mut self, tweak: &[u8; 32]) -> Result<(), Error> {
+        let mut t = Scalar::default();
+        if t.set_b32(tweak) {
+            return Err(Error::InvalidTweak);
+        }
+        let mut one = Scalar::default();
+        one.set_int(1);
+        let mut pj = Jacobian::default();
+        pj.set_ge(&self.0);
+        let mut qj = Jacobian::default();
+        ECMULT_CONTEXT.ecmult(&mut qj, &pj, &one, &t);
+        let mut q = Affine::default();
+        q.set_gej_var(&qj);
+        if q.is_infinity() {
+            return Err(Error::InvalidTweak);
+        }
+        self.0 = q;
+        Ok(())
+    }
+
+    /// Tweak the public key by multiplying it with the 32-byte tweak. Fails if the tweak is zero or not below the
+    /// group order, in which case the key is left unchanged.
+    pub fn tweak_mul_assign(&mut self, tweak: &[u8; 32]) -> Result<(), Error> {
+        let mut t = Scalar::default();
+        if t.set_b32(tweak) || t.is_zero() {
+            return Err(Error::InvalidTweak);
+        }
+        let mut pj = Jacobian::default();
+        ECMULT_CONTEXT.ecmult_const(&mut pj, &self.0, &t);
+        self.0.set_gej(&pj);
+        Ok(())
+    }
+
+    /// Negate the public key in place.
+    pub fn negate(&mut self) {
+        self.0 = self.0.neg();
+    }
+
+    /// Compute the sum of the given public keys. Fails if the slice is empty or the sum is infinity.
+    pub fn combine(keys: &[PublicKey]) -> Result<PublicKey, Error> {
+        let mut qj = Jacobian::default();
+        qj.set_infinity();
+        for key in keys {
+            qj = qj.add_ge_var(&key.0, None);
+        }
+        if qj.is_infinity() {
+            return Err(Error::InvalidPublicKey);
+        }
+        let mut q = Affine::default();
+        q.set_gej_var(&qj);
+        Ok(PublicKey(q))
+    }
 }
 
 impl Display for PublicKey {
@@ -187,6 +261,98 @@ impl Sub for PublicKey {
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// Parity of the y-coordinate of a curve point.
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    pub fn from_odd(odd: bool) -> Parity {
+        if odd {
+            Parity::Odd
+        } else {
+            Parity::Even
+        }
+    }
+
+    pub fn is_odd(&self) -> bool {
+        *self == Parity::Odd
+    }
+}
+
+impl XOnlyPublicKey {
+    /// Create an x-only public key from a 32-byte x-coordinate. The point is lifted to the one with an even
+    /// y-coordinate, as BIP-340 requires.
+    pub fn parse(p: &[u8; 32]) -> Result<XOnlyPublicKey, Error> {
+        let mut x = Field::default();
+        if !x.set_b32(p) {
+            return Err(Error::InvalidPublicKey);
+        }
+        let mut elem = Affine::default();
+        if !elem.set_xo_var(&x, false) {
+            return Err(Error::InvalidPublicKey);
+        }
+        elem.y.normalize();
+        Ok(XOnlyPublicKey(elem))
+    }
+
+    pub fn from_hex(h: &str) -> Result<XOnlyPublicKey, Error> {
+        let data = hex::decode(h).or(Err(Error::InvalidHex))?;
+        match data.len() {
+            32 => XOnlyPublicKey::parse(array_ref!(data, 0, 32)),
+            _ => Err(Error::InvalidPublicKey),
+        }
+    }
+
+    /// Return the 32-byte x-coordinate of the key.
+    pub fn serialize(&self) -> [u8; 32] {
+        let mut x = self.0.x;
+        x.normalize_var();
+        x.b32()
+    }
+
+    /// Return the hexadecimal representation of the x-only public key
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.serialize())
+    }
+
+    /// Compute Q = P + t.G, where P is this key and t is the 32-byte tweak. Returns the x-only form of Q together
+    /// with the parity of its y-coordinate. Fails if the tweak is not below the group order or Q is infinity.
+    pub fn tweak_add(&self, tweak: &[u8; 32]) -> Result<(XOnlyPublicKey, Parity), Error> {
+        let mut q = PublicKey(self.0);
+        q.tweak_add_assign(tweak)?;
+        Ok(q.x_only())
+    }
+
+    /// Check that `tweaked` with the given parity is the result of tweaking this key with `tweak`.
+    pub fn tweak_add_check(&self, tweaked: &XOnlyPublicKey, parity: Parity, tweak: &[u8; 32]) -> bool {
+        match self.tweak_add(tweak) {
+            Ok((q, p)) => q == *tweaked && p == parity,
+            Err(_) => false,
+        }
+    }
+}
+
+impl From<PublicKey> for XOnlyPublicKey {
+    fn from(p: PublicKey) -> XOnlyPublicKey {
+        p.x_only().0
+    }
+}
+
+impl From<XOnlyPublicKey> for PublicKey {
+    fn from(p: XOnlyPublicKey) -> PublicKey {
+        PublicKey(p.0)
+    }
+}
+
+impl Display for XOnlyPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str(&self.to_hex())
+    }
+}
+
 impl SecretKey {
     /// Read a 32-byte array into a Secret key
     pub fn parse(p: &[u8; 32]) -> Result<SecretKey, Error> {
@@ -241,6 +407,37 @@ impl SecretKey {
     pub fn inv(&self) -> SecretKey {
         SecretKey(self.0.inv())
     }
+
+    /// Tweak the secret key by adding the 32-byte tweak modulo the group order. Fails if the tweak is not below the
+    /// group order or the result is zero, in which case the key is left unchanged.
+    pub fn tweak_add_assign(&mut self, tweak: &[u8; 32]) -> Result<(), Error> {
+        let mut t = Scalar::default();
+        if t.set_b32(tweak) {
+            return Err(Error::InvalidTweak);
+        }
+        let sum = self.0 + t;
+        if sum.is_zero() {
+            return Err(Error::InvalidTweak);
+        }
+        self.0 = sum;
+        Ok(())
+    }
+
+    /// Tweak the secret key by multiplying it with the 32-byte tweak modulo the group order. Fails if the tweak is
+    /// zero or not below the group order, in which case the key is left unchanged.
+    pub fn tweak_mul_assign(&mut self, tweak: &[u8; 32]) -> Result<(), Error> {
+        let mut t = Scalar::default();
+        if t.set_b32(tweak) || t.is_zero() {
+            return Err(Error::InvalidTweak);
+        }
+        self.0 *= t;
+        Ok(())
+    }
+
+    /// Negate the secret key in place. The negation of a valid secret key is always valid.
+    pub fn negate(&mut self) {
+        self.0 = -self.0;
+    }
 }
 
 impl Display for SecretKey {
@@ -302,7 +499,7 @@ impl Neg for SecretKey {
 #[cfg(test)]
 mod tests {
     use secp256k1::rand::thread_rng;
-    use {Error, PublicKey, SecretKey};
+    use {Error, Parity, PublicKey, SecretKey, XOnlyPublicKey};
     use secp256k1::Scalar;
 
     #[test]
@@ -449,4 +646,138 @@ mod tests {
         let k = small(3);
         assert_eq!(p1 + p1 + p1, k * p1);
     }
+
+    #[test]
+    fn x_only_parse_serialize() {
+        let hex = "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659";
+        let key = XOnlyPublicKey::from_hex(hex).unwrap();
+        assert_eq!(&key.to_hex(), hex);
+        assert_eq!(XOnlyPublicKey::parse(&key.serialize()).unwrap(), key);
+        let full = PublicKey::from(key);
+        assert_eq!(&full.to_hex(true)[..2], "02");
+
+        // Not on the curve
+        let key = XOnlyPublicKey::from_hex("eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34");
+        assert_eq!(key.err().unwrap(), Error::InvalidPublicKey);
+        // Exceeds the field size
+        let key = XOnlyPublicKey::from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30");
+        assert_eq!(key.err().unwrap(), Error::InvalidPublicKey);
+    }
+
+    #[test]
+    fn x_only_from_public_key() {
+        let even = PublicKey::from_hex("0241cc121c419921942add6db6482fb36243faf83317c866d2a28d8c6d7089f7ba").unwrap();
+        let (x, parity) = even.x_only();
+        assert_eq!(parity, Parity::Even);
+        assert_eq!(&x.to_hex(), "41cc121c419921942add6db6482fb36243faf83317c866d2a28d8c6d7089f7ba");
+        assert_eq!(PublicKey::from(x), even);
+
+        let odd = PublicKey::from_hex("0384526253c27c7aef56c7b71a5cd25bebb66dddda437826defc5b2568bde81f07").unwrap();
+        let (x, parity) = odd.x_only();
+        assert_eq!(parity, Parity::Odd);
+        assert_eq!(&x.to_hex(), "84526253c27c7aef56c7b71a5cd25bebb66dddda437826defc5b2568bde81f07");
+        assert_eq!(PublicKey::from(x), PublicKey(odd.0.neg()));
+    }
+
+    #[test]
+    fn x_only_tweak_add() {
+        let k = SecretKey::random(&mut thread_rng());
+        let t = SecretKey::random(&mut thread_rng());
+        let (p, parity) = PublicKey::from_secret_key(&k).x_only();
+        // The x-only key stands for the even-y point, i.e. for the negated secret key if the parity was odd.
+        let k = if parity.is_odd() { -k } else { k };
+        let (q, q_parity) = p.tweak_add(&t.serialize()).unwrap();
+        let expected = PublicKey::from_secret_key(&(k + t));
+        assert_eq!(expected.x_only(), (q, q_parity));
+        assert!(p.tweak_add_check(&q, q_parity, &t.serialize()));
+        assert!(!p.tweak_add_check(&q, Parity::from_odd(!q_parity.is_odd()), &t.serialize()));
+
+        // Tweak at or above the group order
+        assert_eq!(p.tweak_add(&ORDER).err().unwrap(), Error::InvalidTweak);
+    }
+
+    const ORDER: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+        0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+    ];
+
+    #[test]
+    fn tweak_add_keys() {
+        let mut k = small(1);
+        let mut p = PublicKey::from_secret_key(&k);
+        k.tweak_add_assign(&small(1).serialize()).unwrap();
+        p.tweak_add_assign(&small(1).serialize()).unwrap();
+        assert_eq!(k, small(2));
+        assert_eq!(&p.to_hex(true), "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5");
+
+        let mut k = SecretKey::random(&mut thread_rng());
+        let mut p = PublicKey::from_secret_key(&k);
+        let t = SecretKey::random(&mut thread_rng()).serialize();
+        k.tweak_add_assign(&t).unwrap();
+        p.tweak_add_assign(&t).unwrap();
+        assert_eq!(p, PublicKey::from_secret_key(&k));
+
+        // A zero tweak is allowed and leaves the key unchanged.
+        let before = k;
+        k.tweak_add_assign(&[0u8; 32]).unwrap();
+        assert_eq!(k, before);
+
+        // Out of range tweaks and zero results fail and leave the key unchanged.
+        assert_eq!(k.tweak_add_assign(&ORDER), Err(Error::InvalidTweak));
+        assert_eq!(p.tweak_add_assign(&ORDER), Err(Error::InvalidTweak));
+        let neg = (-k).serialize();
+        assert_eq!(k.tweak_add_assign(&neg), Err(Error::InvalidTweak));
+        assert_eq!(p.tweak_add_assign(&neg), Err(Error::InvalidTweak));
+        assert_eq!(k, before);
+        assert_eq!(p, PublicKey::from_secret_key(&before));
+    }
+
+    #[test]
+    fn tweak_mul_keys() {
+        let mut k = SecretKey::random(&mut thread_rng());
+        let mut p = PublicKey::from_secret_key(&k);
+        let t = SecretKey::random(&mut thread_rng());
+        k.tweak_mul_assign(&t.serialize()).unwrap();
+        p.tweak_mul_assign(&t.serialize()).unwrap();
+        assert_eq!(p, PublicKey::from_secret_key(&k));
+
+        let before = k;
+        assert_eq!(k.tweak_mul_assign(&[0u8; 32]), Err(Error::InvalidTweak));
+        assert_eq!(p.tweak_mul_assign(&[0u8; 32]), Err(Error::InvalidTweak));
+        assert_eq!(k.tweak_mul_assign(&ORDER), Err(Error::InvalidTweak));
+        assert_eq!(p.tweak_mul_assign(&ORDER), Err(Error::InvalidTweak));
+        assert_eq!(k, before);
+        assert_eq!(p, PublicKey::from_secret_key(&before));
+    }
+
+    #[test]
+    fn negate_keys() {
+        let mut k = SecretKey::random(&mut thread_rng());
+        let mut p = PublicKey::from_secret_key(&k);
+        let original = p;
+        k.negate();
+        p.negate();
+        assert_eq!(p, PublicKey::from_secret_key(&k));
+        assert_ne!(p, original);
+        assert_eq!(p.serialize_compressed()[1..], original.serialize_compressed()[1..]);
+        p.negate();
+        assert_eq!(p, original);
+    }
+
+    #[test]
+    fn combine_public_keys() {
+        let p1 =
+            PublicKey::from_hex("0241cc121c419921942add6db6482fb36243faf83317c866d2a28d8c6d7089f7ba").unwrap();
+        let p2 =
+            PublicKey::from_hex("02e6642fd69bd211f93f7f1f36ca51a26a5290eb2dd1b0d8279a87bb0d480c8443").unwrap();
+        assert_eq!(PublicKey::combine(&[p1]).unwrap(), p1);
+        assert_eq!(PublicKey::combine(&[p1, p2]).unwrap(), p1 + p2);
+        assert_eq!(PublicKey::combine(&[p1, p1, p1]).unwrap(), small(3) * p1);
+
+        let mut neg = p2;
+        neg.negate();
+        assert_eq!(PublicKey::combine(&[p1, p2, neg]).unwrap(), p1);
+        assert_eq!(PublicKey::combine(&[p2, neg]), Err(Error::InvalidPublicKey));
+        assert_eq!(PublicKey::combine(&[]), Err(Error::InvalidPublicKey));
+    }
 }
@@ -1,12 +1,12 @@
 use ecmult::ECMULT_GEN_CONTEXT;
-use secp256k1::hmac_drbg::HmacDRBG;
 use super::sha2::{Digest, Sha256};
-use secp256k1::typenum::U32;
+use secp256k1::nonce::{NonceFunction, Rfc6979};
 use secp256k1::scalar::Scalar;
 use secp256k1::error::Error;
 use secp256k1::keys::{ SecretKey};
 use Signature;
 use secp256k1::recovery_id::RecoveryId;
+use secp256k1::recoverable_signature::RecoverableSignature;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 /// Hashed message input to an ECDSA signature.
@@ -30,30 +30,61 @@ impl Message {
         self.0.b32()
     }
 
-    /// Sign a message using the secret key.
+    /// Sign a message using the secret key, with the RFC 6979 nonce.
     pub fn sign(message: &Message, seckey: &SecretKey) -> Result<(Signature, RecoveryId), Error> {
+        Message::sign_with_nonce_fn(message, seckey, &Rfc6979::new())
+    }
+
+    /// Sign a message using the secret key, returning a signature that
+    /// carries its recovery id.
+    pub fn sign_recoverable(
+        message: &Message,
+        seckey: &SecretKey,
+    ) -> Result<RecoverableSignature, Error> {
+        Message::sign(message, seckey).map(RecoverableSignature::from)
+    }
+
+    /// Sign a message using the secret key, mixing 32 bytes of auxiliary
+    /// randomness into the RFC 6979 nonce as extra data. This protects
+    /// against fault attacks on deterministic signing.
+    pub fn sign_with_aux_rand(
+        message: &Message,
+        seckey: &SecretKey,
+        aux_rand: &[u8; 32],
+    ) -> Result<(Signature, RecoveryId), Error> {
+        Message::sign_with_nonce_fn(message, seckey, &Rfc6979::with_extra_data(aux_rand))
+    }
+
+    /// Sign a message using the secret key and a custom nonce function.
+    /// The nonce function is called with an increasing counter until it
+    /// returns a nonce that yields a valid signature.
+    pub fn sign_with_nonce_fn<N: NonceFunction>(
+        message: &Message,
+        seckey: &SecretKey,
+        nonce_fn: &N,
+    ) -> Result<(Signature, RecoveryId), Error> {
         let seckey_b32 = seckey.0.b32();
         let message_b32 = message.0.b32();
 
-        let mut drbg = HmacDRBG::<Sha256>::new(&seckey_b32, &message_b32, &[]);
-        let generated = drbg.generate::<U32>(None);
-        let mut nonce = Scalar::default();
-        let mut overflow = nonce.set_b32(array_ref!(generated, 0, 32));
+        let mut counter = 0;
+        loop {
+            let generated = match nonce_fn.nonce(&message_b32, &seckey_b32, counter) {
+                Some(generated) => generated,
+                None => return Err(Error::InvalidMessage),
+            };
+            counter += 1;
 
-        while overflow || nonce.is_zero() {
-            let generated = drbg.generate::<U32>(None);
-            overflow = nonce.set_b32(array_ref!(generated, 0, 32));
-        }
+            let mut nonce = Scalar::default();
+            let overflow = nonce.set_b32(&generated);
+            if overflow || nonce.is_zero() {
+                continue;
+            }
 
-        let result = ECMULT_GEN_CONTEXT.sign_raw(&seckey.0, &message.0, &nonce);
-        #[allow(unused_assignments)]
-        {
-            nonce = Scalar::default();
-        }
-        if let Ok((sigr, sigs, recid)) = result {
-            return Ok((Signature { r: sigr, s: sigs }, RecoveryId(recid)));
-        } else {
-            return Err(result.err().unwrap());
+            let result = ECMULT_GEN_CONTEXT.sign_raw(&seckey.0, &message.0, &nonce);
+            nonce.clear();
+            if let Ok((sigr, sigs, recid)) = result {
+                return Ok((Signature { r: sigr, s: sigs }, RecoveryId(recid)));
+            }
         }
     }
 }
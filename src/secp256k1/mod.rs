@@ -14,20 +14,26 @@ pub mod field;
 #[macro_use]
 pub mod group;
 
+pub(crate) mod der;
 mod ecdh;
 mod ecdsa;
 mod error;
 mod keys;
 mod message;
+mod nonce;
+mod recoverable_signature;
 mod recovery_id;
+#[macro_use]
 mod scalar;
 pub mod signature;
 pub mod util;
 
 pub use self::ecdh::SharedSecret;
 pub use self::error::Error;
-pub use self::keys::{PublicKey, SecretKey};
+pub use self::keys::{Parity, PublicKey, SecretKey, XOnlyPublicKey};
 pub use self::message::Message;
+pub use self::nonce::{NonceFunction, Rfc6979};
+pub use self::recoverable_signature::RecoverableSignature;
 pub use self::recovery_id::RecoveryId;
 pub use self::signature::Signature;
 pub use self::scalar::Scalar;
@@ -0,0 +1,276 @@
+use secp256k1::hmac_drbg::HmacDRBG;
+use secp256k1::sha2::Sha256;
+use secp256k1::typenum::U32;
+use secp256k1::scalar::Scalar;
+
+/// A function deterministically generating the nonce used when signing.
+pub trait NonceFunction {
+    /// Generate a 32-byte nonce for the given message hash and secret
+    /// key. `counter` is the number of previous attempts that resulted in
+    /// an unusable nonce; different counters must give different nonces.
+    /// Returning `None` causes signing to fail.
+    fn nonce(&self, message: &[u8; 32], seckey: &[u8; 32], counter: u32) -> Option<[u8; 32]>;
+}
+
+#[derive(Debug, Clone, Default)]
+/// RFC 6979 nonce generation with HMAC-SHA256, byte compatible with
+/// libsecp256k1's `nonce_function_rfc6979`. Optional extra data is mixed
+/// in as described in RFC 6979 section 3.6.
+pub struct Rfc6979 {
+    extra_data: Option<[u8; 32]>,
+}
+
+impl Rfc6979 {
+    /// RFC 6979 nonce generation without extra data.
+    pub fn new() -> Rfc6979 {
+        Rfc6979 { extra_data: None }
+    }
+
+    /// RFC 6979 nonce generation with 32 bytes of extra data, such as
+    /// additional randomness.
+    pub fn with_extra_data(extra_data: &[u8; 32]) -> Rfc6979 {
+        Rfc6979 {
+            extra_data: Some(*extra_data),
+        }
+    }
+}
+
+impl NonceFunction for Rfc6979 {
+    fn nonce(&self, message: &[u8; 32], seckey: &[u8; 32], counter: u32) -> Option<[u8; 32]> {
+        let extra_data: &[u8] = match self.extra_data {
+            Some(ref data) => data,
+            None => &[],
+        };
+
+        // RFC 6979 section 3.2d uses the message reduced modulo the order.
+        let mut reduced = Scalar::default();
+        reduced.set_b32(message);
+        let message = reduced.b32();
+
+        // The DRBG is seeded with seckey || message || extra data, and each
+        // retry generates one more block, as in libsecp256k1.
+        let mut drbg = HmacDRBG::<Sha256>::new(seckey, &message, extra_data);
+        let mut nonce = [0u8; 32];
+        for _ in 0..=counter {
+            let generated = drbg.generate::<U32>(None);
+            nonce.copy_from_slice(&generated);
+        }
+        Some(nonce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+    use std::os::raw::c_void;
+    use std::ptr;
+    use hex;
+    use secp256k1::rand::{thread_rng, Rng};
+    use secp256k1::sha2::{Digest, Sha256};
+    use secp256k1_test::ffi;
+    use secp256k1_test::{Message as SecpMessage, Secp256k1};
+    use secp256k1_test::key::SecretKey as SecpSecretKey;
+    use super::{NonceFunction, Rfc6979, Scalar};
+    use {Message, PublicKey, SecretKey, Signature};
+
+    // Deterministic ECDSA vectors for secp256k1 with SHA-256 message
+    // hashing: secret key, message, nonce, and low-S signature `r || s`.
+    const VECTORS: &[(&str, &str, &str, &str)] = &[
+        (
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "Satoshi Nakamoto",
+            "8f8a276c19f4149656b280621e358cce24f5f52542772691ee69063b74f15d15",
+            "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d82442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "All those moments will be lost in time, like tears in rain. Time to die...",
+            "38aa22d72376b4dbc472e06c3ba403ee0a394da63fc58d88686c611aba98d6b3",
+            "8600dbd41e348fe5c9465ab92d23e3db8b98b873beecd930736488696438cb6b547fe64427496db33bf66019dacbf0039c04199abb0122918601db38a72cfc21",
+        ),
+        (
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140",
+            "Satoshi Nakamoto",
+            "33a19b60e25fb6f4435af53a3d42d493644827367e6453928554f43e49aa6f90",
+            "fd567d121db66e382991534ada77a6bd3106f0a1098c231e47993447cd6af2d06b39cd0eb1bc8603e159ef5c20a5c8ad685a45b06ce9bebed3f153d10d93bed5",
+        ),
+        (
+            "f8b8af8ce3c7cca5e300d33939540c10d45ce001b8f252bfbc57ba0342904181",
+            "Alan Turing",
+            "525a82b70e67874398067543fd84c83d30c175fdc45fdeee082fe13b1d7cfdf1",
+            "7063ae83e7f62bbb171798131b4a0564b956930092b33b07b395615d9ec7e15c58dfcc1e00a35e1572f366ffe34ba0fc47db1e7189759b9fb233c5b05ab388ea",
+        ),
+        (
+            "e91671c46231f833a6406ccbea0e3e392c76c167bac1cb013f6f1013980455c2",
+            "There is a computer disease that anybody who works with computers knows about. It's a very serious disease and it interferes completely with the work. The trouble with computers is that you 'play' with them!",
+            "1f4b84c23a86a221d233f2521be018d9318639d5b8bbd6374a8a59232d16ad3d",
+            "b552edd27580141f3b2a5463048cb7cd3e047b97c9f98076c32dbdf85a68718b279fa72dd19bfae05577e06c7c0c1900c371fcd5893f7e1d56a37d30174671f6",
+        ),
+    ];
+
+    fn decode32(s: &str) -> [u8; 32] {
+        let mut ret = [0u8; 32];
+        ret.copy_from_slice(&hex::decode(s).unwrap());
+        ret
+    }
+
+    // The binding in secp256k1-test predates the current argument order, so
+    // call the C function through its actual signature.
+    type RawNonceFn =
+        unsafe extern "C" fn(*mut u8, *const u8, *const u8, *const u8, *const c_void, u32) -> i32;
+
+    fn libsecp256k1_nonce(
+        message: &[u8; 32],
+        seckey: &[u8; 32],
+        data: Option<&[u8; 32]>,
+        counter: u32,
+    ) -> [u8; 32] {
+        let mut nonce = [0u8; 32];
+        let data = data.map(|d| d.as_ptr() as *const c_void).unwrap_or(ptr::null());
+        let ret = unsafe {
+            let nonce_fn: RawNonceFn = mem::transmute(ffi::secp256k1_nonce_function_rfc6979);
+            nonce_fn(
+                nonce.as_mut_ptr(),
+                message.as_ptr(),
+                seckey.as_ptr(),
+                ptr::null(),
+                data,
+                counter,
+            )
+        };
+        assert_eq!(ret, 1);
+        nonce
+    }
+
+    #[test]
+    fn rfc6979_vectors() {
+        for &(seckey, message, nonce, signature) in VECTORS {
+            let seckey = SecretKey::parse(&decode32(seckey)).unwrap();
+            let hash = Sha256::digest(message.as_bytes());
+            let message = Message::parse(array_ref!(hash, 0, 32));
+
+            let generated = Rfc6979::new()
+                .nonce(&message.serialize(), &seckey.serialize(), 0)
+                .unwrap();
+            assert_eq!(hex::encode(generated), nonce);
+
+            let (sig, _) = Message::sign(&message, &seckey).unwrap();
+            assert_eq!(hex::encode(&sig.serialize()[..]), signature);
+            assert!(Signature::verify(&message, &sig, &PublicKey::from_secret_key(&seckey)));
+        }
+    }
+
+    #[test]
+    fn rfc6979_against_libsecp256k1() {
+        let mut rng = thread_rng();
+
+        for _ in 0..32 {
+            let message: [u8; 32] = rng.gen();
+            let seckey: [u8; 32] = rng.gen();
+            let extra_data: [u8; 32] = rng.gen();
+
+            for counter in 0..4 {
+                assert_eq!(
+                    Rfc6979::new().nonce(&message, &seckey, counter).unwrap(),
+                    libsecp256k1_nonce(&message, &seckey, None, counter)
+                );
+                assert_eq!(
+                    Rfc6979::with_extra_data(&extra_data).nonce(&message, &seckey, counter).unwrap(),
+                    libsecp256k1_nonce(&message, &seckey, Some(&extra_data), counter)
+                );
+            }
+        }
+
+        // Messages at or above the group order are reduced first. The C
+        // library bundled with secp256k1-test predates this, so compare
+        // against it with the reduced message.
+        let seckey = [1u8; 32];
+        let message = [0xff; 32];
+        let mut reduced = Scalar::default();
+        reduced.set_b32(&message);
+        assert_eq!(
+            Rfc6979::new().nonce(&message, &seckey, 0).unwrap(),
+            libsecp256k1_nonce(&reduced.b32(), &seckey, None, 0)
+        );
+    }
+
+    #[test]
+    fn sign_against_libsecp256k1() {
+        let secp256k1 = Secp256k1::new();
+        let mut rng = thread_rng();
+
+        for _ in 0..16 {
+            let message_arr: [u8; 32] = rng.gen();
+            let seckey_arr: [u8; 32] = rng.gen();
+            let seckey = match SecretKey::parse(&seckey_arr) {
+                Ok(seckey) => seckey,
+                Err(_) => continue,
+            };
+            let message = Message::parse(&message_arr);
+
+            let secp_seckey = SecpSecretKey::from_slice(&secp256k1, &seckey_arr).unwrap();
+            let secp_message = SecpMessage::from_slice(&message_arr).unwrap();
+            let secp_sig = secp256k1.sign(&secp_message, &secp_seckey).unwrap();
+
+            let (sig, _) = Message::sign(&message, &seckey).unwrap();
+            let compact: &[u8] = &secp_sig.serialize_compact(&secp256k1);
+            let ours: &[u8] = &sig.serialize();
+            assert_eq!(ours, compact);
+        }
+    }
+
+    #[test]
+    fn sign_with_aux_rand() {
+        let seckey = SecretKey::parse(&[0x42; 32]).unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        let message = Message::parse(&[6u8; 32]);
+
+        let (plain, _) = Message::sign(&message, &seckey).unwrap();
+        let (first, _) = Message::sign_with_aux_rand(&message, &seckey, &[1u8; 32]).unwrap();
+        let (second, _) = Message::sign_with_aux_rand(&message, &seckey, &[2u8; 32]).unwrap();
+        assert!(plain != first && first != second);
+        assert!(Signature::verify(&message, &first, &pubkey));
+        assert!(Signature::verify(&message, &second, &pubkey));
+
+        let (again, _) = Message::sign_with_aux_rand(&message, &seckey, &[1u8; 32]).unwrap();
+        assert_eq!(first, again);
+    }
+
+    struct Retrying;
+
+    impl NonceFunction for Retrying {
+        fn nonce(&self, _: &[u8; 32], _: &[u8; 32], counter: u32) -> Option<[u8; 32]> {
+            match counter {
+                // Overflowing and zero nonces are skipped.
+                0 => Some([0xff; 32]),
+                1 => Some([0u8; 32]),
+                2 => Some([3u8; 32]),
+                _ => None,
+            }
+        }
+    }
+
+    struct Failing;
+
+    impl NonceFunction for Failing {
+        fn nonce(&self, _: &[u8; 32], _: &[u8; 32], _: u32) -> Option<[u8; 32]> {
+            None
+        }
+    }
+
+    #[test]
+    fn sign_with_nonce_fn() {
+        let seckey = SecretKey::parse(&[0x42; 32]).unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        let message = Message::parse(&[6u8; 32]);
+
+        let (sig, _) = Message::sign_with_nonce_fn(&message, &seckey, &Retrying).unwrap();
+        assert!(Signature::verify(&message, &sig, &pubkey));
+
+        let nonce_pubkey = PublicKey::from_secret_key(&SecretKey::parse(&[3u8; 32]).unwrap());
+        let r = nonce_pubkey.serialize_compressed();
+        assert_eq!(&sig.r.b32()[..], &r[1..]);
+
+        assert!(Message::sign_with_nonce_fn(&message, &seckey, &Failing).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use secp256k1::message::Message;
+use secp256k1::keys::PublicKey;
+use secp256k1::error::Error;
+use secp256k1::recovery_id::RecoveryId;
+use secp256k1::signature::Signature;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// An ECDSA signature together with the recovery id needed to recover the
+/// signing public key.
+pub struct RecoverableSignature {
+    pub signature: Signature,
+    pub recovery_id: RecoveryId,
+}
+
+impl RecoverableSignature {
+    pub fn new(signature: Signature, recovery_id: RecoveryId) -> RecoverableSignature {
+        RecoverableSignature {
+            signature,
+            recovery_id,
+        }
+    }
+
+    /// Parse the 65-byte `r || s || v` form, where `v` is the raw recovery
+    /// id in `0..4`.
+    pub fn parse(p: &[u8; 65]) -> Result<RecoverableSignature, Error> {
+        let signature = Signature::parse_standard(array_ref!(p, 0, 64))?;
+        let recovery_id = RecoveryId::parse(p[64])?;
+        Ok(RecoverableSignature::new(signature, recovery_id))
+    }
+
+    /// Serialize to the 65-byte `r || s || v` form, where `v` is the raw
+    /// recovery id.
+    pub fn serialize(&self) -> [u8; 65] {
+        let mut ret = [0u8; 65];
+        ret[0..64].copy_from_slice(&self.signature.serialize());
+        ret[64] = self.recovery_id.serialize();
+        ret
+    }
+
+    /// Parse the 65-byte Bitcoin compact form `header || r || s`, as used by
+    /// signed messages. Returns the signature and whether the signing key
+    /// is to be serialized compressed.
+    pub fn parse_bitcoin(p: &[u8; 65]) -> Result<(RecoverableSignature, bool), Error> {
+        let (recovery_id, compressed) = RecoveryId::from_bitcoin_header(p[0])?;
+        let signature = Signature::parse_standard(array_ref!(p, 1, 64))?;
+        Ok((RecoverableSignature::new(signature, recovery_id), compressed))
+    }
+
+    /// Serialize to the 65-byte Bitcoin compact form `header || r || s`.
+    pub fn serialize_bitcoin(&self, compressed: bool) -> [u8; 65] {
+        let mut ret = [0u8; 65];
+        ret[0] = self.recovery_id.to_bitcoin_header(compressed);
+        ret[1..65].copy_from_slice(&self.signature.serialize());
+        ret
+    }
+
+    /// Parse the 65-byte Ethereum form `r || s || v`. `v` may be the raw
+    /// recovery id, 27/28, or an EIP-155 value `chain_id * 2 + 35 + recid`,
+    /// in which case the chain id is returned as well.
+    pub fn parse_ethereum(p: &[u8; 65]) -> Result<(RecoverableSignature, Option<u64>), Error> {
+        let (recovery_id, chain_id) = RecoveryId::from_ethereum_v(p[64] as u64)?;
+        let signature = Signature::parse_standard(array_ref!(p, 0, 64))?;
+        Ok((RecoverableSignature::new(signature, recovery_id), chain_id))
+    }
+
+    /// Serialize to the 65-byte Ethereum form `r || s || v` with `v` being
+    /// 27 or 28. Use `ethereum_v` for EIP-155 values, which may not fit in
+    /// a byte.
+    pub fn serialize_ethereum(&self) -> Result<[u8; 65], Error> {
+        let v = self.ethereum_v(None)?;
+        let mut ret = [0u8; 65];
+        ret[0..64].copy_from_slice(&self.signature.serialize());
+        ret[64] = v as u8;
+        Ok(ret)
+    }
+
+    /// The Ethereum `v` value for this signature, with EIP-155 replay
+    /// protection if a chain id is given.
+    pub fn ethereum_v(&self, chain_id: Option<u64>) -> Result<u64, Error> {
+        self.recovery_id.to_ethereum_v(chain_id)
+    }
+
+    /// Recover the public key that signed the message.
+    pub fn recover(&self, message: &Message) -> Result<PublicKey, Error> {
+        Signature::recover(message, &self.signature, &self.recovery_id)
+    }
+
+    /// The plain ECDSA signature, without the recovery id.
+    pub fn to_signature(&self) -> Signature {
+        self.signature.clone()
+    }
+}
+
+impl From<(Signature, RecoveryId)> for RecoverableSignature {
+    fn from(pair: (Signature, RecoveryId)) -> RecoverableSignature {
+        RecoverableSignature::new(pair.0, pair.1)
+    }
+}
+
+impl From<RecoverableSignature> for Signature {
+    fn from(sig: RecoverableSignature) -> Signature {
+        sig.signature
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::rand::{thread_rng, Rng};
+    use secp256k1_test::{Message as SecpMessage, Secp256k1};
+    use secp256k1_test::key::SecretKey as SecpSecretKey;
+    use {Error, Message, PublicKey, RecoverableSignature, RecoveryId, SecretKey, Signature};
+
+    fn sign() -> (Message, PublicKey, RecoverableSignature) {
+        let seckey = SecretKey::parse(&[0x42; 32]).unwrap();
+        let message = Message::parse(&[6u8; 32]);
+        let sig = Message::sign_recoverable(&message, &seckey).unwrap();
+        (message, PublicKey::from_secret_key(&seckey), sig)
+    }
+
+    #[test]
+    fn test_serialize_parse() {
+        let (message, pubkey, sig) = sign();
+
+        let raw = sig.serialize();
+        assert_eq!(raw[64], sig.recovery_id.serialize());
+        assert_eq!(RecoverableSignature::parse(&raw).unwrap(), sig);
+        assert_eq!(sig.recover(&message).unwrap(), pubkey);
+
+        let plain: Signature = sig.clone().into();
+        assert_eq!(plain, sig.to_signature());
+        assert!(Signature::verify(&message, &plain, &pubkey));
+
+        let mut bad = raw;
+        bad[64] = 4;
+        assert_eq!(RecoverableSignature::parse(&bad), Err(Error::InvalidRecoveryId));
+        let mut bad = raw;
+        for b in bad[32..64].iter_mut() {
+            *b = 0xff;
+        }
+        assert_eq!(RecoverableSignature::parse(&bad), Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn test_bitcoin_header() {
+        let (message, pubkey, sig) = sign();
+        let recid = sig.recovery_id.serialize();
+
+        let compressed = sig.serialize_bitcoin(true);
+        assert_eq!(compressed[0], 31 + recid);
+        assert_eq!(&compressed[1..], &sig.serialize()[..64]);
+        let (parsed, is_compressed) = RecoverableSignature::parse_bitcoin(&compressed).unwrap();
+        assert!(is_compressed);
+        assert_eq!(parsed, sig);
+        assert_eq!(parsed.recover(&message).unwrap(), pubkey);
+
+        let uncompressed = sig.serialize_bitcoin(false);
+        assert_eq!(uncompressed[0], 27 + recid);
+        let (parsed, is_compressed) = RecoverableSignature::parse_bitcoin(&uncompressed).unwrap();
+        assert!(!is_compressed);
+        assert_eq!(parsed, sig);
+
+        for header in 27..35 {
+            let (recid, compressed) = RecoveryId::from_bitcoin_header(header).unwrap();
+            assert_eq!(recid.to_bitcoin_header(compressed), header);
+        }
+        assert!(RecoveryId::from_bitcoin_header(26).is_err());
+        assert!(RecoveryId::from_bitcoin_header(35).is_err());
+    }
+
+    #[test]
+    fn test_ethereum_v() {
+        assert_eq!(RecoveryId::from_ethereum_v(0), Ok((RecoveryId(0), None)));
+        assert_eq!(RecoveryId::from_ethereum_v(1), Ok((RecoveryId(1), None)));
+        assert_eq!(RecoveryId::from_ethereum_v(27), Ok((RecoveryId(0), None)));
+        assert_eq!(RecoveryId::from_ethereum_v(28), Ok((RecoveryId(1), None)));
+        // EIP-155 mainnet.
+        assert_eq!(RecoveryId::from_ethereum_v(37), Ok((RecoveryId(0), Some(1))));
+        assert_eq!(RecoveryId::from_ethereum_v(38), Ok((RecoveryId(1), Some(1))));
+        assert_eq!(RecoveryId::from_ethereum_v(2 * 137 + 36), Ok((RecoveryId(1), Some(137))));
+        for v in &[2, 26, 29, 34] {
+            assert_eq!(RecoveryId::from_ethereum_v(*v), Err(Error::InvalidRecoveryId));
+        }
+
+        assert_eq!(RecoveryId(0).to_ethereum_v(None), Ok(27));
+        assert_eq!(RecoveryId(1).to_ethereum_v(None), Ok(28));
+        assert_eq!(RecoveryId(0).to_ethereum_v(Some(1)), Ok(37));
+        assert_eq!(RecoveryId(1).to_ethereum_v(Some(137)), Ok(2 * 137 + 36));
+        assert_eq!(RecoveryId(2).to_ethereum_v(None), Err(Error::InvalidRecoveryId));
+        assert_eq!(RecoveryId(0).to_ethereum_v(Some(u64::MAX)), Err(Error::InvalidRecoveryId));
+    }
+
+    #[test]
+    fn test_ethereum_serialize_parse() {
+        let (message, pubkey, sig) = sign();
+        let recid = sig.recovery_id.serialize() as u64;
+
+        let eth = sig.serialize_ethereum().unwrap();
+        assert_eq!(eth[64] as u64, 27 + recid);
+        let (parsed, chain_id) = RecoverableSignature::parse_ethereum(&eth).unwrap();
+        assert_eq!(parsed, sig);
+        assert_eq!(chain_id, None);
+        assert_eq!(parsed.recover(&message).unwrap(), pubkey);
+
+        let mut eip155 = eth;
+        eip155[64] = sig.ethereum_v(Some(1)).unwrap() as u8;
+        assert_eq!(eip155[64] as u64, 37 + recid);
+        let (parsed, chain_id) = RecoverableSignature::parse_ethereum(&eip155).unwrap();
+        assert_eq!(parsed, sig);
+        assert_eq!(chain_id, Some(1));
+    }
+
+    #[test]
+    fn test_against_libsecp256k1() {
+        let secp256k1 = Secp256k1::new();
+        let mut rng = thread_rng();
+
+        for _ in 0..16 {
+            let message_arr: [u8; 32] = rng.gen();
+            let seckey_arr: [u8; 32] = rng.gen();
+            let seckey = match SecretKey::parse(&seckey_arr) {
+                Ok(seckey) => seckey,
+                Err(_) => continue,
+            };
+
+            let secp_seckey = SecpSecretKey::from_slice(&secp256k1, &seckey_arr).unwrap();
+            let secp_message = SecpMessage::from_slice(&message_arr).unwrap();
+            let (secp_recid, secp_sig) = secp256k1
+                .sign_recoverable(&secp_message, &secp_seckey)
+                .unwrap()
+                .serialize_compact(&secp256k1);
+
+            let sig = Message::sign_recoverable(&Message::parse(&message_arr), &seckey).unwrap();
+            let raw = sig.serialize();
+            assert_eq!(&raw[..64], &secp_sig[..]);
+            assert_eq!(raw[64] as i32, secp_recid.to_i32());
+        }
+    }
+}
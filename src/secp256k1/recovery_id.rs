@@ -16,6 +16,50 @@ impl RecoveryId {
     pub fn serialize(&self) -> u8 {
         self.0
     }
+
+    /// Parse a Bitcoin compact signature header byte, `27 + recid`, plus 4
+    /// if the signing key is compressed. Returns the recovery id and
+    /// whether the key is compressed.
+    pub fn from_bitcoin_header(header: u8) -> Result<(RecoveryId, bool), Error> {
+        if !(27..=34).contains(&header) {
+            return Err(Error::InvalidRecoveryId);
+        }
+        let header = header - 27;
+        Ok((RecoveryId(header & 3), header & 4 != 0))
+    }
+
+    /// The Bitcoin compact signature header byte for this recovery id.
+    pub fn to_bitcoin_header(&self, compressed: bool) -> u8 {
+        27 + self.0 + if compressed { 4 } else { 0 }
+    }
+
+    /// Parse an Ethereum `v` value: the raw recovery id 0/1, 27/28, or an
+    /// EIP-155 value `chain_id * 2 + 35 + recid`. Returns the recovery id
+    /// and the chain id, if any.
+    pub fn from_ethereum_v(v: u64) -> Result<(RecoveryId, Option<u64>), Error> {
+        match v {
+            0 | 1 => Ok((RecoveryId(v as u8), None)),
+            27 | 28 => Ok((RecoveryId((v - 27) as u8), None)),
+            v if v >= 35 => Ok((RecoveryId(((v - 35) % 2) as u8), Some((v - 35) / 2))),
+            _ => Err(Error::InvalidRecoveryId),
+        }
+    }
+
+    /// The Ethereum `v` value for this recovery id: 27/28, or the EIP-155
+    /// value if a chain id is given. Ethereum cannot represent recovery ids
+    /// 2 and 3.
+    pub fn to_ethereum_v(&self, chain_id: Option<u64>) -> Result<u64, Error> {
+        if self.0 > 1 {
+            return Err(Error::InvalidRecoveryId);
+        }
+        match chain_id {
+            None => Ok(27 + self.0 as u64),
+            Some(chain_id) => chain_id
+                .checked_mul(2)
+                .and_then(|v| v.checked_add(35 + self.0 as u64))
+                .ok_or(Error::InvalidRecoveryId),
+        }
+    }
 }
 
 impl Into<u8> for RecoveryId {
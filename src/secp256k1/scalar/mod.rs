@@ -0,0 +1,428 @@
+use std::ops::{ Add, AddAssign, Neg, Mul, MulAssign };
+
+macro_rules! scalar_const {
+    ($d7:expr, $d6:expr, $d5:expr, $d4:expr, $d3:expr, $d2:expr, $d1:expr, $d0:expr) => {
+        $crate::secp256k1::Scalar::new($d7, $d6, $d5, $d4, $d3, $d2, $d1, $d0)
+    };
+}
+
+// The 4x64 representation needs 64x64->128 bit products and is used on
+// 64-bit targets unless the `force-32bit` feature is set. Tests build both
+// to cross-check them.
+#[cfg(any(test, not(all(target_pointer_width = "64", not(feature = "force-32bit")))))]
+#[cfg_attr(all(target_pointer_width = "64", not(feature = "force-32bit")), allow(dead_code))]
+mod scalar_8x32;
+#[cfg(any(test, all(target_pointer_width = "64", not(feature = "force-32bit"))))]
+#[cfg_attr(not(all(target_pointer_width = "64", not(feature = "force-32bit"))), allow(dead_code))]
+mod scalar_4x64;
+
+#[cfg(not(all(target_pointer_width = "64", not(feature = "force-32bit"))))]
+pub use self::scalar_8x32::Scalar;
+#[cfg(all(target_pointer_width = "64", not(feature = "force-32bit")))]
+pub use self::scalar_4x64::Scalar;
+
+/// The cube root of unity `lambda` such that `lambda.(x, y) = (beta.x, y)`
+/// on secp256k1.
+const LAMBDA: Scalar = scalar_const!(
+    0x5363AD4C, 0xC05C30E0, 0xA5261C02, 0x8812645A, 0x122E22EA, 0x20816678, 0xDF02967C, 0x1B23BD72
+);
+
+/// `-b1` and `-b2` for the reduced basis `{a1 + b1.lambda, a2 + b2.lambda}`
+/// of the multiples of `lambda` that are zero mod n, and the rounded
+/// `g1 = 2^384.b2/n` and `g2 = 2^384.(-b1)/n`, used by `split_lambda`.
+const MINUS_B1: Scalar = scalar_const!(0, 0, 0, 0, 0xE4437ED6, 0x010E8828, 0x6F547FA9, 0x0ABFE4C3);
+const MINUS_B2: Scalar = scalar_const!(
+    0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFE, 0x8A280AC5, 0x0774346D, 0xD765CDA8, 0x3DB1562C
+);
+const G1: Scalar = scalar_const!(
+    0x3086D221, 0xA7D46BCD, 0xE86C90E4, 0x9284EB15, 0x3DAA8A14, 0x71E8CA7F, 0xE893209A, 0x45DBB031
+);
+const G2: Scalar = scalar_const!(
+    0xE4437ED6, 0x010E8828, 0x6F547FA9, 0x0ABFE4C4, 0x221208AC, 0x9DF506C6, 0x1571B4AE, 0x8AC47F71
+);
+
+impl Scalar {
+    /// Split a scalar `k` into `(k1, k2)` with `k = k1 + k2.lambda`, where
+    /// `k1` and `k2`, or their negations, are below 2^128. This runs in
+    /// constant time.
+    pub fn split_lambda(&self) -> (Scalar, Scalar) {
+        let c1 = self.mul_shift_var(&G1, 384) * MINUS_B1;
+        let c2 = self.mul_shift_var(&G2, 384) * MINUS_B2;
+        let r2 = c1 + c2;
+        let r1 = (r2 * LAMBDA).neg() + *self;
+        (r1, r2)
+    }
+
+
+    pub fn sqr(&self) -> Scalar {
+        let mut ret = Scalar::default();
+        ret.sqr_in_place(self);
+        ret
+    }
+
+    pub fn inv_in_place(&mut self, x: &Scalar) {
+        let u2 = x.sqr();
+        let x2 = &u2 * x;
+        let u5 = &u2 * &x2;
+        let x3 = &u5 * &u2;
+        let u9 = &x3 * &u2;
+        let u11 = &u9 * &u2;
+        let u13 = &u11 * &u2;
+
+        let mut x6 = u13.sqr();
+        x6 = x6.sqr();
+        x6 *= &u11;
+
+        let mut x8 = x6.sqr();
+        x8 = x8.sqr();
+        x8 *= &x2;
+
+        let mut x14 = x8.sqr();
+        for _ in 0..5 {
+            x14 = x14.sqr();
+        }
+        x14 *= &x6;
+
+        let mut x28 = x14.sqr();
+        for _ in 0..13 {
+            x28 = x28.sqr();
+        }
+        x28 *= &x14;
+
+        let mut x56 = x28.sqr();
+        for _ in 0..27 {
+            x56 = x56.sqr();
+        }
+        x56 *= &x28;
+
+        let mut x112 = x56.sqr();
+        for _ in 0..55 {
+            x112 = x112.sqr();
+        }
+        x112 *= &x56;
+
+        let mut x126 = x112.sqr();
+        for _ in 0..13 {
+            x126 = x126.sqr();
+        }
+        x126 *= &x14;
+
+        let mut t = x126;
+        for _ in 0..3 {
+            t = t.sqr();
+        }
+        t *= &u5;
+        for _ in 0..4 {
+            t = t.sqr();
+        }
+        t *= &x3;
+        for _ in 0..4 {
+            t = t.sqr();
+        }
+        t *= &u5;
+        for _ in 0..5 {
+            t = t.sqr();
+        }
+        t *= &u11;
+        for _ in 0..4 {
+            t = t.sqr();
+        }
+        t *= &u11;
+        for _ in 0..4 {
+            t = t.sqr();
+        }
+        t *= &x3;
+        for _ in 0..5 {
+            t = t.sqr();
+        }
+        t *= &x3;
+        for _ in 0..6 {
+            t = t.sqr();
+        }
+        t *= &u13;
+        for _ in 0..4 {
+            t = t.sqr();
+        }
+        t *= &u5;
+        for _ in 0..3 {
+            t = t.sqr();
+        }
+        t *= &x3;
+        for _ in 0..5 {
+            t = t.sqr();
+        }
+        t *= &u9;
+        for _ in 0..6 {
+            t = t.sqr();
+        }
+        t *= &u5;
+        for _ in 0..10 {
+            t = t.sqr();
+        }
+        t *= &x3;
+        for _ in 0..4 {
+            t = t.sqr();
+        }
+        t *= &x3;
+        for _ in 0..9 {
+            t = t.sqr();
+        }
+        t *= &x8;
+        for _ in 0..5 {
+            t = t.sqr();
+        }
+        t *= &u9;
+        for _ in 0..6 {
+            t = t.sqr();
+        }
+        t *= &u11;
+        for _ in 0..4 {
+            t = t.sqr();
+        }
+        t *= &u13;
+        for _ in 0..5 {
+            t = t.sqr();
+        }
+        t *= &x2;
+        for _ in 0..6 {
+            t = t.sqr();
+        }
+        t *= &u13;
+        for _ in 0..10 {
+            t = t.sqr();
+        }
+        t *= &u13;
+        for _ in 0..4 {
+            t = t.sqr();
+        }
+        t *= &u9;
+        for _ in 0..6 {
+            t = t.sqr();
+        }
+        t *= x;
+        for _ in 0..8 {
+            t = t.sqr();
+        }
+        *self = &t * &x6;
+    }
+
+    pub fn inv(&self) -> Scalar {
+        let mut ret = Scalar::default();
+        ret.inv_in_place(self);
+        ret
+    }
+
+    pub fn inv_var(&self) -> Scalar {
+        self.inv()
+    }
+}
+
+impl Add<Scalar> for Scalar {
+    type Output = Scalar;
+    fn add(self, other: Scalar) -> Scalar {
+        let mut ret = Scalar::default();
+        ret.add_in_place(&self, &other);
+        ret
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> <Self as Neg>::Output {
+        Self::neg(&self)
+    }
+}
+
+impl<'a, 'b> Add<&'a Scalar> for &'b Scalar {
+    type Output = Scalar;
+    fn add(self, other: &'a Scalar) -> Scalar {
+        let mut ret = Scalar::default();
+        ret.add_in_place(self, other);
+        ret
+    }
+}
+
+impl<'a> AddAssign<&'a Scalar> for Scalar {
+    fn add_assign(&mut self, other: &'a Scalar) {
+        let mut ret = Scalar::default();
+        ret.add_in_place(self, other);
+        *self = ret;
+    }
+}
+
+impl AddAssign<Scalar> for Scalar {
+    fn add_assign(&mut self, other: Scalar) {
+        self.add_assign(&other)
+    }
+}
+
+impl Mul<Scalar> for Scalar {
+    type Output = Scalar;
+    fn mul(self, other: Scalar) -> Scalar {
+        let mut ret = Scalar::default();
+        ret.mul_in_place(&self, &other);
+        ret
+    }
+}
+
+impl<'a, 'b> Mul<&'a Scalar> for &'b Scalar {
+    type Output = Scalar;
+    fn mul(self, other: &'a Scalar) -> Scalar {
+        let mut ret = Scalar::default();
+        ret.mul_in_place(self, other);
+        ret
+    }
+}
+
+impl<'a> MulAssign<&'a Scalar> for Scalar {
+    fn mul_assign(&mut self, other: &'a Scalar) {
+        let mut ret = Scalar::default();
+        ret.mul_in_place(self, other);
+        *self = ret;
+    }
+}
+
+impl MulAssign<Scalar> for Scalar {
+    fn mul_assign(&mut self, other: Scalar) {
+        self.mul_assign(&other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scalar_4x64::Scalar as Scalar4x64;
+    use super::scalar_8x32::Scalar as Scalar8x32;
+    use rand::{thread_rng, Rng};
+
+    fn random_b32() -> [u8; 32] {
+        let mut b32 = [0u8; 32];
+        thread_rng().fill_bytes(&mut b32);
+        b32
+    }
+
+    fn pair(b32: &[u8; 32]) -> (Scalar8x32, Scalar4x64) {
+        let mut a = Scalar8x32::default();
+        let mut b = Scalar4x64::default();
+        assert_eq!(a.set_b32(b32), b.set_b32(b32));
+        assert_eq!(a.b32(), b.b32());
+        (a, b)
+    }
+
+    /// 0, 1, n/2, n/2 + 1, n - 1, n and 2^256 - 1.
+    fn edge_b32() -> Vec<[u8; 32]> {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut half = [0u8; 32];
+        half.copy_from_slice(&::hex::decode("7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF5D576E7357A4501DDFE92F46681B20A0").unwrap());
+        let mut half_p1 = half;
+        half_p1[31] += 1;
+        let mut n = [0u8; 32];
+        n.copy_from_slice(&::hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141").unwrap());
+        let mut nm1 = n;
+        nm1[31] -= 1;
+        vec![[0u8; 32], one, half, half_p1, nm1, n, [0xffu8; 32]]
+    }
+
+    #[test]
+    fn test_const() {
+        let a = scalar_const!(
+            0x5363AD4C, 0xC05C30E0, 0xA5261C02, 0x8812645A, 0x122E22EA, 0x20816678, 0xDF02967C, 0x1B23BD72
+        );
+        let b = Scalar8x32::new(
+            0x5363AD4C, 0xC05C30E0, 0xA5261C02, 0x8812645A, 0x122E22EA, 0x20816678, 0xDF02967C, 0x1B23BD72,
+        );
+        assert_eq!(a.b32(), b.b32());
+    }
+
+    #[test]
+    fn test_limbs() {
+        for b32 in edge_b32().iter().chain(Some(random_b32()).iter()) {
+            let (a, b) = pair(b32);
+            assert_eq!(a.limbs(), b.limbs());
+            assert_eq!(Scalar8x32::from_limbs(a.limbs()), a);
+            assert_eq!(Scalar4x64::from_limbs(b.limbs()), b);
+        }
+        let limbs = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(Scalar4x64::from_limbs(limbs).limbs(), limbs);
+        // Values not below the group order are reduced: n + 1 becomes 1.
+        let n_plus_1 = [
+            0xD0364142, 0xBFD25E8C, 0xAF48A03B, 0xBAAEDCE6, 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF,
+        ];
+        assert_eq!(Scalar8x32::from_limbs(n_plus_1).limbs(), [1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(Scalar4x64::from_limbs(n_plus_1).limbs(), [1, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        for b32 in edge_b32().iter() {
+            let (a, b) = pair(b32);
+            assert_eq!(a.is_zero(), b.is_zero());
+            assert_eq!(a.is_one(), b.is_one());
+            assert_eq!(a.is_high(), b.is_high());
+            assert_eq!(a.is_even(), b.is_even());
+            assert_eq!(a.neg().b32(), b.neg().b32());
+        }
+        let edges = edge_b32();
+        assert!(!pair(&edges[2]).0.is_high());
+        assert!(pair(&edges[3]).0.is_high());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut inputs = edge_b32();
+        inputs.extend((0..64).map(|_| random_b32()));
+        for x in inputs.iter() {
+            for y in inputs.iter().take(16) {
+                let (x1, x2) = pair(x);
+                let (y1, y2) = pair(y);
+
+                let mut r1 = Scalar8x32::default();
+                let mut r2 = Scalar4x64::default();
+                assert_eq!(r1.add_in_place(&x1, &y1), r2.add_in_place(&x2, &y2));
+                assert_eq!(r1.b32(), r2.b32());
+
+                r1.mul_in_place(&x1, &y1);
+                r2.mul_in_place(&x2, &y2);
+                assert_eq!(r1.b32(), r2.b32());
+
+                for shift in [256, 272, 384, 400, 511].iter() {
+                    assert_eq!(x1.mul_shift_var(&y1, *shift).b32(), x2.mul_shift_var(&y2, *shift).b32());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bits() {
+        for _ in 0..64 {
+            let (mut a, mut b) = pair(&random_b32());
+            let mut s1 = Scalar8x32::default();
+            let mut s2 = Scalar4x64::default();
+            s1.sqr_in_place(&a);
+            s2.sqr_in_place(&b);
+            assert_eq!(s1.b32(), s2.b32());
+
+            for offset in 0..250 {
+                assert_eq!(a.bits_var(offset, 6), b.bits_var(offset, 6));
+            }
+            for offset in 0..16 {
+                assert_eq!(a.bits(offset * 16, 16), b.bits(offset * 16, 16));
+            }
+
+            let (mut c1, mut c2) = (a, b);
+            assert_eq!(c1.cond_neg_mut(true), c2.cond_neg_mut(true));
+            assert_eq!(c1.b32(), c2.b32());
+            assert_eq!(c1.cond_neg_mut(false), c2.cond_neg_mut(false));
+            assert_eq!(c1.b32(), c2.b32());
+
+            let n = 1 + (a.bits(0, 4) as usize % 15);
+            assert_eq!(a.shr_int(n), b.shr_int(n));
+            assert_eq!(a.b32(), b.b32());
+
+            a.cadd_bit(200, true);
+            b.cadd_bit(200, true);
+            assert_eq!(a.b32(), b.b32());
+        }
+    }
+}
@@ -0,0 +1,604 @@
+const SECP256K1_N_0: u64 = 0xBFD25E8CD0364141;
+const SECP256K1_N_1: u64 = 0xBAAEDCE6AF48A03B;
+const SECP256K1_N_2: u64 = 0xFFFFFFFFFFFFFFFE;
+const SECP256K1_N_3: u64 = 0xFFFFFFFFFFFFFFFF;
+
+const SECP256K1_N_C_0: u64 = !SECP256K1_N_0 + 1;
+const SECP256K1_N_C_1: u64 = !SECP256K1_N_1;
+const SECP256K1_N_C_2: u64 = 1;
+
+const SECP256K1_N_H_0: u64 = 0xDFE92F46681B20A0;
+const SECP256K1_N_H_1: u64 = 0x5D576E7357A4501D;
+const SECP256K1_N_H_2: u64 = 0xFFFFFFFFFFFFFFFF;
+const SECP256K1_N_H_3: u64 = 0x7FFFFFFFFFFFFFFF;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+/// A 256-bit scalar value. The limb layout depends on the backend; use
+/// `limbs` and `from_limbs` to get at the 32-bit limbs.
+pub struct Scalar(pub(crate) [u64; 4]);
+
+impl Scalar {
+    pub const fn new(d7: u32, d6: u32, d5: u32, d4: u32, d3: u32, d2: u32, d1: u32, d0: u32) -> Self {
+        Scalar([
+            ((d1 as u64) << 32) | (d0 as u64),
+            ((d3 as u64) << 32) | (d2 as u64),
+            ((d5 as u64) << 32) | (d4 as u64),
+            ((d7 as u64) << 32) | (d6 as u64),
+        ])
+    }
+
+    /// Build a scalar from eight 32-bit limbs, least significant first,
+    /// reduced modulo the group order.
+    pub fn from_limbs(limbs: [u32; 8]) -> Scalar {
+        let mut ret = Scalar::new(
+            limbs[7], limbs[6], limbs[5], limbs[4], limbs[3], limbs[2], limbs[1], limbs[0],
+        );
+        let overflow = ret.check_overflow();
+        ret.reduce(overflow);
+        ret
+    }
+
+    /// The eight 32-bit limbs of the scalar, least significant first.
+    pub fn limbs(&self) -> [u32; 8] {
+        let mut ret = [0u32; 8];
+        for (i, limb) in self.0.iter().enumerate() {
+            ret[2 * i] = *limb as u32;
+            ret[2 * i + 1] = (*limb >> 32) as u32;
+        }
+        ret
+    }
+
+    /// Clear a scalar to prevent the leak of sensitive data.
+    pub fn clear(&mut self) {
+        self.0 = [0u64; 4];
+    }
+
+    /// Set a scalar to an unsigned integer.
+    pub fn set_int(&mut self, v: u32) {
+        self.0 = [v as u64, 0, 0, 0];
+    }
+
+    /// Access bits from a scalar. All requested bits must belong to
+    /// the same 64-bit limb.
+    pub fn bits(&self, offset: usize, count: usize) -> u32 {
+        debug_assert!((offset + count - 1) >> 6 == offset >> 6);
+        ((self.0[offset >> 6] >> (offset & 0x3F)) & ((1 << count) - 1)) as u32
+    }
+
+    /// Access bits from a scalar. Not constant time.
+    pub fn bits_var(&self, offset: usize, count: usize) -> u32 {
+        debug_assert!(count < 32);
+        debug_assert!(offset + count <= 256);
+        if (offset + count - 1) >> 6 == offset >> 6 {
+            self.bits(offset, count)
+        } else {
+            debug_assert!((offset >> 6) + 1 < 4);
+            (((self.0[offset >> 6] >> (offset & 0x3F))
+                | (self.0[(offset >> 6) + 1] << (64 - (offset & 0x3F))))
+                & ((1 << count) - 1)) as u32
+        }
+    }
+
+    fn check_overflow(&self) -> bool {
+        let mut yes: bool = false;
+        let mut no: bool = false;
+        no = no || (self.0[3] < SECP256K1_N_3); /* No need for a > check. */
+        no = no || (self.0[2] < SECP256K1_N_2);
+        yes = yes || ((self.0[2] > SECP256K1_N_2) && !no);
+        no = no || (self.0[1] < SECP256K1_N_1);
+        yes = yes || ((self.0[1] > SECP256K1_N_1) && !no);
+        yes = yes || ((self.0[0] >= SECP256K1_N_0) && !no);
+        yes
+    }
+
+    fn reduce(&mut self, overflow: bool) -> bool {
+        let o: u128 = if overflow { 1 } else { 0 };
+        let mut t: u128;
+        t = (self.0[0] as u128) + o * (SECP256K1_N_C_0 as u128);
+        self.0[0] = t as u64;
+        t >>= 64;
+        t += (self.0[1] as u128) + o * (SECP256K1_N_C_1 as u128);
+        self.0[1] = t as u64;
+        t >>= 64;
+        t += (self.0[2] as u128) + o * (SECP256K1_N_C_2 as u128);
+        self.0[2] = t as u64;
+        t >>= 64;
+        t += self.0[3] as u128;
+        self.0[3] = t as u64;
+        overflow
+    }
+
+    /// Add two scalars together (modulo the group order). Returns
+    /// whether it overflowed.
+    pub fn add_in_place(&mut self, a: &Scalar, b: &Scalar) -> bool {
+        let mut t: u128 = (a.0[0] as u128) + (b.0[0] as u128);
+        self.0[0] = t as u64;
+        t >>= 64;
+        t += (a.0[1] as u128) + (b.0[1] as u128);
+        self.0[1] = t as u64;
+        t >>= 64;
+        t += (a.0[2] as u128) + (b.0[2] as u128);
+        self.0[2] = t as u64;
+        t >>= 64;
+        t += (a.0[3] as u128) + (b.0[3] as u128);
+        self.0[3] = t as u64;
+        t >>= 64;
+        let overflow = t as u64 + if self.check_overflow() { 1 } else { 0 };
+        debug_assert!(overflow == 0 || overflow == 1);
+        self.reduce(overflow == 1);
+        overflow == 1
+    }
+
+    /// Conditionally add a power of two to a scalar. The result is
+    /// not allowed to overflow.
+    pub fn cadd_bit(&mut self, mut bit: usize, flag: bool) {
+        let mut t: u128;
+        debug_assert!(bit < 256);
+        bit += if flag { 0 } else { usize::MAX } & 0x100;
+        t = (self.0[0] as u128) + ((if (bit >> 6) == 0 { 1 } else { 0 }) << (bit & 0x3F));
+        self.0[0] = t as u64;
+        t >>= 64;
+        t += (self.0[1] as u128) + ((if (bit >> 6) == 1 { 1 } else { 0 }) << (bit & 0x3F));
+        self.0[1] = t as u64;
+        t >>= 64;
+        t += (self.0[2] as u128) + ((if (bit >> 6) == 2 { 1 } else { 0 }) << (bit & 0x3F));
+        self.0[2] = t as u64;
+        t >>= 64;
+        t += (self.0[3] as u128) + ((if (bit >> 6) == 3 { 1 } else { 0 }) << (bit & 0x3F));
+        self.0[3] = t as u64;
+        debug_assert!((t >> 64) == 0);
+        debug_assert!(!self.check_overflow());
+    }
+
+    /// Set a scalar from a big endian byte array.
+    pub fn set_b32(&mut self, b32: &[u8; 32]) -> bool {
+        self.0[0] = (b32[31] as u64)
+            | ((b32[30] as u64) << 8)
+            | ((b32[29] as u64) << 16)
+            | ((b32[28] as u64) << 24)
+            | ((b32[27] as u64) << 32)
+            | ((b32[26] as u64) << 40)
+            | ((b32[25] as u64) << 48)
+            | ((b32[24] as u64) << 56);
+        self.0[1] = (b32[23] as u64)
+            | ((b32[22] as u64) << 8)
+            | ((b32[21] as u64) << 16)
+            | ((b32[20] as u64) << 24)
+            | ((b32[19] as u64) << 32)
+            | ((b32[18] as u64) << 40)
+            | ((b32[17] as u64) << 48)
+            | ((b32[16] as u64) << 56);
+        self.0[2] = (b32[15] as u64)
+            | ((b32[14] as u64) << 8)
+            | ((b32[13] as u64) << 16)
+            | ((b32[12] as u64) << 24)
+            | ((b32[11] as u64) << 32)
+            | ((b32[10] as u64) << 40)
+            | ((b32[9] as u64) << 48)
+            | ((b32[8] as u64) << 56);
+        self.0[3] = (b32[7] as u64)
+            | ((b32[6] as u64) << 8)
+            | ((b32[5] as u64) << 16)
+            | ((b32[4] as u64) << 24)
+            | ((b32[3] as u64) << 32)
+            | ((b32[2] as u64) << 40)
+            | ((b32[1] as u64) << 48)
+            | ((b32[0] as u64) << 56);
+
+        let overflow = self.check_overflow();
+        self.reduce(overflow)
+    }
+
+    /// Convert a scalar to a byte array.
+    pub fn b32(&self) -> [u8; 32] {
+        let mut bin = [0u8; 32];
+        self.fill_b32(&mut bin);
+        bin
+    }
+
+    /// Convert a scalar to a byte array.
+    pub fn fill_b32(&self, bin: &mut [u8; 32]) {
+        bin[0] = (self.0[3] >> 56) as u8;
+        bin[1] = (self.0[3] >> 48) as u8;
+        bin[2] = (self.0[3] >> 40) as u8;
+        bin[3] = (self.0[3] >> 32) as u8;
+        bin[4] = (self.0[3] >> 24) as u8;
+        bin[5] = (self.0[3] >> 16) as u8;
+        bin[6] = (self.0[3] >> 8) as u8;
+        bin[7] = (self.0[3]) as u8;
+        bin[8] = (self.0[2] >> 56) as u8;
+        bin[9] = (self.0[2] >> 48) as u8;
+        bin[10] = (self.0[2] >> 40) as u8;
+        bin[11] = (self.0[2] >> 32) as u8;
+        bin[12] = (self.0[2] >> 24) as u8;
+        bin[13] = (self.0[2] >> 16) as u8;
+        bin[14] = (self.0[2] >> 8) as u8;
+        bin[15] = (self.0[2]) as u8;
+        bin[16] = (self.0[1] >> 56) as u8;
+        bin[17] = (self.0[1] >> 48) as u8;
+        bin[18] = (self.0[1] >> 40) as u8;
+        bin[19] = (self.0[1] >> 32) as u8;
+        bin[20] = (self.0[1] >> 24) as u8;
+        bin[21] = (self.0[1] >> 16) as u8;
+        bin[22] = (self.0[1] >> 8) as u8;
+        bin[23] = (self.0[1]) as u8;
+        bin[24] = (self.0[0] >> 56) as u8;
+        bin[25] = (self.0[0] >> 48) as u8;
+        bin[26] = (self.0[0] >> 40) as u8;
+        bin[27] = (self.0[0] >> 32) as u8;
+        bin[28] = (self.0[0] >> 24) as u8;
+        bin[29] = (self.0[0] >> 16) as u8;
+        bin[30] = (self.0[0] >> 8) as u8;
+        bin[31] = (self.0[0]) as u8;
+    }
+
+    /// Check whether a scalar equals zero.
+    pub fn is_zero(&self) -> bool {
+        (self.0[0] | self.0[1] | self.0[2] | self.0[3]) == 0
+    }
+
+    /// Compute the complement of a scalar (modulo the group order).
+    pub fn neg_in_place(&mut self, a: &Scalar) {
+        let nonzero: u128 = 0xFFFFFFFFFFFFFFFF * if !a.is_zero() { 1 } else { 0 };
+        let mut t: u128 = (!a.0[0]) as u128 + (SECP256K1_N_0 + 1) as u128;
+        self.0[0] = (t & nonzero) as u64;
+        t >>= 64;
+        t += (!a.0[1]) as u128 + SECP256K1_N_1 as u128;
+        self.0[1] = (t & nonzero) as u64;
+        t >>= 64;
+        t += (!a.0[2]) as u128 + SECP256K1_N_2 as u128;
+        self.0[2] = (t & nonzero) as u64;
+        t >>= 64;
+        t += (!a.0[3]) as u128 + SECP256K1_N_3 as u128;
+        self.0[3] = (t & nonzero) as u64;
+    }
+
+    pub fn neg(&self) -> Scalar {
+        let mut ret = Scalar::default();
+        ret.neg_in_place(self);
+        ret
+    }
+
+    /// Check whether a scalar equals one.
+    pub fn is_one(&self) -> bool {
+        ((self.0[0] ^ 1) | self.0[1] | self.0[2] | self.0[3]) == 0
+    }
+
+    /// Check whether a scalar is higher than the group order divided
+    /// by 2.
+    pub fn is_high(&self) -> bool {
+        let mut yes: bool = false;
+        let mut no: bool = false;
+        no = no || (self.0[3] < SECP256K1_N_H_3);
+        yes = yes || ((self.0[3] > SECP256K1_N_H_3) && !no);
+        no = no || ((self.0[2] < SECP256K1_N_H_2) && !yes); /* No need for a > check. */
+        no = no || ((self.0[1] < SECP256K1_N_H_1) && !yes);
+        yes = yes || ((self.0[1] > SECP256K1_N_H_1) && !no);
+        yes = yes || ((self.0[0] > SECP256K1_N_H_0) && !no);
+        yes
+    }
+
+    /// Conditionally negate a number, in constant time. Returns -1 if
+    /// the number was negated, 1 otherwise.
+    pub fn cond_neg_mut(&mut self, flag: bool) -> isize {
+        let mask = if flag { u64::MAX } else { 0 };
+        let nonzero: u128 = 0xFFFFFFFFFFFFFFFF * if !self.is_zero() { 1 } else { 0 };
+        let mut t: u128 = (self.0[0] ^ mask) as u128 + ((SECP256K1_N_0 + 1) & mask) as u128;
+        self.0[0] = (t & nonzero) as u64;
+        t >>= 64;
+        t += (self.0[1] ^ mask) as u128 + (SECP256K1_N_1 & mask) as u128;
+        self.0[1] = (t & nonzero) as u64;
+        t >>= 64;
+        t += (self.0[2] ^ mask) as u128 + (SECP256K1_N_2 & mask) as u128;
+        self.0[2] = (t & nonzero) as u64;
+        t >>= 64;
+        t += (self.0[3] ^ mask) as u128 + (SECP256K1_N_3 & mask) as u128;
+        self.0[3] = (t & nonzero) as u64;
+
+        if mask == 0 {
+            1
+        } else {
+            -1
+        }
+    }
+}
+
+macro_rules! define_ops {
+    ($c0:ident, $c1:ident, $c2:ident) => {
+        #[allow(unused_macros)]
+        macro_rules! muladd {
+            ($a: expr,$b: expr) => {
+                let a = $a;
+                let b = $b;
+                let t = (a as u128) * (b as u128);
+                let mut th = (t >> 64) as u64;
+                let tl = t as u64;
+                $c0 = $c0.wrapping_add(tl);
+                th = th.wrapping_add(if $c0 < tl { 1 } else { 0 });
+                $c1 = $c1.wrapping_add(th);
+                $c2 = $c2.wrapping_add(if $c1 < th { 1 } else { 0 });
+                debug_assert!($c1 >= th || $c2 != 0);
+            };
+        }
+
+        #[allow(unused_macros)]
+        macro_rules! muladd_fast {
+            ($a: expr,$b: expr) => {
+                let a = $a;
+                let b = $b;
+                let t = (a as u128) * (b as u128);
+                let mut th = (t >> 64) as u64;
+                let tl = t as u64;
+                $c0 = $c0.wrapping_add(tl);
+                th = th.wrapping_add(if $c0 < tl { 1 } else { 0 });
+                $c1 = $c1.wrapping_add(th);
+                debug_assert!($c1 >= th);
+            };
+        }
+
+        #[allow(unused_macros)]
+        macro_rules! muladd2 {
+            ($a: expr,$b: expr) => {
+                let a = $a;
+                let b = $b;
+                let t = (a as u128) * (b as u128);
+                let th = (t >> 64) as u64;
+                let tl = t as u64;
+                let mut th2 = th.wrapping_add(th);
+                $c2 = $c2.wrapping_add(if th2 < th { 1 } else { 0 });
+                debug_assert!(th2 >= th || $c2 != 0);
+                let tl2 = tl.wrapping_add(tl);
+                th2 = th2.wrapping_add(if tl2 < tl { 1 } else { 0 });
+                $c0 = $c0.wrapping_add(tl2);
+                th2 = th2.wrapping_add(if $c0 < tl2 { 1 } else { 0 });
+                $c2 = $c2.wrapping_add(if $c0 < tl2 && th2 == 0 { 1 } else { 0 });
+                debug_assert!($c0 >= tl2 || th2 != 0 || $c2 != 0);
+                $c1 = $c1.wrapping_add(th2);
+                $c2 = $c2.wrapping_add(if $c1 < th2 { 1 } else { 0 });
+                debug_assert!($c1 >= th2 || $c2 != 0);
+            };
+        }
+
+        #[allow(unused_macros)]
+        macro_rules! sumadd {
+            ($a: expr) => {
+                let a = $a;
+                $c0 = $c0.wrapping_add(a);
+                let over = if $c0 < a { 1 } else { 0 };
+                $c1 = $c1.wrapping_add(over);
+                $c2 = $c2.wrapping_add(if $c1 < over { 1 } else { 0 });
+            };
+        }
+
+        #[allow(unused_macros)]
+        macro_rules! sumadd_fast {
+            ($a: expr) => {
+                let a = $a;
+                $c0 = $c0.wrapping_add(a);
+                $c1 = $c1.wrapping_add(if $c0 < a { 1 } else { 0 });
+                debug_assert!($c1 != 0 || $c0 >= a);
+                debug_assert!($c2 == 0);
+            };
+        }
+
+        #[allow(unused_macros)]
+        macro_rules! extract {
+            () => {{
+                #[allow(unused_assignments)]
+                {
+                    let n = $c0;
+                    $c0 = $c1;
+                    $c1 = $c2;
+                    $c2 = 0;
+                    n
+                }
+            }};
+        }
+
+        #[allow(unused_macros)]
+        macro_rules! extract_fast {
+            () => {{
+                #[allow(unused_assignments)]
+                {
+                    let n = $c0;
+                    $c0 = $c1;
+                    $c1 = 0;
+                    debug_assert!($c2 == 0);
+                    n
+                }
+            }};
+        }
+    };
+}
+
+impl Scalar {
+    fn reduce_512(&mut self, l: &[u64; 8]) {
+        let (mut c0, mut c1, mut c2): (u64, u64, u64);
+        define_ops!(c0, c1, c2);
+
+        let mut c: u128;
+        let (n0, n1, n2, n3) = (l[4], l[5], l[6], l[7]);
+        let (m0, m1, m2, m3, m4, m5, m6): (u64, u64, u64, u64, u64, u64, u64);
+        let (p0, p1, p2, p3, p4): (u64, u64, u64, u64, u64);
+
+        /* Reduce 512 bits into 385. */
+        /* m[0..6] = l[0..3] + n[0..3] * SECP256K1_N_C. */
+        c0 = l[0];
+        c1 = 0;
+        c2 = 0;
+        muladd_fast!(n0, SECP256K1_N_C_0);
+        m0 = extract_fast!();
+        sumadd_fast!(l[1]);
+        muladd!(n1, SECP256K1_N_C_0);
+        muladd!(n0, SECP256K1_N_C_1);
+        m1 = extract!();
+        sumadd!(l[2]);
+        muladd!(n2, SECP256K1_N_C_0);
+        muladd!(n1, SECP256K1_N_C_1);
+        sumadd!(n0);
+        m2 = extract!();
+        sumadd!(l[3]);
+        muladd!(n3, SECP256K1_N_C_0);
+        muladd!(n2, SECP256K1_N_C_1);
+        sumadd!(n1);
+        m3 = extract!();
+        muladd!(n3, SECP256K1_N_C_1);
+        sumadd!(n2);
+        m4 = extract!();
+        sumadd_fast!(n3);
+        m5 = extract_fast!();
+        debug_assert!(c0 <= 1);
+        m6 = c0;
+
+        /* Reduce 385 bits into 258. */
+        /* p[0..4] = m[0..3] + m[4..6] * SECP256K1_N_C. */
+        c0 = m0;
+        c1 = 0;
+        c2 = 0;
+        muladd_fast!(m4, SECP256K1_N_C_0);
+        p0 = extract_fast!();
+        sumadd_fast!(m1);
+        muladd!(m5, SECP256K1_N_C_0);
+        muladd!(m4, SECP256K1_N_C_1);
+        p1 = extract!();
+        sumadd!(m2);
+        muladd!(m6, SECP256K1_N_C_0);
+        muladd!(m5, SECP256K1_N_C_1);
+        sumadd!(m4);
+        p2 = extract!();
+        sumadd_fast!(m3);
+        muladd_fast!(m6, SECP256K1_N_C_1);
+        sumadd_fast!(m5);
+        p3 = extract_fast!();
+        p4 = c0 + m6;
+        debug_assert!(p4 <= 2);
+
+        /* Reduce 258 bits into 256. */
+        /* r[0..3] = p[0..3] + p[4] * SECP256K1_N_C. */
+        c = p0 as u128 + SECP256K1_N_C_0 as u128 * p4 as u128;
+        self.0[0] = c as u64;
+        c >>= 64;
+        c += p1 as u128 + SECP256K1_N_C_1 as u128 * p4 as u128;
+        self.0[1] = c as u64;
+        c >>= 64;
+        c += p2 as u128 + p4 as u128;
+        self.0[2] = c as u64;
+        c >>= 64;
+        c += p3 as u128;
+        self.0[3] = c as u64;
+        c >>= 64;
+
+        let overflow = self.check_overflow();
+        debug_assert!(c + if overflow { 1 } else { 0 } <= 1);
+        self.reduce(c + if overflow { 1 } else { 0 } == 1);
+    }
+
+    fn mul_512(&self, b: &Scalar, l: &mut [u64; 8]) {
+        let (mut c0, mut c1, mut c2): (u64, u64, u64) = (0, 0, 0);
+        define_ops!(c0, c1, c2);
+
+        /* l[0..7] = a[0..3] * b[0..3]. */
+        muladd_fast!(self.0[0], b.0[0]);
+        l[0] = extract_fast!();
+        muladd!(self.0[0], b.0[1]);
+        muladd!(self.0[1], b.0[0]);
+        l[1] = extract!();
+        muladd!(self.0[0], b.0[2]);
+        muladd!(self.0[1], b.0[1]);
+        muladd!(self.0[2], b.0[0]);
+        l[2] = extract!();
+        muladd!(self.0[0], b.0[3]);
+        muladd!(self.0[1], b.0[2]);
+        muladd!(self.0[2], b.0[1]);
+        muladd!(self.0[3], b.0[0]);
+        l[3] = extract!();
+        muladd!(self.0[1], b.0[3]);
+        muladd!(self.0[2], b.0[2]);
+        muladd!(self.0[3], b.0[1]);
+        l[4] = extract!();
+        muladd!(self.0[2], b.0[3]);
+        muladd!(self.0[3], b.0[2]);
+        l[5] = extract!();
+        muladd_fast!(self.0[3], b.0[3]);
+        l[6] = extract_fast!();
+        debug_assert!(c1 == 0);
+        l[7] = c0;
+    }
+
+    fn sqr_512(&self, l: &mut [u64; 8]) {
+        let (mut c0, mut c1, mut c2): (u64, u64, u64) = (0, 0, 0);
+        define_ops!(c0, c1, c2);
+
+        /* l[0..7] = a[0..3] * a[0..3]. */
+        muladd_fast!(self.0[0], self.0[0]);
+        l[0] = extract_fast!();
+        muladd2!(self.0[0], self.0[1]);
+        l[1] = extract!();
+        muladd2!(self.0[0], self.0[2]);
+        muladd!(self.0[1], self.0[1]);
+        l[2] = extract!();
+        muladd2!(self.0[0], self.0[3]);
+        muladd2!(self.0[1], self.0[2]);
+        l[3] = extract!();
+        muladd2!(self.0[1], self.0[3]);
+        muladd!(self.0[2], self.0[2]);
+        l[4] = extract!();
+        muladd2!(self.0[2], self.0[3]);
+        l[5] = extract!();
+        muladd_fast!(self.0[3], self.0[3]);
+        l[6] = extract_fast!();
+        debug_assert!(c1 == 0);
+        l[7] = c0;
+    }
+
+    pub fn mul_in_place(&mut self, a: &Scalar, b: &Scalar) {
+        let mut l = [0u64; 8];
+        a.mul_512(b, &mut l);
+        self.reduce_512(&l);
+    }
+
+    /// Multiply two scalars and shift the 512-bit product right by `shift`
+    /// bits, at least 256, rounding to nearest.
+    pub fn mul_shift_var(&self, b: &Scalar, shift: usize) -> Scalar {
+        debug_assert!(shift >= 256);
+        let mut l = [0u64; 8];
+        self.mul_512(b, &mut l);
+        let shiftlimbs = shift >> 6;
+        let shiftlow = shift & 0x3F;
+        let shifthigh = 64 - shiftlow;
+        let mut ret = Scalar::default();
+        for i in 0..4 {
+            if shift + 64 * i < 512 {
+                ret.0[i] = l[i + shiftlimbs] >> shiftlow;
+                if shift + 64 * (i + 1) < 512 && shiftlow != 0 {
+                    ret.0[i] |= l[i + 1 + shiftlimbs] << shifthigh;
+                }
+            }
+        }
+        ret.cadd_bit(0, (l[(shift - 1) >> 6] >> ((shift - 1) & 0x3F)) & 1 == 1);
+        ret
+    }
+
+    /// Shift a scalar right by some amount strictly between 0 and 16,
+    /// returning the low bits that were shifted off.
+    pub fn shr_int(&mut self, n: usize) -> u32 {
+        debug_assert!(n > 0);
+        debug_assert!(n < 16);
+        let ret = (self.0[0] & ((1 << n) - 1)) as u32;
+        self.0[0] = (self.0[0] >> n) + (self.0[1] << (64 - n));
+        self.0[1] = (self.0[1] >> n) + (self.0[2] << (64 - n));
+        self.0[2] = (self.0[2] >> n) + (self.0[3] << (64 - n));
+        self.0[3] >>= n;
+        ret
+    }
+
+    pub fn sqr_in_place(&mut self, a: &Scalar) {
+        let mut l = [0u64; 8];
+        a.sqr_512(&mut l);
+        self.reduce_512(&l);
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.0[0] & 1 == 0
+    }
+}
@@ -1,4 +1,3 @@
-use std::ops::{ Add, AddAssign, Neg, Mul, MulAssign };
 
 const SECP256K1_N_0: u32 = 0xD0364141;
 const SECP256K1_N_1: u32 = 0xBFD25E8C;
@@ -25,10 +24,29 @@ const SECP256K1_N_H_6: u32 = 0xFFFFFFFF;
 const SECP256K1_N_H_7: u32 = 0x7FFFFFFF;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
-/// A 256-bit scalar value.
-pub struct Scalar(pub [u32; 8]);
+/// A 256-bit scalar value. The limb layout depends on the backend; use
+/// `limbs` and `from_limbs` to get at the 32-bit limbs.
+pub struct Scalar(pub(crate) [u32; 8]);
 
 impl Scalar {
+    pub const fn new(d7: u32, d6: u32, d5: u32, d4: u32, d3: u32, d2: u32, d1: u32, d0: u32) -> Self {
+        Scalar([d0, d1, d2, d3, d4, d5, d6, d7])
+    }
+
+    /// Build a scalar from eight 32-bit limbs, least significant first,
+    /// reduced modulo the group order.
+    pub fn from_limbs(limbs: [u32; 8]) -> Scalar {
+        let mut ret = Scalar(limbs);
+        let overflow = ret.check_overflow();
+        ret.reduce(overflow);
+        ret
+    }
+
+    /// The eight 32-bit limbs of the scalar, least significant first.
+    pub fn limbs(&self) -> [u32; 8] {
+        self.0
+    }
+
     /// Clear a scalar to prevent the leak of sensitive data.
     pub fn clear(&mut self) {
         self.0 = [0u32; 8];
@@ -330,7 +348,7 @@ impl Scalar {
         yes = yes || ((self.0[2] > SECP256K1_N_H_2) && !no);
         no = no || ((self.0[1] < SECP256K1_N_H_1) && !yes);
         yes = yes || ((self.0[1] > SECP256K1_N_H_1) && !no);
-        yes = yes || ((self.0[0] >= SECP256K1_N_H_0) && !no);
+        yes = yes || ((self.0[0] > SECP256K1_N_H_0) && !no);
         return yes;
     }
 
@@ -812,6 +830,28 @@ impl Scalar {
         self.reduce_512(&l);
     }
 
+    /// Multiply two scalars and shift the 512-bit product right by `shift`
+    /// bits, at least 256, rounding to nearest.
+    pub fn mul_shift_var(&self, b: &Scalar, shift: usize) -> Scalar {
+        debug_assert!(shift >= 256);
+        let mut l = [0u32; 16];
+        self.mul_512(b, &mut l);
+        let shiftlimbs = shift >> 5;
+        let shiftlow = shift & 0x1F;
+        let shifthigh = 32 - shiftlow;
+        let mut ret = Scalar::default();
+        for i in 0..8 {
+            if shift + 32 * i < 512 {
+                ret.0[i] = l[i + shiftlimbs] >> shiftlow;
+                if shift + 32 * (i + 1) < 512 && shiftlow != 0 {
+                    ret.0[i] |= l[i + 1 + shiftlimbs] << shifthigh;
+                }
+            }
+        }
+        ret.cadd_bit(0, (l[(shift - 1) >> 5] >> ((shift - 1) & 0x1F)) & 1 == 1);
+        ret
+    }
+
     /// Shift a scalar right by some amount strictly between 0 and 16,
     /// returning the low bits that were shifted off.
     pub fn shr_int(&mut self, n: usize) -> u32 {
@@ -836,168 +876,6 @@ impl Scalar {
         self.reduce_512(&l);
     }
 
-    pub fn sqr(&self) -> Scalar {
-        let mut ret = Scalar::default();
-        ret.sqr_in_place(self);
-        ret
-    }
-
-    pub fn inv_in_place(&mut self, x: &Scalar) {
-        let u2 = x.sqr();
-        let x2 = &u2 * x;
-        let u5 = &u2 * &x2;
-        let x3 = &u5 * &u2;
-        let u9 = &x3 * &u2;
-        let u11 = &u9 * &u2;
-        let u13 = &u11 * &u2;
-
-        let mut x6 = u13.sqr();
-        x6 = x6.sqr();
-        x6 *= &u11;
-
-        let mut x8 = x6.sqr();
-        x8 = x8.sqr();
-        x8 *= &x2;
-
-        let mut x14 = x8.sqr();
-        for _ in 0..5 {
-            x14 = x14.sqr();
-        }
-        x14 *= &x6;
-
-        let mut x28 = x14.sqr();
-        for _ in 0..13 {
-            x28 = x28.sqr();
-        }
-        x28 *= &x14;
-
-        let mut x56 = x28.sqr();
-        for _ in 0..27 {
-            x56 = x56.sqr();
-        }
-        x56 *= &x28;
-
-        let mut x112 = x56.sqr();
-        for _ in 0..55 {
-            x112 = x112.sqr();
-        }
-        x112 *= &x56;
-
-        let mut x126 = x112.sqr();
-        for _ in 0..13 {
-            x126 = x126.sqr();
-        }
-        x126 *= &x14;
-
-        let mut t = x126;
-        for _ in 0..3 {
-            t = t.sqr();
-        }
-        t *= &u5;
-        for _ in 0..4 {
-            t = t.sqr();
-        }
-        t *= &x3;
-        for _ in 0..4 {
-            t = t.sqr();
-        }
-        t *= &u5;
-        for _ in 0..5 {
-            t = t.sqr();
-        }
-        t *= &u11;
-        for _ in 0..4 {
-            t = t.sqr();
-        }
-        t *= &u11;
-        for _ in 0..4 {
-            t = t.sqr();
-        }
-        t *= &x3;
-        for _ in 0..5 {
-            t = t.sqr();
-        }
-        t *= &x3;
-        for _ in 0..6 {
-            t = t.sqr();
-        }
-        t *= &u13;
-        for _ in 0..4 {
-            t = t.sqr();
-        }
-        t *= &u5;
-        for _ in 0..3 {
-            t = t.sqr();
-        }
-        t *= &x3;
-        for _ in 0..5 {
-            t = t.sqr();
-        }
-        t *= &u9;
-        for _ in 0..6 {
-            t = t.sqr();
-        }
-        t *= &u5;
-        for _ in 0..10 {
-            t = t.sqr();
-        }
-        t *= &x3;
-        for _ in 0..4 {
-            t = t.sqr();
-        }
-        t *= &x3;
-        for _ in 0..9 {
-            t = t.sqr();
-        }
-        t *= &x8;
-        for _ in 0..5 {
-            t = t.sqr();
-        }
-        t *= &u9;
-        for _ in 0..6 {
-            t = t.sqr();
-        }
-        t *= &u11;
-        for _ in 0..4 {
-            t = t.sqr();
-        }
-        t *= &u13;
-        for _ in 0..5 {
-            t = t.sqr();
-        }
-        t *= &x2;
-        for _ in 0..6 {
-            t = t.sqr();
-        }
-        t *= &u13;
-        for _ in 0..10 {
-            t = t.sqr();
-        }
-        t *= &u13;
-        for _ in 0..4 {
-            t = t.sqr();
-        }
-        t *= &u9;
-        for _ in 0..6 {
-            t = t.sqr();
-        }
-        t *= x;
-        for _ in 0..8 {
-            t = t.sqr();
-        }
-        *self = &t * &x6;
-    }
-
-    pub fn inv(&self) -> Scalar {
-        let mut ret = Scalar::default();
-        ret.inv_in_place(self);
-        ret
-    }
-
-    pub fn inv_var(&self) -> Scalar {
-        self.inv()
-    }
-
     pub fn is_even(&self) -> bool {
         return self.0[0] & 1 == 0;
     }
@@ -1008,75 +886,3 @@ impl Default for Scalar {
         Scalar([0u32; 8])
     }
 }
-
-impl Add<Scalar> for Scalar {
-    type Output = Scalar;
-    fn add(self, other: Scalar) -> Scalar {
-        let mut ret = Scalar::default();
-        ret.add_in_place(&self, &other);
-        ret
-    }
-}
-
-impl Neg for Scalar {
-    type Output = Scalar;
-
-    fn neg(self) -> <Self as Neg>::Output {
-        Self::neg(&self)
-    }
-}
-
-impl<'a, 'b> Add<&'a Scalar> for &'b Scalar {
-    type Output = Scalar;
-    fn add(self, other: &'a Scalar) -> Scalar {
-        let mut ret = Scalar::default();
-        ret.add_in_place(self, other);
-        ret
-    }
-}
-
-impl<'a> AddAssign<&'a Scalar> for Scalar {
-    fn add_assign(&mut self, other: &'a Scalar) {
-        let mut ret = Scalar::default();
-        ret.add_in_place(self, other);
-        *self = ret;
-    }
-}
-
-impl AddAssign<Scalar> for Scalar {
-    fn add_assign(&mut self, other: Scalar) {
-        self.add_assign(&other)
-    }
-}
-
-impl Mul<Scalar> for Scalar {
-    type Output = Scalar;
-    fn mul(self, other: Scalar) -> Scalar {
-        let mut ret = Scalar::default();
-        ret.mul_in_place(&self, &other);
-        ret
-    }
-}
-
-impl<'a, 'b> Mul<&'a Scalar> for &'b Scalar {
-    type Output = Scalar;
-    fn mul(self, other: &'a Scalar) -> Scalar {
-        let mut ret = Scalar::default();
-        ret.mul_in_place(self, other);
-        ret
-    }
-}
-
-impl<'a> MulAssign<&'a Scalar> for Scalar {
-    fn mul_assign(&mut self, other: &'a Scalar) {
-        let mut ret = Scalar::default();
-        ret.mul_in_place(self, other);
-        *self = ret;
-    }
-}
-
-impl MulAssign<Scalar> for Scalar {
-    fn mul_assign(&mut self, other: Scalar) {
-        self.mul_assign(&other)
-    }
-}
@@ -3,6 +3,7 @@ use secp256k1::scalar::Scalar;
 use secp256k1::keys::{ PublicKey };
 use secp256k1::error::Error;
 use secp256k1::recovery_id::RecoveryId;
+use secp256k1::der;
 use ecmult::ECMULT_CONTEXT;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -13,6 +14,9 @@ pub struct Signature {
 }
 
 impl Signature {
+    /// Parse a compact `r || s` signature. Values of `r` or `s` at or above
+    /// the group order are silently reduced; use `parse_standard` to reject
+    /// them instead.
     pub fn parse(p: &[u8; 64]) -> Signature {
         let mut r = Scalar::default();
         let mut s = Scalar::default();
@@ -23,6 +27,22 @@ impl Signature {
         Signature { r, s }
     }
 
+    /// Parse a compact `r || s` signature, rejecting it if `r` or `s` is
+    /// not below the group order.
+    pub fn parse_standard(p: &[u8; 64]) -> Result<Signature, Error> {
+        let mut r = Scalar::default();
+        let mut s = Scalar::default();
+
+        let overflowed_r = r.set_b32(array_ref!(p, 0, 32));
+        let overflowed_s = s.set_b32(array_ref!(p, 32, 32));
+
+        if overflowed_r || overflowed_s {
+            return Err(Error::InvalidSignature);
+        }
+
+        Ok(Signature { r, s })
+    }
+
     pub fn serialize(&self) -> [u8; 64] {
         let mut ret = [0u8; 64];
         self.r.fill_b32(array_mut_ref!(ret, 0, 32));
@@ -30,11 +50,58 @@ impl Signature {
         ret
     }
 
+    /// Parse a DER encoded signature, enforcing the strict encoding rules
+    /// of BIP-66. Signatures whose `r` or `s` is negative or not below the
+    /// group order are rejected.
+    pub fn parse_der(p: &[u8]) -> Result<Signature, Error> {
+        let (r, s) = der::parse_signature(p)?;
+        Ok(Signature { r, s })
+    }
+
+    /// Parse a DER-like signature, accepting the malformed encodings found
+    /// in the historical Bitcoin blockchain. This mirrors libsecp256k1's
+    /// `ecdsa_signature_parse_der_lax`: if `r` or `s` overflows, an all-zero
+    /// signature is returned, which never verifies.
+    pub fn parse_der_lax(p: &[u8]) -> Result<Signature, Error> {
+        let (r, s) = der::parse_signature_lax(p)?;
+        Ok(Signature { r, s })
+    }
+
+    /// Serialize the signature in DER format. The result is at most
+    /// 72 bytes long.
+    pub fn serialize_der(&self) -> Vec<u8> {
+        der::serialize_signature(&self.r, &self.s)
+    }
+
+    /// Check whether `s` is in the lower half of the group order, that is
+    /// at most `n/2`, as required by BIP-62 and BIP-146.
+    pub fn is_low_s(&self) -> bool {
+        !self.s.is_high()
+    }
+
+    /// Replace `s` by `n - s` if it is in the upper half of the group
+    /// order. Both forms verify against the same message and key. Returns
+    /// whether the signature was changed.
+    pub fn normalize_s(&mut self) -> bool {
+        if self.s.is_high() {
+            self.s = self.s.neg();
+            true
+        } else {
+            false
+        }
+    }
+
     /// Check signature is a valid message signed by public key.
     pub fn verify(message: &Message, signature: &Signature, pubkey: &PublicKey) -> bool {
         ECMULT_CONTEXT.verify_raw(&signature.r, &signature.s, &pubkey.0, &message.0)
     }
 
+    /// Check signature is a valid message signed by public key, rejecting
+    /// signatures whose `s` is not in low-S form.
+    pub fn verify_strict(message: &Message, signature: &Signature, pubkey: &PublicKey) -> bool {
+        signature.is_low_s() && Signature::verify(message, signature, pubkey)
+    }
+
     /// Recover public key from a signed message.
     pub fn recover(
         message: &Message,
@@ -51,7 +118,8 @@ impl Signature {
 mod tests {
     use secp256k1::rand::thread_rng;
     use super::ECMULT_CONTEXT;
-    use {Message, PublicKey, RecoveryId, SecretKey, SharedSecret, Signature};
+    use hex;
+    use {Error, Message, PublicKey, RecoveryId, SecretKey, SharedSecret, Signature};
     use secp256k1_test::ecdh::SharedSecret as SecpSharedSecret;
     use secp256k1_test::key as SecpKey;
     use secp256k1_test::{
@@ -59,6 +127,104 @@ mod tests {
         RecoveryId as SecpRecoveryId, Secp256k1, Signature as SecpSignature,
     };
 
+    // DER edge cases from the Wycheproof `ecdsa_secp256k1_sha256_bitcoin`
+    // set shipped with libsecp256k1. Each entry is the encoding, whether
+    // `parse_der` accepts it, whether `parse_der_lax` accepts it, and
+    // whether the lax result is a usable (non-zero) signature.
+    const DER_VECTORS: &[(&str, bool, bool, bool)] = &[
+        // tcId 1: Signature malleability
+        ("3046022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc9832365022100900e75ad233fcc908509dbff5922647db37c21f4afd3203ae8dc4ae7794b0f87", true, true, true),
+        // tcId 2: valid
+        ("3045022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", true, true, true),
+        // tcId 3: length of sequence [r, s] uses long form encoding
+        ("308145022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 4: length of sequence [r, s] contains a leading 0
+        ("30820045022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 5: length of sequence [r, s] uses 70 instead of 69
+        ("3046022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 6: length of sequence [r, s] uses 68 instead of 69
+        ("3044022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 14: incorrect length of sequence [r, s]
+        ("30ff022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, false, false),
+        // tcId 15: replaced sequence [r, s] by an indefinite length tag without termination
+        ("3080022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 17: lonely sequence tag
+        ("30", false, false, false),
+        // tcId 18: appending 0's to sequence [r, s]
+        ("3047022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba0000", false, true, true),
+        // tcId 20: appending unused 0's to sequence [r, s]
+        ("3045022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba0000", false, true, true),
+        // tcId 28: truncated length of sequence [r, s]
+        ("3081", false, false, false),
+        // tcId 38: dropping value of sequence [r, s]
+        ("3000", false, false, false),
+        // tcId 40: truncated sequence [r, s]
+        ("3044022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31", false, false, false),
+        // tcId 43: indefinite length
+        ("3080022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba0000", false, true, true),
+        // tcId 58: flipped bit 0 in r
+        ("304300813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236402206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, false, false),
+        // tcId 62: length of r uses long form encoding
+        ("304602812100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 63: length of r contains a leading 0
+        ("30470282002100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 64: length of r uses 34 instead of 33
+        ("3045022200813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, false, false),
+        // tcId 65: length of r uses 32 instead of 33
+        ("3045022000813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, false, false),
+        // tcId 73: incorrect length of r
+        ("304502ff00813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, false, false),
+        // tcId 79: prepending 0's to r
+        ("30470223000000813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 80: appending unused 0's to r
+        ("3047022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc9832365000002206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, false, false),
+        // tcId 85: truncated length of r
+        ("3024028102206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, false, false),
+        // tcId 95: dropping value of r
+        ("3024020002206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, false),
+        // tcId 102: leading ff in r
+        ("30460222ff00813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, false),
+        // tcId 104: replacing r with zero
+        ("302502010002206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", true, true, false),
+        // tcId 109: length of s uses long form encoding
+        ("3046022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc98323650281206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 110: length of s contains a leading 0
+        ("3047022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc9832365028200206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 123: prepending 0's to s
+        ("3047022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc9832365022200006ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 145: leading ff in s
+        ("3046022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc98323650221ff6ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, false),
+        // tcId 148: replaced r by r + n
+        ("3045022101813ef79ccefa9a56f7ba805f0e478583b90deabca4b05c4574e49b5899b964a602206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, false),
+        // tcId 151: replaced r by -r
+        ("30450221ff7ec10863310565a908457fa0f1b87a7b01a0f22a0a9843f64aedc334367cdc9b02206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, false),
+        // tcId 154: replaced r by r + 2**256
+        ("3045022101813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, false),
+        // tcId 159: replaced s by -s
+        ("30440220900e75ad233fcc908509dbff5922647ef8cd450e008a7fff2909ec5aa914ce4602206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba", false, true, true),
+        // tcId 164: Signature with special case values r=0 and s=0
+        ("3006020100020100", true, true, false),
+        // tcId 173: Signature with special case values r=1 and s=1
+        ("3006020101020101", true, true, true),
+        // tcId 174: Signature with special case values r=1 and s=-1
+        ("30060201010201ff", false, true, true),
+        // tcId 189: Signature with special case values r=n and s=1
+        ("3026022100fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141020101", false, true, false),
+        // tcId 197: Signature with special case values r=n - 1 and s=1
+        ("3026022100fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140020101", true, true, true),
+    ];
+
+    // Historical signatures accepted by libsecp256k1's lax parser.
+    const LAX_DER_VECTORS: &[&str] = &[
+        "304402204c2dd8a9b6f8d425fcd8ee9a20ac73b619906a6367eac6cb93e70375225ec0160220356878eff111ff3663d7e6bf08947f94443845e0dcc54961664d922f7660b80c",
+        "304402202ea9d51c7173b1d96d331bd41b3d1b4e78e66148e64ed5992abd6ca66290321c0220628c47517e049b3e41509e9d71e480a0cdc766f8cdec265ef0017711c1b5336f",
+        "3045022100bf8e050c85ffa1c313108ad8c482c4849027937916374617af3f2e9a881861c9022023f65814222cab09d5ec41032ce9c72ca96a5676020736614de7b78a4e55325a",
+        "3046022100839c1fbc5304de944f697c9f4b1d01d1faeba32d751c0f7acb21ac8a0f436a72022100e89bd46bb3a5a62adc679f659b7ce876d83ee297c7a5587b2011c4fcc72eab45",
+        "3046022100eaa5f90483eb20224616775891397d47efa64c68b969db1dacb1c30acdfc50aa022100cf9903bbefb1c8000cf482b0aeeb5af19287af20bd794de11d82716f9bae3db1",
+        "3045022047d512bc85842ac463ca3b669b62666ab8672ee60725b6c06759e476cebdc6c102210083805e93bd941770109bcc797784a71db9e48913f702c56e60b1c3e2ff379a60",
+        "3044022023ee4e95151b2fbbb08a72f35babe02830d14d54bd7ed1320e4751751d1baa4802206235245254f58fd1be6ff19ca291817da76da65c2f6d81d654b5185dd86b8acf",
+    ];
+
     #[test]
     fn test_verify() {
         let secp256k1 = Secp256k1::new();
@@ -346,4 +512,194 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_low_s() {
+        let seckey = SecretKey::parse(&[0x42; 32]).unwrap();
+        let pubkey = PublicKey::from_secret_key(&seckey);
+        let message = Message::parse(&[6u8; 32]);
+
+        let (sig, _) = Message::sign(&message, &seckey).unwrap();
+        assert!(sig.is_low_s());
+        assert!(Signature::verify_strict(&message, &sig, &pubkey));
+
+        let mut high = sig.clone();
+        high.s = high.s.neg();
+        assert!(!high.is_low_s());
+        assert!(Signature::verify(&message, &high, &pubkey));
+        assert!(!Signature::verify_strict(&message, &high, &pubkey));
+
+        assert!(high.normalize_s());
+        assert_eq!(high, sig);
+        assert!(!high.normalize_s());
+        assert_eq!(high, sig);
+        assert!(Signature::verify_strict(&message, &high, &pubkey));
+    }
+
+    #[test]
+    fn test_low_s_boundary() {
+        // s = n/2 is the largest low s, as in libsecp256k1; n/2 + 1 is high.
+        let mut raw = [0u8; 64];
+        raw[31] = 1;
+        raw[32..].copy_from_slice(&hex::decode("7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0").unwrap());
+        let mut half = Signature::parse_standard(&raw).unwrap();
+        assert!(half.is_low_s());
+        assert!(!half.normalize_s());
+        assert_eq!(half.serialize()[..], raw[..]);
+
+        raw[63] += 1;
+        let mut above = Signature::parse_standard(&raw).unwrap();
+        assert!(!above.is_low_s());
+        assert!(above.normalize_s());
+        // n - (n/2 + 1) = n/2.
+        assert_eq!(above, half);
+    }
+
+    #[test]
+    fn test_normalize_s_against_libsecp256k1() {
+        let secp256k1 = Secp256k1::new();
+
+        for _ in 0..16 {
+            let (_, _, _, seckey) = genkey(&secp256k1);
+            let (mut sig, _) = Message::sign(&Message::parse(&[9u8; 32]), &seckey).unwrap();
+            sig.s = sig.s.neg();
+
+            let mut secp_sig = SecpSignature::from_compact(&secp256k1, &sig.serialize()).unwrap();
+            secp_sig.normalize_s(&secp256k1);
+
+            assert!(sig.normalize_s());
+            let compact: &[u8] = &secp_sig.serialize_compact(&secp256k1);
+            let ours: &[u8] = &sig.serialize();
+            assert_eq!(ours, compact);
+        }
+    }
+
+    #[test]
+    fn test_parse_standard() {
+        // The group order n.
+        let order = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36,
+            0x41, 0x41,
+        ];
+        let mut below = order;
+        below[31] -= 1;
+
+        let mut sig = [0u8; 64];
+        sig[..32].copy_from_slice(&below);
+        sig[32..].copy_from_slice(&below);
+        let parsed = Signature::parse_standard(&sig).unwrap();
+        assert_eq!(parsed, Signature::parse(&sig));
+        assert_eq!(&parsed.serialize()[..], &sig[..]);
+
+        let mut high_r = sig;
+        high_r[..32].copy_from_slice(&order);
+        assert_eq!(Signature::parse_standard(&high_r), Err(Error::InvalidSignature));
+        assert!(Signature::parse(&high_r).r.is_zero());
+
+        let mut high_s = sig;
+        high_s[32..].copy_from_slice(&order);
+        assert_eq!(Signature::parse_standard(&high_s), Err(Error::InvalidSignature));
+
+        assert_eq!(Signature::parse_standard(&[0xff; 64]), Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn test_der_vectors() {
+        for &(sig_hex, strict, lax, lax_valid) in DER_VECTORS {
+            let sig = hex::decode(sig_hex).unwrap();
+
+            let parsed = Signature::parse_der(&sig);
+            assert_eq!(parsed.is_ok(), strict, "parse_der {}", sig_hex);
+            if let Ok(parsed) = parsed {
+                assert_eq!(parsed.serialize_der(), sig);
+            }
+
+            let parsed_lax = Signature::parse_der_lax(&sig);
+            assert_eq!(parsed_lax.is_ok(), lax, "parse_der_lax {}", sig_hex);
+            if let Ok(parsed_lax) = parsed_lax {
+                let valid = !parsed_lax.r.is_zero() && !parsed_lax.s.is_zero();
+                assert_eq!(valid, lax_valid, "parse_der_lax {}", sig_hex);
+            }
+        }
+
+        for sig_hex in LAX_DER_VECTORS {
+            let sig = hex::decode(sig_hex).unwrap();
+            let parsed = Signature::parse_der_lax(&sig).unwrap();
+            assert_eq!(Signature::parse_der(&sig).unwrap(), parsed);
+            assert_eq!(parsed.serialize_der(), sig);
+        }
+    }
+
+    #[test]
+    fn test_der_edge_cases() {
+        // Minimal encodings of small values.
+        let sig = Signature::parse_der(&[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02]).unwrap();
+        assert!(sig.r.is_one());
+        assert_eq!(sig.s.b32()[31], 2);
+        assert_eq!(sig.serialize_der(), vec![0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02]);
+
+        // A zero integer is encoded as a single zero byte.
+        let zero = Signature::parse_der(&[0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00]).unwrap();
+        assert!(zero.r.is_zero() && zero.s.is_zero());
+        assert_eq!(zero.serialize_der(), vec![0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00]);
+
+        // Zero-length integers are invalid DER but accepted by the lax parser.
+        let empty = [0x30, 0x04, 0x02, 0x00, 0x02, 0x00];
+        assert!(Signature::parse_der(&empty).is_err());
+        let lax = Signature::parse_der_lax(&empty).unwrap();
+        assert!(lax.r.is_zero() && lax.s.is_zero());
+
+        // Trailing bytes after a lax signature are ignored.
+        let mut trailing = vec![0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02];
+        trailing.push(0x01);
+        assert!(Signature::parse_der(&trailing).is_err());
+        assert_eq!(Signature::parse_der_lax(&trailing).unwrap(), sig);
+
+        // Nothing to parse.
+        assert!(Signature::parse_der(&[]).is_err());
+        assert!(Signature::parse_der_lax(&[]).is_err());
+    }
+
+    #[test]
+    fn test_der_against_libsecp256k1() {
+        let secp256k1 = Secp256k1::new();
+
+        for _ in 0..64 {
+            let (_, secp_privkey, _, seckey) = genkey(&secp256k1);
+            let message_arr = [7u8; 32];
+            let message = Message::parse(&message_arr);
+            let (sig, _) = Message::sign(&message, &seckey).unwrap();
+
+            let secp_message = SecpMessage::from_slice(&message_arr).unwrap();
+            let secp_sig = secp256k1.sign(&secp_message, &secp_privkey).unwrap();
+
+            let der = sig.serialize_der();
+            assert!(der.len() <= 72);
+            assert_eq!(der, secp_sig.serialize_der(&secp256k1));
+            assert_eq!(Signature::parse_der(&der).unwrap(), sig);
+            assert_eq!(Signature::parse_der_lax(&der).unwrap(), sig);
+        }
+
+        for &(sig_hex, _, _, _) in DER_VECTORS {
+            let sig = hex::decode(sig_hex).unwrap();
+
+            // Anything we accept must be accepted by libsecp256k1 with the
+            // same value.
+            if let Ok(parsed) = Signature::parse_der(&sig) {
+                let secp_sig = SecpSignature::from_der(&secp256k1, &sig).unwrap();
+                let compact: &[u8] = &secp_sig.serialize_compact(&secp256k1);
+                let ours: &[u8] = &parsed.serialize();
+                assert_eq!(ours, compact);
+            }
+
+            let lax = Signature::parse_der_lax(&sig);
+            let secp_lax = SecpSignature::from_der_lax(&secp256k1, &sig);
+            assert_eq!(lax.is_ok(), secp_lax.is_ok());
+            if let (Ok(lax), Ok(secp_lax)) = (lax, secp_lax) {
+                let compact: &[u8] = &secp_lax.serialize_compact(&secp256k1);
+                let ours: &[u8] = &lax.serialize();
+                assert_eq!(ours, compact);
+            }
+        }
+    }
 }
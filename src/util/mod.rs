@@ -0,0 +1,59 @@
+//! Scalar and point helpers shared by the `musig` and `frost` modules.
+
+use ecmult::{ECMULT_CONTEXT, ECMULT_GEN_CONTEXT};
+#[cfg(test)]
+use hex;
+use secp256k1::group::{Affine, Jacobian};
+use secp256k1::Scalar;
+
+pub fn scalar_one() -> Scalar {
+    let mut ret = Scalar::default();
+    ret.set_int(1);
+    ret
+}
+
+/// Interpret 32 bytes, typically a hash, as an integer modulo the group
+/// order.
+pub fn scalar_from_b32(b: &[u8; 32]) -> Scalar {
+    let mut ret = Scalar::default();
+    ret.set_b32(b);
+    ret
+}
+
+/// Compute `k.G` in constant time.
+pub fn mul_gen(k: &Scalar) -> Jacobian {
+    let mut ret = Jacobian::default();
+    ECMULT_GEN_CONTEXT.ecmult_gen(&mut ret, k);
+    ret
+}
+
+/// Compute `k.P` in variable time. Only used with public points and scalars.
+pub fn point_mul(p: &Affine, k: &Scalar) -> Jacobian {
+    let mut ret = Jacobian::default();
+    if p.is_infinity() {
+        ret.set_infinity();
+        return ret;
+    }
+    let mut pj = Jacobian::default();
+    pj.set_ge(p);
+    ECMULT_CONTEXT.ecmult(&mut ret, &pj, k, &Scalar::default());
+    ret
+}
+
+/// Convert to affine coordinates with normalized x and y.
+pub fn to_affine(p: &Jacobian) -> Affine {
+    let mut ret = Affine::default();
+    ret.set_gej_var(p);
+    ret.x.normalize_var();
+    ret.y.normalize_var();
+    ret
+}
+
+
+/// Decode a 32-byte hex string from a test vector.
+#[cfg(test)]
+pub fn decode32(h: &str) -> [u8; 32] {
+    let mut ret = [0u8; 32];
+    ret.copy_from_slice(&hex::decode(h).unwrap());
+    ret
+}
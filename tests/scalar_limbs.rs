@@ -0,0 +1,46 @@
+//! `Scalar::limbs` and `Scalar::from_limbs` through the public API. They replace direct access to the limb array,
+//! whose layout depends on the backend.
+extern crate libsecp256k1_rs as secp256k1;
+
+use secp256k1::secp256k1::Scalar;
+
+/// The group order, least significant limb first.
+const N: [u32; 8] = [
+    0xD0364141, 0xBFD25E8C, 0xAF48A03B, 0xBAAEDCE6, 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF,
+];
+
+#[test]
+fn limbs_round_trip() {
+    let limbs = [
+        0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c, 0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
+    ];
+    let s = Scalar::from_limbs(limbs);
+    assert_eq!(s.limbs(), limbs);
+
+    let mut b32 = [0u8; 32];
+    for (i, b) in b32.iter_mut().enumerate() {
+        *b = 31 - i as u8;
+    }
+    assert_eq!(s.b32(), b32);
+
+    let mut t = Scalar::default();
+    t.set_b32(&b32);
+    assert_eq!(t, s);
+}
+
+#[test]
+fn from_limbs_reduces() {
+    assert!(Scalar::from_limbs(N).is_zero());
+
+    let mut above = N;
+    above[0] += 1;
+    let mut one = Scalar::default();
+    one.set_int(1);
+    assert_eq!(Scalar::from_limbs(above), one);
+    assert_eq!(Scalar::from_limbs(above).limbs(), [1, 0, 0, 0, 0, 0, 0, 0]);
+
+    let mut below = N;
+    below[0] -= 1;
+    assert_eq!(Scalar::from_limbs(below).limbs(), below);
+    assert_eq!(Scalar::from_limbs(below), one.neg());
+}